@@ -0,0 +1,163 @@
+use std::path::{Path, PathBuf};
+use tokio::task::JoinHandle;
+
+use super::App;
+use crate::mise::file_watcher::FileWatcher;
+use crate::models::config_layout::ConfigLayout;
+use crate::models::file_watch::WatchSet;
+use crate::models::{AppEvent, MiseTask};
+
+/// Globs for the files that define tasks: the config files and task directories of
+/// `layout`, and the files the tasks come from
+pub fn config_globs(layout: &ConfigLayout, tasks: &[MiseTask]) -> Vec<String> {
+    let mut globs: Vec<String> = layout
+        .config_files
+        .iter()
+        .map(|file| file.display().to_string())
+        .collect();
+    for dir in layout.task_dirs.keys() {
+        // The directory itself too, for includes naming a file and for it being created
+        globs.push(dir.display().to_string());
+        globs.push(format!("{}/**", dir.display()));
+    }
+    for task in tasks {
+        let source = Path::new(&task.source);
+        if task.source.ends_with(".toml") {
+            globs.push(task.source.clone());
+        } else if let Some(dir) = source.parent() {
+            globs.push(format!("{}/*", dir.display()));
+        }
+    }
+    globs.sort();
+    globs.dedup();
+    globs
+}
+
+/// Watch of the files defining the tasks, stopped when dropped
+#[derive(Debug)]
+pub struct ConfigWatch {
+    /// What is watched, to tell whether a new task list needs a new watch
+    globs: Vec<String>,
+    handle: JoinHandle<()>,
+}
+
+impl Drop for ConfigWatch {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
+
+impl App {
+    /// Directory of the config defining `task`, which its relative `sources` and `outputs`
+    /// are taken from
+    pub fn task_config_root(&self, task: &MiseTask) -> PathBuf {
+        let root = self.config_layout.task_config_root(Path::new(&task.source));
+        if root.is_absolute() {
+            return root;
+        }
+        std::env::current_dir().unwrap_or_default().join(root)
+    }
+
+    /// Refresh the task list whenever a file defining the current tasks changes. Kept as it
+    /// is while the tasks come from the same files
+    pub fn watch_config(&mut self) {
+        let globs = config_globs(&self.config_layout, &self.tasks);
+        if self
+            .config_watch
+            .as_ref()
+            .is_some_and(|watch| watch.globs == globs)
+        {
+            return;
+        }
+
+        self.config_watch = None;
+        let watcher =
+            match FileWatcher::new(WatchSet::new(&self.config_layout.project_root, &globs)) {
+                Ok(watcher) => watcher,
+                Err(e) => {
+                    log::warn!("Not refreshing tasks on config changes: {e:#}");
+                    return;
+                }
+            };
+        let event_tx = self.event_tx.clone();
+        let handle = tokio::spawn(async move {
+            let on_change = move |_| event_tx.send(AppEvent::ConfigChanged).is_ok();
+            if let Err(e) = watcher.run(on_change).await {
+                log::warn!("Stopped watching the mise config: {e:#}");
+            }
+        });
+        self.config_watch = Some(ConfigWatch { globs, handle });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use tokio::sync::mpsc;
+
+    #[test]
+    fn test_config_globs() {
+        let tasks = vec![
+            MiseTask::new("build".to_string(), "/repo/mise.toml".to_string()),
+            MiseTask::new("deploy".to_string(), "/repo/scripts/deploy".to_string()),
+            MiseTask::new("lint".to_string(), "/repo/scripts/lint".to_string()),
+        ];
+        let layout = ConfigLayout {
+            project_root: PathBuf::from("/repo"),
+            config_files: vec![
+                PathBuf::from("/home/me/.config/mise/config.toml"),
+                PathBuf::from("/repo/mise.local.toml"),
+                PathBuf::from("/repo/mise.toml"),
+            ],
+            task_dirs: [(PathBuf::from("/repo/.mise/tasks"), PathBuf::from("/repo"))].into(),
+        };
+        let globs = config_globs(&layout, &tasks);
+
+        assert_eq!(
+            globs,
+            vec![
+                "/home/me/.config/mise/config.toml",
+                "/repo/.mise/tasks",
+                "/repo/.mise/tasks/**",
+                "/repo/mise.local.toml",
+                "/repo/mise.toml",
+                "/repo/scripts/*",
+            ]
+        );
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn test_config_change_is_reported() {
+        let dir =
+            std::env::temp_dir().join(format!("mise-sequencer-config-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let config = dir.join("mise.toml");
+        std::fs::write(&config, "").unwrap();
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let mut app = App::new(tx);
+
+        // A config without tasks yet is watched, as is a local config that does not exist
+        let layout = ConfigLayout::new(&dir, vec![config.clone()]);
+        app.handle_event(AppEvent::ConfigLayoutLoaded(layout))
+            .await
+            .unwrap();
+        assert_eq!(app.config_layout.project_root, dir);
+        std::fs::write(dir.join("mise.local.toml"), "").unwrap();
+        let event = tokio::time::timeout(std::time::Duration::from_secs(5), rx.recv())
+            .await
+            .unwrap();
+        assert!(matches!(event, Some(AppEvent::ConfigChanged)));
+        while rx.try_recv().is_ok() {}
+
+        std::fs::write(&config, "[tasks.build]\nrun = 'cargo build'\n").unwrap();
+        let event = tokio::time::timeout(std::time::Duration::from_secs(5), rx.recv())
+            .await
+            .unwrap();
+        assert!(matches!(event, Some(AppEvent::ConfigChanged)));
+
+        app.config_watch = None;
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,157 @@
+use ratatui::crossterm::event::{KeyCode, KeyEvent};
+
+use super::App;
+use crate::models::error_location::find_locations;
+use crate::models::ErrorNavigator;
+
+impl App {
+    /// List the file locations in the output pane, starting at the first one in view
+    pub fn open_error_navigator(&mut self) {
+        let locations = find_locations(&self.task_output);
+        if locations.is_empty() {
+            self.task_output
+                .push_notice("No file locations found in the output");
+            return;
+        }
+
+        let first_visible = self
+            .output_view_lines(self.output_scroll_offset..self.output_scroll_offset + 1)
+            .first()
+            .map_or(0, |(index, _)| *index);
+        let mut navigator = ErrorNavigator::new(locations);
+        navigator.selected = navigator
+            .locations
+            .iter()
+            .position(|found| found.index >= first_visible)
+            .unwrap_or(0);
+        self.error_navigator = Some(navigator);
+        self.show_selected_location();
+    }
+
+    pub fn handle_error_navigator_key(&mut self, key: KeyEvent) {
+        let Some(navigator) = &mut self.error_navigator else {
+            return;
+        };
+        match key.code {
+            KeyCode::Down | KeyCode::Char('j') => {
+                navigator.select_next();
+                self.show_selected_location();
+            }
+            KeyCode::Up | KeyCode::Char('k') => {
+                navigator.select_previous();
+                self.show_selected_location();
+            }
+            KeyCode::Enter => self.open_selected_location(),
+            KeyCode::Esc | KeyCode::Char('q') => self.error_navigator = None,
+            _ => {}
+        }
+    }
+
+    /// Scroll the output pane to the line the selected location was found on
+    fn show_selected_location(&mut self) {
+        if let Some(found) = self
+            .error_navigator
+            .as_ref()
+            .and_then(ErrorNavigator::selected)
+        {
+            self.scroll_output_to_line(found.index);
+        }
+    }
+
+    /// Open the selected location in the editor and close the list
+    pub fn open_selected_location(&mut self) {
+        let Some(found) = self
+            .error_navigator
+            .take()
+            .and_then(|navigator| navigator.selected().cloned())
+        else {
+            return;
+        };
+        let location = found.location;
+        if let Err(e) = self.launch_editor(&location.path, Some(location.line), location.column) {
+            self.task_output.push_notice(format!(
+                "Failed to open {} with '{}': {e}",
+                location.label(),
+                self.editor
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::OutputLine;
+    use ratatui::crossterm::event::KeyModifiers;
+    use tokio::sync::mpsc;
+
+    fn create_test_app() -> App {
+        let (tx, _rx) = mpsc::unbounded_channel();
+        let mut app = App::new(tx);
+        app.show_output_pane = true;
+        app.current_output_visible_height = 2;
+        for i in 0..10 {
+            let line = if i % 4 == 1 {
+                OutputLine::stderr(format!("src/lib.rs:{i}:1: error"))
+            } else {
+                OutputLine::stdout(format!("line {i}"))
+            };
+            app.push_task_output(line);
+        }
+        app
+    }
+
+    fn press(app: &mut App, code: KeyCode) {
+        app.handle_error_navigator_key(KeyEvent::new(code, KeyModifiers::NONE));
+    }
+
+    #[test]
+    fn test_navigator_starts_in_view_and_scrolls_output() {
+        let mut app = create_test_app();
+        app.output_scroll_offset = 3;
+        app.open_error_navigator();
+
+        let navigator = app.error_navigator.as_ref().unwrap();
+        assert_eq!(navigator.locations.len(), 3);
+        assert_eq!(navigator.selected().unwrap().location.line, 5);
+        assert_eq!(app.output_scroll_offset, 4);
+
+        press(&mut app, KeyCode::Down);
+        assert_eq!(app.output_scroll_offset, 8);
+        press(&mut app, KeyCode::Down);
+        assert_eq!(app.output_scroll_offset, 0);
+
+        press(&mut app, KeyCode::Esc);
+        assert!(app.error_navigator.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_open_location_reports_missing_editor() {
+        let mut app = create_test_app();
+        app.editor = "/nonexistent/editor".to_string();
+        app.open_error_navigator();
+        press(&mut app, KeyCode::Enter);
+
+        assert!(app.error_navigator.is_none());
+        assert!(app
+            .task_output
+            .back()
+            .unwrap()
+            .text
+            .starts_with("Failed to open src/lib.rs:1:1 with '/nonexistent/editor'"));
+    }
+
+    #[test]
+    fn test_no_locations() {
+        let (tx, _rx) = mpsc::unbounded_channel();
+        let mut app = App::new(tx);
+        app.push_task_output(OutputLine::stdout("all good"));
+        app.open_error_navigator();
+
+        assert!(app.error_navigator.is_none());
+        assert_eq!(
+            app.task_output.back().unwrap().text,
+            "No file locations found in the output"
+        );
+    }
+}
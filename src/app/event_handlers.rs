@@ -8,7 +8,7 @@ use crate::models::app_event::ScrollDirection;
 use crate::models::{AppEvent, AppState, SequenceEvent};
 use crate::ui::button_layout::{
     get_dialog_button_at_position, ActionButton, ActionButtonLayout, ButtonHoverState, ButtonType,
-    DialogButton, SequenceButtonLayout, StepButtonLayout,
+    DialogButton, SequenceButtonLayout, StepButtonLayout, TabBarLayout,
 };
 use crate::ui::constants::*;
 
@@ -19,6 +19,7 @@ impl App {
         match event {
             AppEvent::Quit => self.should_quit = true,
             AppEvent::KeyPress(key) => self.handle_key(key).await?,
+            AppEvent::Paste(text) => self.handle_paste(&text),
             AppEvent::MouseClick { button, row, col } => {
                 self.handle_mouse_click(button, row, col).await?
             }
@@ -29,59 +30,26 @@ impl App {
             } => self.handle_mouse_scroll(direction).await?,
             AppEvent::MouseMove { row, col } => self.handle_mouse_move(row, col).await?,
             AppEvent::TasksRefreshed(tasks) => {
-                self.tasks = tasks;
+                self.set_tasks(tasks);
+                self.task_arg_specs.clear();
                 self.last_updated = Instant::now();
+                self.refresh_freshness();
             }
-            AppEvent::TaskOutput(output) => {
-                self.task_output.push_back(output);
-                // Keep only the last 100 lines
-                while self.task_output.len() > 100 {
-                    self.task_output.pop_front();
-                    // Adjust scroll offset to maintain position when lines are removed
-                    if self.output_scroll_offset > 0 {
-                        self.output_scroll_offset = self.output_scroll_offset.saturating_sub(1);
-                    }
-                }
-
-                // Auto-scroll to bottom if follow mode is enabled and we're at or near the bottom
-                if self.show_output_pane
-                    && self.task_running
-                    && self.output_follow_mode
-                    && self.current_output_visible_height > 0
-                {
-                    let visible_height = self.current_output_visible_height;
-                    let total_lines = self.task_output.len();
-
-                    // If we're within a few lines of the bottom, keep following
-                    if total_lines > visible_height {
-                        let max_scroll = total_lines - visible_height;
-                        if self.output_scroll_offset >= max_scroll.saturating_sub(3) {
-                            self.output_scroll_offset = max_scroll;
-                        }
-                    }
-                }
-            }
-            AppEvent::TaskCompleted => {
-                self.task_running = false;
-                self.running_task_name = None;
-                self.running_task_handle = None;
-            }
-            AppEvent::TaskCancelled => {
-                // Only update state if we're not already cancelled
-                if self.task_running || self.running_task_handle.is_some() {
-                    self.task_running = false;
-                    self.running_task_name = None;
-                    self.running_task_handle = None;
-                    self.task_output
-                        .push_back("Task cancelled by user".to_string());
-                }
+            AppEvent::ConfigLayoutLoaded(layout) => {
+                self.config_layout = layout;
+                self.watch_config();
             }
-            AppEvent::Tick => {
-                // Handle periodic updates if needed
+            AppEvent::ConfigChanged => self.refresh_tasks().await?,
+            AppEvent::FreshnessRefreshed(freshness) => {
+                self.task_freshness = freshness;
+                self.freshness_refreshing = false;
             }
-            AppEvent::Sequence(sequence_event) => {
-                self.handle_sequence_event(sequence_event).await?;
+            AppEvent::TaskOutput(output) => self.push_task_output(output),
+            AppEvent::ForTab(tab_id, event) => self.handle_tab_event(tab_id, *event).await?,
+            AppEvent::TaskCompleted(_) | AppEvent::Sequence(_) | AppEvent::SourcesChanged(_) => {
+                self.handle_tab_scoped_event(event).await?
             }
+            AppEvent::Tick => self.refresh_freshness_if_due(),
             AppEvent::DeleteTask(task_name) => {
                 // Store the task name for confirmation
                 self.pending_delete_task = Some(task_name);
@@ -90,6 +58,23 @@ impl App {
         Ok(())
     }
 
+    /// Events that act on the state of a single tab
+    pub async fn handle_tab_scoped_event(&mut self, event: AppEvent) -> Result<()> {
+        match event {
+            AppEvent::TaskCompleted(result) => {
+                self.record_task_results(std::slice::from_ref(&result));
+                self.notify_run_finished(result.success(), result.summary());
+                self.finish_run();
+            }
+            AppEvent::Sequence(sequence_event) => {
+                self.handle_sequence_event(sequence_event).await?;
+            }
+            AppEvent::SourcesChanged(files) => self.handle_sources_changed(files).await?,
+            _ => {}
+        }
+        Ok(())
+    }
+
     pub async fn handle_key(&mut self, key_event: KeyEvent) -> Result<()> {
         let KeyEvent {
             code: key,
@@ -97,6 +82,12 @@ impl App {
             ..
         } = key_event;
 
+        // Keys go to the running task while it has focus, up to the escape chord
+        if self.is_input_focused() {
+            self.handle_task_input_key(key_event);
+            return Ok(());
+        }
+
         // Handle delete confirmation first
         if let Some(ref task_name) = self.pending_delete_task.clone() {
             match key {
@@ -109,19 +100,19 @@ impl App {
                     match self.client.delete_task(&task_name).await {
                         Ok(()) => {
                             // Remove from sequence state if it exists
-                            self.sequence_state.remove_task(&task_name);
+                            self.remove_task_from_sequences(&task_name);
 
                             // Refresh task list
                             self.refresh_tasks().await?;
 
                             // Add success message to output
-                            self.task_output.push_back(format!("Task '{task_name}' deleted successfully. Remember to keep your mise tasks under version control."));
+                            self.task_output.push_notice(format!("Task '{task_name}' deleted successfully. Remember to keep your mise tasks under version control."));
                             self.show_output_pane = true;
                         }
                         Err(e) => {
                             // Add error message to output
                             self.task_output
-                                .push_back(format!("Failed to delete task '{task_name}': {e}"));
+                                .push_notice(format!("Failed to delete task '{task_name}': {e}"));
                             self.show_output_pane = true;
                         }
                     }
@@ -143,6 +134,43 @@ impl App {
             }
         }
 
+        // Text prompts take every key until they are submitted or cancelled
+        if let Some(ref mut prompt) = self.prompt {
+            match key {
+                KeyCode::Enter => self.submit_prompt().await?,
+                KeyCode::Esc => self.cancel_prompt(),
+                _ => {
+                    let crossterm_event = ratatui::crossterm::event::Event::Key(key_event);
+                    prompt.input.handle_event(&crossterm_event);
+                    // The output is searched as the pattern is typed
+                    if self.is_searching_output() {
+                        self.update_output_search();
+                    }
+                }
+            }
+            return Ok(());
+        }
+
+        if self.args_form.is_some() {
+            self.handle_args_form_key(key_event).await?;
+            return Ok(());
+        }
+
+        if self.error_navigator.is_some() {
+            self.handle_error_navigator_key(key_event);
+            return Ok(());
+        }
+
+        // Confirm deleting a saved sequence with y, any other key cancels
+        if self.pending_delete_sequence.is_some() {
+            if matches!(key, KeyCode::Char('y') | KeyCode::Char('Y')) {
+                self.confirm_delete_saved_sequence();
+            } else {
+                self.pending_delete_sequence = None;
+            }
+            return Ok(());
+        }
+
         // Handle rename mode first
         if let AppState::Renaming(_) = &self.state {
             match key {
@@ -170,17 +198,12 @@ impl App {
         match (&self.state, key) {
             (_, KeyCode::Char('q')) => self.should_quit = true,
             (_, KeyCode::Char('r')) => self.refresh_tasks().await?,
-            // Handle Ctrl+C to cancel running tasks
+            // Cancel the running task or sequence and every process it started
             (_, KeyCode::Char('c')) if self.task_running => {
-                // First mark as cancelled to prevent new tasks from starting
-                self.task_running = false;
-
-                if let Some(handle) = self.running_task_handle.take() {
-                    handle.abort();
-                    // Send cancellation event after handle is safely removed
-                    if self.event_tx.send(AppEvent::TaskCancelled).is_err() {
-                        eprintln!("Warning: Failed to send TaskCancelled event");
-                    }
+                if self.sequence_state.is_running {
+                    self.stop_sequence().await?;
+                } else {
+                    self.stop_current_task().await?;
                 }
             }
 
@@ -188,6 +211,33 @@ impl App {
             (AppState::Detail(_), KeyCode::Char('x')) => self.run_selected_task().await?,
 
             (AppState::Running(_), KeyCode::Esc | KeyCode::Char('b')) => self.back_to_list(),
+            (AppState::Running(_), KeyCode::Char('I')) => self.focus_task_input(),
+
+            // Saved sequences list
+            (AppState::Sequences, KeyCode::Down | KeyCode::Char('j')) => {
+                self.select_next_saved_sequence()
+            }
+            (AppState::Sequences, KeyCode::Up | KeyCode::Char('k')) => {
+                self.select_previous_saved_sequence()
+            }
+            (AppState::Sequences, KeyCode::Enter) => self.load_selected_saved_sequence(),
+            (AppState::Sequences, KeyCode::Char('R')) => self.start_rename_saved_sequence(),
+            (AppState::Sequences, KeyCode::Char('D')) => self.request_delete_saved_sequence(),
+            (AppState::Sequences, KeyCode::Esc | KeyCode::Char('b')) => {
+                self.close_saved_sequences()
+            }
+
+            // Run history
+            (AppState::History, KeyCode::Down | KeyCode::Char('j')) => {
+                self.select_next_history_run()
+            }
+            (AppState::History, KeyCode::Up | KeyCode::Char('k')) => {
+                self.select_previous_history_run()
+            }
+            (AppState::History, KeyCode::Enter) => self.open_selected_run_output(),
+            (AppState::History, KeyCode::Char('x')) => self.rerun_selected_run().await?,
+            (AppState::History, KeyCode::Char('/')) => self.start_filter_history(),
+            (AppState::History, KeyCode::Esc | KeyCode::Char('b')) => self.close_run_history(),
 
             // Output scrolling controls when output pane is visible (must come before regular navigation)
             (AppState::SequenceBuilder, KeyCode::Up)
@@ -215,6 +265,27 @@ impl App {
             (AppState::SequenceBuilder, KeyCode::Char('F')) if self.show_output_pane => {
                 self.toggle_output_follow_mode();
             }
+            (AppState::SequenceBuilder, KeyCode::Char('L')) if self.show_output_pane => {
+                self.open_error_navigator();
+            }
+            (AppState::SequenceBuilder, KeyCode::Char('E')) if self.show_output_pane => {
+                self.toggle_stderr_only();
+            }
+            (AppState::SequenceBuilder, KeyCode::Char('C')) if self.show_output_pane => {
+                self.toggle_output_timestamps();
+            }
+            (AppState::SequenceBuilder, KeyCode::Char('/')) if self.show_output_pane => {
+                self.start_output_search();
+            }
+            (AppState::SequenceBuilder, KeyCode::Char('n')) if self.output_search.is_some() => {
+                self.jump_to_search_match(true);
+            }
+            (AppState::SequenceBuilder, KeyCode::Char('N')) if self.output_search.is_some() => {
+                self.jump_to_search_match(false);
+            }
+            (AppState::SequenceBuilder, KeyCode::Esc) if self.output_search.is_some() => {
+                self.clear_output_search();
+            }
 
             // Sequence Builder controls
             (AppState::SequenceBuilder, KeyCode::Down | KeyCode::Char('j')) => {
@@ -235,14 +306,37 @@ impl App {
                 self.scroll_up(visible_height);
                 // Don't change selected task - let it go out of view if needed
             }
-            (AppState::SequenceBuilder, KeyCode::Char('1')) => {
-                self.toggle_current_task_step(0).await?
+            (AppState::SequenceBuilder, KeyCode::Char(digit @ '1'..='9')) => {
+                let step = digit as usize - '1' as usize;
+                if step < self.sequence_state.num_steps {
+                    self.selected_step = step;
+                    self.ensure_selected_step_visible();
+                    self.toggle_current_task_step(step).await?
+                }
+            }
+            (AppState::SequenceBuilder, KeyCode::Char(' ')) => {
+                self.toggle_current_task_step(self.selected_step).await?
             }
-            (AppState::SequenceBuilder, KeyCode::Char('2')) => {
-                self.toggle_current_task_step(1).await?
+            (AppState::SequenceBuilder, KeyCode::Left) => self.select_previous_step(),
+            (AppState::SequenceBuilder, KeyCode::Right) => self.select_next_step(),
+            (AppState::SequenceBuilder, KeyCode::Char('+')) => {
+                self.handle_sequence_event(SequenceEvent::AddStep).await?
             }
-            (AppState::SequenceBuilder, KeyCode::Char('3')) => {
-                self.toggle_current_task_step(2).await?
+            (AppState::SequenceBuilder, KeyCode::Char('i')) => {
+                self.handle_sequence_event(SequenceEvent::InsertStep(self.selected_step))
+                    .await?
+            }
+            (AppState::SequenceBuilder, KeyCode::Char('p')) => {
+                self.handle_sequence_event(SequenceEvent::CycleStepPolicy(self.selected_step))
+                    .await?
+            }
+            (AppState::SequenceBuilder, KeyCode::Char('f')) => {
+                self.handle_sequence_event(SequenceEvent::ToggleOnlyStale)
+                    .await?
+            }
+            (AppState::SequenceBuilder, KeyCode::Char('-')) => {
+                self.handle_sequence_event(SequenceEvent::RemoveStep(self.selected_step))
+                    .await?
             }
             (AppState::SequenceBuilder, KeyCode::Enter) => {
                 let _ = self
@@ -264,7 +358,26 @@ impl App {
                     .event_tx
                     .send(AppEvent::Sequence(SequenceEvent::AddAsTask));
             }
+            (AppState::SequenceBuilder, KeyCode::Char('w'))
+                if modifiers.contains(KeyModifiers::CONTROL) =>
+            {
+                self.toggle_sequence_watch()
+            }
+            (AppState::SequenceBuilder, KeyCode::Char('W')) => self.toggle_task_watch(),
+            (AppState::SequenceBuilder, KeyCode::Char('t')) => self.open_new_tab(),
+            (AppState::SequenceBuilder, KeyCode::Char('w')) => self.close_active_tab(),
+            (AppState::SequenceBuilder, KeyCode::Char(']')) => self.next_tab(),
+            (AppState::SequenceBuilder, KeyCode::Char('[')) => self.previous_tab(),
+            (AppState::SequenceBuilder, KeyCode::Char('s')) => self.start_save_sequence(),
+            (AppState::SequenceBuilder, KeyCode::Char('o')) => self.open_saved_sequences(),
+            (AppState::SequenceBuilder, KeyCode::Char('H')) => self.open_run_history(),
+            (AppState::SequenceBuilder, KeyCode::Char('T')) => self.toggle_pty_mode(),
+            (AppState::SequenceBuilder, KeyCode::Char('I')) => self.focus_task_input(),
+            (AppState::SequenceBuilder, KeyCode::Char('O')) => {
+                self.open_current_task_in_builder().await?
+            }
             (AppState::SequenceBuilder, KeyCode::Char('x')) => self.run_current_task().await?,
+            (AppState::SequenceBuilder, KeyCode::Char('A')) => self.edit_step_args().await?,
             (AppState::SequenceBuilder, KeyCode::Char('e')) => self.edit_current_task().await?,
             (AppState::SequenceBuilder, KeyCode::Char('D')) => {
                 if let Some(task) = self.tasks.get(self.selected_task) {
@@ -279,6 +392,7 @@ impl App {
                     self.task_output.clear();
                     self.task_output_rx = None;
                     self.output_scroll_offset = 0;
+                    self.clear_output_search();
                 } else {
                     self.state = AppState::SequenceBuilder;
                 }
@@ -307,6 +421,8 @@ impl App {
                     ScrollDirection::Down => {
                         self.scroll_down(3, visible_height); // Scroll 3 lines at a time
                     }
+                    ScrollDirection::Left => self.scroll_steps_left(1),
+                    ScrollDirection::Right => self.scroll_steps_right(1),
                 }
             }
             _ => {
@@ -322,8 +438,12 @@ impl App {
         row: u16,
         col: u16,
     ) -> Result<()> {
-        // Only handle left mouse button clicks
-        if button != MouseButton::Left {
+        // Only handle left mouse button clicks, and none while a dialog is open
+        if button != MouseButton::Left
+            || self.prompt.is_some()
+            || self.args_form.is_some()
+            || self.error_navigator.is_some()
+        {
             return Ok(());
         }
 
@@ -341,36 +461,36 @@ impl App {
     async fn handle_sequence_builder_click(&mut self, row: u16, col: u16) -> Result<()> {
         // Handle dialog button clicks first if there's a pending delete
         if self.pending_delete_task.is_some() {
-            if let Some(dialog_area) = self.delete_dialog_area {
-                if let Some(button) = get_dialog_button_at_position(dialog_area, row, col) {
-                    match button {
-                        DialogButton::Delete => {
-                            // Trigger delete confirmation (same as pressing Y)
-                            if let Some(task_name) = self.pending_delete_task.take() {
-                                match self.client.delete_task(&task_name).await {
-                                    Ok(()) => {
-                                        self.sequence_state.remove_task(&task_name);
-                                        self.refresh_tasks().await?;
-                                        self.task_output.push_back(format!("Task '{task_name}' deleted successfully. Remember to keep your mise tasks under version control."));
-                                        self.show_output_pane = true;
-                                    }
-                                    Err(e) => {
-                                        self.task_output.push_back(format!(
-                                            "Failed to delete task '{task_name}': {e}"
-                                        ));
-                                        self.show_output_pane = true;
-                                    }
+            if let Some(dialog_area) = self.delete_dialog_area
+                && let Some(button) = get_dialog_button_at_position(dialog_area, row, col)
+            {
+                match button {
+                    DialogButton::Delete => {
+                        // Trigger delete confirmation (same as pressing Y)
+                        if let Some(task_name) = self.pending_delete_task.take() {
+                            match self.client.delete_task(&task_name).await {
+                                Ok(()) => {
+                                    self.remove_task_from_sequences(&task_name);
+                                    self.refresh_tasks().await?;
+                                    self.task_output.push_notice(format!("Task '{task_name}' deleted successfully. Remember to keep your mise tasks under version control."));
+                                    self.show_output_pane = true;
+                                }
+                                Err(e) => {
+                                    self.task_output.push_notice(format!(
+                                        "Failed to delete task '{task_name}': {e}"
+                                    ));
+                                    self.show_output_pane = true;
                                 }
                             }
-                            self.delete_dialog_area = None;
-                            return Ok(());
-                        }
-                        DialogButton::Cancel => {
-                            // Cancel delete (same as pressing N/ESC)
-                            self.pending_delete_task = None;
-                            self.delete_dialog_area = None;
-                            return Ok(());
                         }
+                        self.delete_dialog_area = None;
+                        return Ok(());
+                    }
+                    DialogButton::Cancel => {
+                        // Cancel delete (same as pressing N/ESC)
+                        self.pending_delete_task = None;
+                        self.delete_dialog_area = None;
+                        return Ok(());
                     }
                 }
             }
@@ -378,6 +498,19 @@ impl App {
             return Ok(());
         }
 
+        // Clicking a tab switches to it
+        if let Some(tab_bar_area) = self.tab_bar_area
+            && row == tab_bar_area.y
+        {
+            if col >= tab_bar_area.x
+                && let Some(index) =
+                    TabBarLayout::new(&self.tab_titles()).get_tab_at_position(col - tab_bar_area.x)
+            {
+                self.switch_to_tab(index);
+            }
+            return Ok(());
+        }
+
         // Get the stored table layout for accurate column detection
         let Some(table_layout) = &self.table_layout else {
             return Ok(());
@@ -394,108 +527,109 @@ impl App {
                 // Update selected task
                 self.selected_task = actual_task_index;
 
-                // Use the calculated column rectangles for accurate hit detection
-                let num_steps = 3;
-
                 // Column 0: Task name
-                // Columns 1-3: Step columns
-                // Column 4: Actions column
-
-                // Check step columns (1, 2, 3)
-                for step in 0..num_steps {
-                    let column_index = step + 1; // Steps start at column 1
-                    if column_index < table_layout.column_rects.len() {
-                        let column_rect = table_layout.column_rects[column_index];
-                        if col >= column_rect.x && col < column_rect.x + column_rect.width {
-                            // Check if click is within the step button area (7 characters wide, centered in 8-char column)
-                            let relative_col = col - column_rect.x;
-                            if relative_col <= 7 {
-                                self.toggle_current_task_step(step).await?;
-                                return Ok(());
-                            }
-                        }
+                // Columns 1..=visible_steps: Step columns (scrolled by first_step)
+                // Last column: Actions column
+
+                // Check step columns using the stored layout for accurate hit detection
+                if let Some((step, column_rect)) = table_layout.step_at_column(col) {
+                    let step_layout =
+                        StepButtonLayout::new(&column_rect, self.sequence_state.num_steps);
+                    let relative_col = col - column_rect.x;
+                    if step_layout
+                        .get_step_button_at_position(step, relative_col)
+                        .is_some()
+                    {
+                        self.selected_step = step;
+                        self.toggle_current_task_step(step).await?;
+                        return Ok(());
                     }
                 }
 
                 // Check actions column (last column)
-                if let Some(actions_rect) = table_layout.column_rects.last() {
-                    if col >= actions_rect.x && col < actions_rect.x + actions_rect.width {
-                        // Check if this task is being renamed to use the correct button layout
-                        let task_name = &self.tasks[actual_task_index].name;
-                        let is_task_being_renamed = matches!(&self.state, AppState::Renaming(renaming_task) if renaming_task == task_name);
-                        let action_layout =
-                            ActionButtonLayout::new_with_mode(actions_rect, is_task_being_renamed);
-                        let relative_col = col - actions_rect.x;
+                if let Some(actions_rect) = table_layout.column_rects.last()
+                    && col >= actions_rect.x
+                    && col < actions_rect.x + actions_rect.width
+                {
+                    // Check if this task is being renamed to use the correct button layout
+                    let task_name = &self.tasks[actual_task_index].name;
+                    let is_task_being_renamed = matches!(&self.state, AppState::Renaming(renaming_task) if renaming_task == task_name);
+                    let action_layout =
+                        ActionButtonLayout::new_with_mode(actions_rect, is_task_being_renamed);
+                    let relative_col = col - actions_rect.x;
 
-                        if let Some(button) = action_layout.get_button_at_position(relative_col) {
-                            match button {
-                                ActionButton::Run => {
-                                    if let Some(task) = self.tasks.get(actual_task_index) {
-                                        if self.is_task_running(&task.name) {
-                                            self.stop_current_task().await?;
-                                        } else if !self.is_any_task_running() {
-                                            // Set selected task to the clicked task before running
-                                            self.selected_task = actual_task_index;
-                                            self.run_current_task().await?;
-                                        }
-                                    }
-                                }
-                                ActionButton::Cat => self.show_current_task_content().await?,
-                                ActionButton::Edit => self.edit_current_task().await?,
-                                ActionButton::Rename => self.start_rename_task().await?,
-                                ActionButton::Delete => {
-                                    if let Some(task) = self.tasks.get(self.selected_task) {
-                                        let _ = self
-                                            .event_tx
-                                            .send(AppEvent::DeleteTask(task.name.clone()));
+                    if let Some(button) = action_layout.get_button_at_position(relative_col) {
+                        match button {
+                            ActionButton::Run => {
+                                if let Some(task) = self.tasks.get(actual_task_index) {
+                                    if self.is_task_running(&task.name) {
+                                        self.stop_current_task().await?;
+                                    } else if !self.is_any_task_running() {
+                                        // Set selected task to the clicked task before running
+                                        self.selected_task = actual_task_index;
+                                        self.run_current_task().await?;
                                     }
                                 }
-                                ActionButton::Save => {
-                                    // Only available in rename mode
-                                    self.save_rename().await?;
-                                }
-                                ActionButton::Cancel => {
-                                    // Only available in rename mode
-                                    self.cancel_rename();
+                            }
+                            ActionButton::Cat => self.show_current_task_content().await?,
+                            ActionButton::Edit => self.edit_current_task().await?,
+                            ActionButton::Rename => self.start_rename_task().await?,
+                            ActionButton::Delete => {
+                                if let Some(task) = self.tasks.get(self.selected_task) {
+                                    let _ =
+                                        self.event_tx.send(AppEvent::DeleteTask(task.name.clone()));
                                 }
                             }
+                            ActionButton::Save => {
+                                // Only available in rename mode
+                                self.save_rename().await?;
+                            }
+                            ActionButton::Cancel => {
+                                // Only available in rename mode
+                                self.cancel_rename();
+                            }
                         }
                     }
                 }
             }
         }
+        // Clicking a step header selects that step column
+        else if row == table_start_row + 1 {
+            if let Some((step, _)) = table_layout.step_at_column(col) {
+                self.selected_step = step;
+            }
+        }
         // Check for sequence control buttons in the title area
-        else if row == table_start_row {
-            if let Some((controls_start_col, controls_width)) =
+        else if row == table_start_row
+            && let Some((controls_start_col, controls_width)) =
                 self.calculate_sequence_controls_position(table_layout)
-            {
-                if col >= controls_start_col && col < controls_start_col + controls_width as u16 {
-                    let relative_col = col - controls_start_col;
-                    let sequence_layout = SequenceButtonLayout::new(0);
-
-                    if let Some(button) = sequence_layout.get_button_at_position(relative_col) {
-                        match button {
-                            crate::ui::button_layout::SequenceButton::RunSequence => {
-                                if self.sequence_state.is_running {
-                                    self.stop_sequence().await?;
-                                } else {
-                                    let _ = self
-                                        .event_tx
-                                        .send(AppEvent::Sequence(SequenceEvent::RunSequence));
-                                }
-                            }
-                            crate::ui::button_layout::SequenceButton::AddAsTask => {
-                                let _ = self
-                                    .event_tx
-                                    .send(AppEvent::Sequence(SequenceEvent::AddAsTask));
-                            }
-                            crate::ui::button_layout::SequenceButton::Clear => {
-                                let _ = self
-                                    .event_tx
-                                    .send(AppEvent::Sequence(SequenceEvent::ClearSequence));
-                            }
+            && col >= controls_start_col
+            && col < controls_start_col + controls_width as u16
+        {
+            let relative_col = col - controls_start_col;
+            let sequence_layout = SequenceButtonLayout::new(0);
+
+            if let Some(button) = sequence_layout.get_button_at_position(relative_col) {
+                match button {
+                    crate::ui::button_layout::SequenceButton::RunSequence => {
+                        if self.sequence_state.is_running {
+                            self.stop_sequence().await?;
+                        } else {
+                            let _ = self
+                                .event_tx
+                                .send(AppEvent::Sequence(SequenceEvent::RunSequence));
                         }
                     }
+                    crate::ui::button_layout::SequenceButton::AddAsTask => {
+                        let _ = self
+                            .event_tx
+                            .send(AppEvent::Sequence(SequenceEvent::AddAsTask));
+                    }
+                    crate::ui::button_layout::SequenceButton::Clear => {
+                        let _ = self
+                            .event_tx
+                            .send(AppEvent::Sequence(SequenceEvent::ClearSequence));
+                    }
                 }
             }
         }
@@ -520,11 +654,10 @@ impl App {
 
         // Handle dialog button hover first if there's a pending delete
         if self.pending_delete_task.is_some() {
-            if let Some(dialog_area) = self.delete_dialog_area {
-                if let Some(button) = get_dialog_button_at_position(dialog_area, row, col) {
-                    new_hover_state =
-                        Some(ButtonHoverState::new(ButtonType::Dialog(button), row, col));
-                }
+            if let Some(dialog_area) = self.delete_dialog_area
+                && let Some(button) = get_dialog_button_at_position(dialog_area, row, col)
+            {
+                new_hover_state = Some(ButtonHoverState::new(ButtonType::Dialog(button), row, col));
             }
             // Only update hover state if it actually changed
             if self.button_hover_state != new_hover_state {
@@ -556,26 +689,26 @@ impl App {
                     // Only process hover if this is the task being renamed
                     if task_name == renaming_task {
                         // Check actions column (last column) for Save/Cancel buttons
-                        if let Some(actions_rect) = table_layout.column_rects.last() {
-                            if col >= actions_rect.x && col < actions_rect.x + actions_rect.width {
-                                let action_layout =
-                                    ActionButtonLayout::new_with_mode(actions_rect, true); // Always rename mode
-                                let relative_col = col - actions_rect.x;
-
-                                if let Some(button) =
-                                    action_layout.get_button_at_position(relative_col)
-                                {
-                                    // Only allow Save/Cancel buttons
-                                    if matches!(button, ActionButton::Save | ActionButton::Cancel) {
-                                        new_hover_state = Some(ButtonHoverState::new(
-                                            ButtonType::Action {
-                                                button,
-                                                task_index: actual_task_index,
-                                            },
-                                            row,
-                                            col,
-                                        ));
-                                    }
+                        if let Some(actions_rect) = table_layout.column_rects.last()
+                            && col >= actions_rect.x
+                            && col < actions_rect.x + actions_rect.width
+                        {
+                            let action_layout =
+                                ActionButtonLayout::new_with_mode(actions_rect, true); // Always rename mode
+                            let relative_col = col - actions_rect.x;
+
+                            if let Some(button) = action_layout.get_button_at_position(relative_col)
+                            {
+                                // Only allow Save/Cancel buttons
+                                if matches!(button, ActionButton::Save | ActionButton::Cancel) {
+                                    new_hover_state = Some(ButtonHoverState::new(
+                                        ButtonType::Action {
+                                            button,
+                                            task_index: actual_task_index,
+                                        },
+                                        row,
+                                        col,
+                                    ));
                                 }
                             }
                         }
@@ -590,30 +723,29 @@ impl App {
             if row == table_start_row {
                 if let Some((controls_start_col, controls_width)) =
                     self.calculate_sequence_controls_position(table_layout)
+                    && col >= controls_start_col
+                    && col < controls_start_col + controls_width as u16
                 {
-                    if col >= controls_start_col && col < controls_start_col + controls_width as u16
-                    {
-                        let relative_col = col - controls_start_col;
-                        let sequence_layout = SequenceButtonLayout::new(0);
-
-                        // Try the current position and the position offset by 1 to handle coordinate mismatches
-                        let button = sequence_layout
-                            .get_button_at_position(relative_col)
-                            .or_else(|| {
-                                if relative_col > 0 {
-                                    sequence_layout.get_button_at_position(relative_col - 1)
-                                } else {
-                                    None
-                                }
-                            });
+                    let relative_col = col - controls_start_col;
+                    let sequence_layout = SequenceButtonLayout::new(0);
 
-                        if let Some(button) = button {
-                            new_hover_state = Some(ButtonHoverState::new(
-                                ButtonType::Sequence(button),
-                                row,
-                                col,
-                            ));
-                        }
+                    // Try the current position and the position offset by 1 to handle coordinate mismatches
+                    let button = sequence_layout
+                        .get_button_at_position(relative_col)
+                        .or_else(|| {
+                            if relative_col > 0 {
+                                sequence_layout.get_button_at_position(relative_col - 1)
+                            } else {
+                                None
+                            }
+                        });
+
+                    if let Some(button) = button {
+                        new_hover_state = Some(ButtonHoverState::new(
+                            ButtonType::Sequence(button),
+                            row,
+                            col,
+                        ));
                     }
                 }
             }
@@ -624,60 +756,49 @@ impl App {
 
                 if actual_task_index < self.tasks.len() {
                     // Check step columns using StepButtonLayout like action buttons
-                    let num_steps = 3;
-                    for step in 0..num_steps {
-                        let column_index = step + 1; // Steps start at column 1
-                        if column_index < table_layout.column_rects.len() {
-                            let column_rect = table_layout.column_rects[column_index];
-                            if col >= column_rect.x && col < column_rect.x + column_rect.width {
-                                let step_layout = StepButtonLayout::new(&column_rect);
-                                let relative_col = col - column_rect.x;
-
-                                if step_layout
-                                    .get_step_button_at_position(step, relative_col)
-                                    .is_some()
-                                {
-                                    new_hover_state = Some(ButtonHoverState::new(
-                                        ButtonType::Step {
-                                            step_index: step,
-                                            task_index: actual_task_index,
-                                        },
-                                        row,
-                                        col,
-                                    ));
-                                    break;
-                                }
-                            }
+                    if let Some((step, column_rect)) = table_layout.step_at_column(col) {
+                        let step_layout =
+                            StepButtonLayout::new(&column_rect, self.sequence_state.num_steps);
+                        let relative_col = col - column_rect.x;
+
+                        if step_layout
+                            .get_step_button_at_position(step, relative_col)
+                            .is_some()
+                        {
+                            new_hover_state = Some(ButtonHoverState::new(
+                                ButtonType::Step {
+                                    step_index: step,
+                                    task_index: actual_task_index,
+                                },
+                                row,
+                                col,
+                            ));
                         }
                     }
 
                     // If no step button hover, check actions column (last column)
-                    if new_hover_state.is_none() {
-                        if let Some(actions_rect) = table_layout.column_rects.last() {
-                            if col >= actions_rect.x && col < actions_rect.x + actions_rect.width {
-                                // Check if this task is being renamed to use the correct button layout
-                                let task_name = &self.tasks[actual_task_index].name;
-                                let is_task_being_renamed = matches!(&self.state, AppState::Renaming(renaming_task) if renaming_task == task_name);
-
-                                let action_layout = ActionButtonLayout::new_with_mode(
-                                    actions_rect,
-                                    is_task_being_renamed,
-                                );
-                                let relative_col = col - actions_rect.x;
-
-                                if let Some(button) =
-                                    action_layout.get_button_at_position(relative_col)
-                                {
-                                    new_hover_state = Some(ButtonHoverState::new(
-                                        ButtonType::Action {
-                                            button,
-                                            task_index: actual_task_index,
-                                        },
-                                        row,
-                                        col,
-                                    ));
-                                }
-                            }
+                    if new_hover_state.is_none()
+                        && let Some(actions_rect) = table_layout.column_rects.last()
+                        && col >= actions_rect.x
+                        && col < actions_rect.x + actions_rect.width
+                    {
+                        // Check if this task is being renamed to use the correct button layout
+                        let task_name = &self.tasks[actual_task_index].name;
+                        let is_task_being_renamed = matches!(&self.state, AppState::Renaming(renaming_task) if renaming_task == task_name);
+
+                        let action_layout =
+                            ActionButtonLayout::new_with_mode(actions_rect, is_task_being_renamed);
+                        let relative_col = col - actions_rect.x;
+
+                        if let Some(button) = action_layout.get_button_at_position(relative_col) {
+                            new_hover_state = Some(ButtonHoverState::new(
+                                ButtonType::Action {
+                                    button,
+                                    task_index: actual_task_index,
+                                },
+                                row,
+                                col,
+                            ));
                         }
                     }
                 }
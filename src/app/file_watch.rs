@@ -0,0 +1,288 @@
+use anyhow::Result;
+use tokio::task::JoinHandle;
+
+use super::App;
+use crate::mise::file_watcher::FileWatcher;
+use crate::models::file_watch::{watching_label, WatchSet};
+use crate::models::AppEvent;
+
+/// What a tab re-runs when the files it watches change
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WatchTarget {
+    /// A task, run without arguments in the builder's output pane
+    Task(String),
+    /// The tab's whole sequence
+    Sequence,
+}
+
+/// Watch mode of a tab, stopped when dropped
+#[derive(Debug)]
+pub struct FileWatch {
+    pub target: WatchTarget,
+    /// Number of files matching the watched `sources`
+    pub files: usize,
+    /// A change cancelled the run in progress, which starts again once it has stopped
+    pub rerun_pending: bool,
+    handle: JoinHandle<()>,
+}
+
+impl Drop for FileWatch {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
+
+impl App {
+    /// Re-run the selected task whenever its `sources` change, or stop doing so
+    pub fn toggle_task_watch(&mut self) {
+        let Some(task) = self.tasks.get(self.selected_task) else {
+            return;
+        };
+        let task_name = task.name.clone();
+        let set = WatchSet::new(&self.task_config_root(task), &task.sources);
+        let target = WatchTarget::Task(task_name.clone());
+        if self.stop_watch(&target) {
+            return;
+        }
+        if set.is_empty() {
+            self.task_output
+                .push_notice(format!("Task '{task_name}' has no sources to watch"));
+            self.show_output_pane = true;
+            return;
+        }
+        self.start_watch(target, set);
+    }
+
+    /// Re-run the tab's sequence whenever the `sources` of any of its tasks change, or
+    /// stop doing so
+    pub fn toggle_sequence_watch(&mut self) {
+        if self.stop_watch(&WatchTarget::Sequence) {
+            return;
+        }
+        let sequence_tasks: Vec<String> = (0..self.sequence_state.num_steps)
+            .flat_map(|step| self.sequence_state.get_tasks_for_step(step))
+            .collect();
+        if sequence_tasks.is_empty() {
+            self.task_output
+                .push_notice("Add tasks to the sequence before watching it");
+            self.show_output_pane = true;
+            return;
+        }
+        let mut set = WatchSet::default();
+        for task in self
+            .tasks
+            .iter()
+            .filter(|task| sequence_tasks.contains(&task.name))
+        {
+            set.add(&self.task_config_root(task), &task.sources);
+        }
+        if set.is_empty() {
+            self.task_output
+                .push_notice("No task in the sequence has sources to watch");
+            self.show_output_pane = true;
+            return;
+        }
+        self.start_watch(WatchTarget::Sequence, set);
+    }
+
+    /// Stop the tab's watch if it is on `target`, telling whether it was
+    fn stop_watch(&mut self, target: &WatchTarget) -> bool {
+        if self
+            .watch
+            .as_ref()
+            .is_none_or(|watch| watch.target != *target)
+        {
+            return false;
+        }
+        self.watch = None;
+        self.task_output.push_notice("Stopped watching");
+        true
+    }
+
+    fn start_watch(&mut self, target: WatchTarget, set: WatchSet) {
+        self.show_output_pane = true;
+        let watcher = match FileWatcher::new(set) {
+            Ok(watcher) => watcher,
+            Err(e) => {
+                self.task_output
+                    .push_notice(format!("Failed to watch sources: {e:#}"));
+                return;
+            }
+        };
+
+        let files = watcher.file_count();
+        let event_tx = self.event_tx.clone();
+        let tab_id = self.tab_id;
+        let handle = tokio::spawn(async move {
+            let on_change = |files| {
+                event_tx
+                    .send(AppEvent::for_tab(tab_id, AppEvent::SourcesChanged(files)))
+                    .is_ok()
+            };
+            if let Err(e) = watcher.run(on_change).await {
+                log::warn!("Stopped watching sources: {e:#}");
+            }
+        });
+
+        let name = match &target {
+            WatchTarget::Task(task_name) => format!("task '{task_name}'"),
+            WatchTarget::Sequence => "the sequence".to_string(),
+        };
+        self.task_output.push_notice(format!(
+            "Re-running {name} when its sources change, {}",
+            watching_label(files)
+        ));
+        // Replacing a watch on another target stops that one
+        self.watch = Some(FileWatch {
+            target,
+            files,
+            rerun_pending: false,
+            handle,
+        });
+    }
+
+    /// Re-run the watched task or sequence, cancelling the run in progress first
+    pub async fn handle_sources_changed(&mut self, files: usize) -> Result<()> {
+        let running = self.is_any_task_running();
+        let Some(watch) = &mut self.watch else {
+            return Ok(());
+        };
+        watch.files = files;
+
+        if running {
+            if !std::mem::replace(&mut watch.rerun_pending, true) {
+                self.task_output
+                    .push_notice("Sources changed, restarting...");
+                self.sequence_state.reset_execution();
+                self.cancel_running_task();
+            }
+            return Ok(());
+        }
+
+        match watch.target.clone() {
+            WatchTarget::Task(task_name) => self.run_task_by_name(&task_name, Vec::new()).await?,
+            WatchTarget::Sequence => self.start_sequence_execution()?,
+        }
+        Ok(())
+    }
+
+    /// Start the run a change cancelled, once that run is over
+    pub fn resume_watch(&mut self) {
+        if let Some(watch) = &mut self.watch
+            && std::mem::take(&mut watch.rerun_pending)
+        {
+            let files = watch.files;
+            self.send_tab_event(AppEvent::SourcesChanged(files));
+        }
+    }
+
+    /// `watching N files` when the tab watches `target`
+    pub fn watch_label(&self, target: &WatchTarget) -> Option<String> {
+        self.watch
+            .as_ref()
+            .filter(|watch| watch.target == *target)
+            .map(|watch| watching_label(watch.files))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::MiseTask;
+    use tokio::sync::mpsc;
+
+    fn create_test_app() -> (App, mpsc::UnboundedReceiver<AppEvent>) {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let mut app = App::new(tx);
+        app.tasks = vec![MiseTask::new("build".to_string(), "mise.toml".to_string())];
+        (app, rx)
+    }
+
+    #[test]
+    fn test_task_without_sources_is_not_watched() {
+        let (mut app, _rx) = create_test_app();
+        app.toggle_task_watch();
+
+        assert!(app.watch.is_none());
+        assert_eq!(
+            app.task_output.back().unwrap().text,
+            "Task 'build' has no sources to watch"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_toggle_task_watch() {
+        let dir =
+            std::env::temp_dir().join(format!("mise-sequencer-app-watch-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("main.rs"), "").unwrap();
+        let (mut app, _rx) = create_test_app();
+        app.tasks[0].sources = vec![format!("{}/*.rs", dir.display())];
+
+        app.toggle_task_watch();
+        let target = WatchTarget::Task("build".to_string());
+        assert_eq!(app.watch_label(&target).as_deref(), Some("watching 1 file"));
+        assert_eq!(app.watch_label(&WatchTarget::Sequence), None);
+
+        app.toggle_task_watch();
+        assert!(app.watch.is_none());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_sources_are_taken_from_the_config_of_their_task() {
+        let dir = std::env::temp_dir().join(format!(
+            "mise-sequencer-app-watch-root-{}",
+            std::process::id()
+        ));
+        std::fs::create_dir_all(dir.join("app/src")).unwrap();
+        std::fs::write(dir.join("app/src/main.rs"), "").unwrap();
+        std::fs::write(dir.join("app/Cargo.toml"), "").unwrap();
+        let (mut app, _rx) = create_test_app();
+        let mut build = MiseTask::new(
+            "build".to_string(),
+            dir.join("app/mise.toml").display().to_string(),
+        );
+        build.sources = vec![
+            "src/*.rs".to_string(),
+            "{{config_root}}/Cargo.toml".to_string(),
+        ];
+        app.tasks = vec![build];
+
+        app.toggle_task_watch();
+        let target = WatchTarget::Task("build".to_string());
+        assert_eq!(
+            app.watch_label(&target).as_deref(),
+            Some("watching 2 files")
+        );
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_change_restarts_run_in_progress() {
+        let (mut app, mut rx) = create_test_app();
+        app.watch = Some(FileWatch {
+            target: WatchTarget::Task("build".to_string()),
+            files: 1,
+            rerun_pending: false,
+            handle: tokio::spawn(async {}),
+        });
+        app.task_running = true;
+        let cancel = app.new_run_cancel();
+
+        app.handle_sources_changed(3).await.unwrap();
+        assert!(*cancel.borrow());
+        assert!(app.watch.as_ref().unwrap().rerun_pending);
+        assert_eq!(app.watch.as_ref().unwrap().files, 3);
+
+        // The run starts again once the cancelled one is over
+        app.finish_run();
+        assert!(!app.watch.as_ref().unwrap().rerun_pending);
+        match rx.try_recv() {
+            Ok(AppEvent::ForTab(_, event)) => {
+                assert!(matches!(*event, AppEvent::SourcesChanged(3)))
+            }
+            other => panic!("Expected SourcesChanged event, got {other:?}"),
+        }
+    }
+}
@@ -0,0 +1,182 @@
+use std::time::Instant;
+
+use super::App;
+use crate::models::freshness::{task_freshness, Freshness, FRESHNESS_REFRESH_INTERVAL};
+use crate::models::{AppEvent, SequenceEvent};
+
+impl App {
+    /// Check the freshness of every task on a blocking thread, the results come back as
+    /// `AppEvent::FreshnessRefreshed`. While a check runs the next one waits for the tick
+    /// after it
+    pub fn refresh_freshness(&mut self) {
+        if self.freshness_refreshing {
+            self.freshness_checked_at = None;
+            return;
+        }
+        self.freshness_refreshing = true;
+        self.freshness_checked_at = Some(Instant::now());
+        let tasks: Vec<_> = self
+            .tasks
+            .iter()
+            .map(|task| (task.clone(), self.task_config_root(task)))
+            .collect();
+        let event_tx = self.event_tx.clone();
+        tokio::task::spawn_blocking(move || {
+            let freshness = tasks
+                .iter()
+                .map(|(task, base)| (task.name.clone(), task_freshness(base, task)))
+                .collect();
+            let _ = event_tx.send(AppEvent::FreshnessRefreshed(freshness));
+        });
+    }
+
+    /// Refresh the freshness once the last check is older than the refresh interval
+    pub fn refresh_freshness_if_due(&mut self) {
+        let due = self
+            .freshness_checked_at
+            .is_none_or(|checked_at| checked_at.elapsed() >= FRESHNESS_REFRESH_INTERVAL);
+        if due && !self.tasks.is_empty() {
+            self.refresh_freshness();
+        }
+    }
+
+    /// Freshness of a task as of the last check, None until it has been checked
+    pub fn freshness_of(&self, task_name: &str) -> Option<Freshness> {
+        self.task_freshness.get(task_name).copied()
+    }
+
+    /// Check on a blocking thread whether every task of `step` is fresh, so running only
+    /// stale steps skips it. Checked anew as earlier steps may just have changed the
+    /// sources, the answer comes back as `SequenceEvent::StepFreshnessChecked`
+    pub fn check_step_freshness(&self, step: usize) {
+        let step_tasks = self.sequence_state.get_tasks_for_step(step);
+        let tasks: Vec<_> = step_tasks
+            .iter()
+            .map(|task_name| {
+                self.tasks
+                    .iter()
+                    .find(|task| task.name == *task_name)
+                    .map(|task| (task.clone(), self.task_config_root(task)))
+            })
+            .collect();
+        let event_tx = self.event_tx.clone();
+        let tab_id = self.tab_id;
+        tokio::task::spawn_blocking(move || {
+            let fresh = !tasks.is_empty()
+                && tasks.iter().all(|task| {
+                    task.as_ref()
+                        .is_some_and(|(task, base)| task_freshness(base, task) == Freshness::Fresh)
+                });
+            let event = SequenceEvent::StepFreshnessChecked { step, fresh };
+            let _ = event_tx.send(AppEvent::for_tab(tab_id, AppEvent::Sequence(event)));
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{MiseTask, SequenceEvent, SequenceState};
+    use tokio::sync::mpsc;
+
+    fn create_test_app(dir: &std::path::Path) -> (App, mpsc::UnboundedReceiver<AppEvent>) {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let mut app = App::new(tx);
+        // Relative globs are taken from the directory of the config defining the task
+        let config = dir.join("mise.toml").display().to_string();
+        let mut build = MiseTask::new("build".to_string(), config.clone());
+        build.sources = vec!["*.rs".to_string()];
+        build.outputs = vec!["{{config_root}}/app".to_string()];
+        app.tasks = vec![build, MiseTask::new("lint".to_string(), config)];
+        (app, rx)
+    }
+
+    #[tokio::test]
+    async fn test_refresh_freshness() {
+        let dir = std::env::temp_dir().join(format!(
+            "mise-sequencer-app-freshness-{}",
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        let (mut app, mut rx) = create_test_app(&dir);
+
+        app.refresh_freshness_if_due();
+        // A refresh asked for while one runs waits for the next tick
+        app.refresh_freshness();
+        let event = rx.recv().await.unwrap();
+        app.handle_event(event).await.unwrap();
+        assert_eq!(app.freshness_of("build"), Some(Freshness::Stale));
+        assert_eq!(app.freshness_of("lint"), Some(Freshness::Untracked));
+        assert_eq!(app.freshness_of("test"), None);
+        assert!(rx.try_recv().is_err());
+
+        app.refresh_freshness_if_due();
+        let event = rx.recv().await.unwrap();
+        app.handle_event(event).await.unwrap();
+
+        // Not checked again until the interval has passed
+        app.refresh_freshness_if_due();
+        assert!(rx.try_recv().is_err());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_only_stale_skips_fresh_steps() {
+        let dir = std::env::temp_dir().join(format!(
+            "mise-sequencer-app-only-stale-{}",
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("main.rs"), "").unwrap();
+        std::fs::write(dir.join("app"), "").unwrap();
+        let (mut app, mut rx) = create_test_app(&dir);
+        app.sequence_state = SequenceState::from_steps(&[vec!["build".to_string()]]);
+
+        app.handle_sequence_event(SequenceEvent::ToggleOnlyStale)
+            .await
+            .unwrap();
+        app.start_sequence_execution().unwrap();
+        let event = rx.recv().await.unwrap();
+        app.handle_event(event).await.unwrap();
+        assert_eq!(
+            app.task_output.back().unwrap().text,
+            "Step 1 skipped, its outputs are up to date"
+        );
+        match rx.try_recv() {
+            Ok(AppEvent::ForTab(_, event)) => assert!(matches!(
+                *event,
+                AppEvent::Sequence(SequenceEvent::StepCompleted(ref results)) if results.is_empty()
+            )),
+            other => panic!("Expected StepCompleted event, got {other:?}"),
+        }
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_sequence_stopped_while_checking_freshness() {
+        let dir = std::env::temp_dir().join(format!(
+            "mise-sequencer-app-only-stale-stop-{}",
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        let (mut app, mut rx) = create_test_app(&dir);
+        app.sequence_state = SequenceState::from_steps(&[vec!["build".to_string()]]);
+
+        app.handle_sequence_event(SequenceEvent::ToggleOnlyStale)
+            .await
+            .unwrap();
+        app.start_sequence_execution().unwrap();
+        app.stop_sequence().await.unwrap();
+        let event = rx.recv().await.unwrap();
+        app.handle_event(event).await.unwrap();
+        assert!(!app.task_running);
+        assert_eq!(
+            app.task_output.back().unwrap().text,
+            "Sequence stopped by user"
+        );
+        assert!(rx.try_recv().is_err());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -1,81 +1,243 @@
 use anyhow::Result;
 use ratatui::layout::Rect;
-use std::{collections::VecDeque, time::Instant};
-use tokio::sync::mpsc;
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    time::Instant,
+};
+use tokio::sync::{mpsc, watch};
 use tokio::task::JoinHandle;
 use tui_input::Input;
 
 use crate::mise::MiseClient;
-use crate::models::{AppEvent, AppState, MiseTask, MiseTaskInfo, SequenceState};
+use crate::models::config_layout::ConfigLayout;
+use crate::models::editor::editor_from_env;
+use crate::models::freshness::Freshness;
+use crate::models::notification::NotifyConfig;
+use crate::models::task_args::{ArgsForm, TaskArg};
+use crate::models::{
+    AppEvent, AppState, ErrorNavigator, MiseTask, MiseTaskInfo, OutputBuffer, OutputLine,
+    OutputSearch, Prompt, PtySize, ResourceUsage, RunHistory, RunRecording, ScrollbackConfig,
+    SequenceState, SequenceStore, TaskInput, TaskResult, TerminalSession, UsageSample,
+    DEFAULT_NUM_STEPS, SEQUENCES_FILE,
+};
 use crate::ui::button_layout::ButtonHoverState;
 use crate::ui::sequence_builder::TableLayout;
 
+pub mod config_watch;
+pub mod error_locations;
 pub mod event_handlers;
+pub mod file_watch;
+pub mod freshness;
+pub mod notifications;
+pub mod output_search;
+pub mod output_view;
+pub mod resource_usage;
+pub mod run_history;
+pub mod saved_sequences;
 pub mod sequence_management;
+pub mod tabs;
+pub mod task_args;
+pub mod task_input;
 pub mod task_management;
+pub mod terminal;
+
+use config_watch::ConfigWatch;
+use file_watch::FileWatch;
+use tabs::SequenceTab;
 
 pub struct App {
     pub client: MiseClient,
     pub tasks: Vec<MiseTask>,
+    /// Where the config defining the tasks lives, as of the last refresh
+    pub config_layout: ConfigLayout,
+    /// Refreshes the task list when the files defining the tasks change
+    pub config_watch: Option<ConfigWatch>,
+    /// Listing of the tasks and prewarming of their details, replaced by each refresh
+    pub task_refresh: Option<JoinHandle<()>>,
     pub selected_task: usize,
     pub scroll_offset: usize,
     pub state: AppState,
     pub task_info: Option<MiseTaskInfo>,
-    pub task_output: VecDeque<String>,
+    pub task_output: OutputBuffer,
     pub should_quit: bool,
     pub last_updated: Instant,
     pub event_tx: mpsc::UnboundedSender<AppEvent>,
-    pub task_output_rx: Option<mpsc::UnboundedReceiver<String>>,
+    pub task_output_rx: Option<mpsc::UnboundedReceiver<OutputLine>>,
+    /// Re-runs a task or the sequence when its sources change
+    pub watch: Option<FileWatch>,
+    /// CPU and memory use reported by the tasks of the current run
+    pub task_usage_rx: Option<mpsc::UnboundedReceiver<UsageSample>>,
+    /// Latest usage of each task still running, shown with the output
+    pub task_usage: BTreeMap<String, ResourceUsage>,
+    /// Screen of the task running in PTY mode, shown in place of the output lines
+    pub terminal: Option<TerminalSession>,
+    /// Stdin of the running task, which keys go to while it has focus
+    pub task_input: Option<TaskInput>,
+    /// Tasks run on a pseudo-terminal instead of pipes
+    pub pty_tasks: HashSet<String>,
+    /// Inner size of the output pane, which PTY tasks get as their terminal size
+    pub output_pane_size: PtySize,
     pub sequence_state: SequenceState,
+    pub selected_step: usize,
+    pub step_scroll_offset: usize,
+    pub current_visible_steps: usize,
     pub table_layout: Option<TableLayout>,
     pub show_output_pane: bool,
     pub task_running: bool,
     pub running_task_name: Option<String>,
     pub running_task_handle: Option<JoinHandle<()>>,
+    /// Stops the running task or sequence, including every process it started
+    pub running_task_cancel: Option<watch::Sender<bool>>,
+    /// Latest result of each task, from single runs and sequence steps alike
+    pub last_task_results: HashMap<String, TaskResult>,
     pub button_hover_state: Option<ButtonHoverState>,
     pub current_visible_height: usize,
     pub output_scroll_offset: usize,
     pub current_output_visible_height: usize,
     pub output_follow_mode: bool,
+    /// Show only the stderr lines in the output pane
+    pub output_stderr_only: bool,
+    /// Show when each output line arrived, relative to the earliest one
+    pub output_timestamps: bool,
+    /// Search through the output pane, whose matches are highlighted
+    pub output_search: Option<OutputSearch>,
+    /// Scroll offset the search prompt was opened at, where typing starts looking
+    pub output_search_origin: usize,
+    /// Why the pattern being typed is not a valid regex
+    pub output_search_error: Option<String>,
+    /// File locations found in the output pane, listed over it
+    pub error_navigator: Option<ErrorNavigator>,
+    /// Command files are opened with, see `EDITOR_ENV`
+    pub editor: String,
+    /// How finished runs are notified about, see `NOTIFY_ENV`
+    pub notify: NotifyConfig,
+    /// How much output each tab keeps, new tabs get their buffer from it
+    pub scrollback: ScrollbackConfig,
     pub pending_delete_task: Option<String>,
     pub delete_dialog_area: Option<Rect>,
     pub rename_input: Option<Input>,
     pub original_task_name: Option<String>,
+    pub sequence_store: SequenceStore,
+    /// Name the current sequence was last saved or loaded under
+    pub current_sequence_name: Option<String>,
+    /// Mise task the sequence was opened from, which "Add as task" writes back to
+    pub source_task_name: Option<String>,
+    pub selected_saved_sequence: usize,
+    pub pending_delete_sequence: Option<String>,
+    pub prompt: Option<Prompt>,
+    /// Dialog asking for the arguments of a task
+    pub args_form: Option<ArgsForm>,
+    /// Whether each task's outputs are up to date with its sources, as of the last check
+    pub task_freshness: HashMap<String, Freshness>,
+    /// When the freshness was last checked, None to check again on the next tick
+    pub freshness_checked_at: Option<Instant>,
+    /// A freshness check runs in the background
+    pub freshness_refreshing: bool,
+    /// Arguments each task declares, cleared whenever the task list is refreshed
+    pub task_arg_specs: HashMap<String, Vec<TaskArg>>,
+    /// Arguments each task last ran with, offered again on its next run
+    pub last_task_args: HashMap<String, Vec<String>>,
+    pub run_history: RunHistory,
+    /// Run of the active tab being captured for the history
+    pub run_recording: Option<RunRecording>,
+    pub selected_history_run: usize,
+    pub history_filter: String,
+    /// Id of the tab whose state is loaded onto `App`
+    pub tab_id: usize,
+    /// All tabs in display order. The active tab's slot is a placeholder, its state is on `App`
+    pub tabs: Vec<SequenceTab>,
+    pub active_tab: usize,
+    pub next_tab_id: usize,
+    pub tab_bar_area: Option<Rect>,
 }
 
 impl App {
     pub fn new(event_tx: mpsc::UnboundedSender<AppEvent>) -> Self {
+        let scrollback = ScrollbackConfig::from_env();
         Self {
-            client: MiseClient::new(),
+            client: MiseClient::from_env(),
             tasks: vec![],
+            config_layout: ConfigLayout::default(),
+            config_watch: None,
+            task_refresh: None,
             selected_task: 0,
             scroll_offset: 0,
             state: AppState::SequenceBuilder,
             task_info: None,
-            task_output: VecDeque::new(),
+            task_output: OutputBuffer::new(scrollback),
             should_quit: false,
             last_updated: Instant::now(),
             event_tx,
             task_output_rx: None,
-            sequence_state: SequenceState::new(3),
+            watch: None,
+            task_usage_rx: None,
+            task_usage: BTreeMap::new(),
+            terminal: None,
+            task_input: None,
+            pty_tasks: HashSet::new(),
+            output_pane_size: PtySize::default(),
+            sequence_state: SequenceState::new(DEFAULT_NUM_STEPS),
+            selected_step: 0,
+            step_scroll_offset: 0,
+            current_visible_steps: 0,
             table_layout: None,
             show_output_pane: false,
             task_running: false,
             running_task_name: None,
             running_task_handle: None,
+            running_task_cancel: None,
+            last_task_results: HashMap::new(),
             button_hover_state: None,
             current_visible_height: 0,
             output_scroll_offset: 0,
             current_output_visible_height: 0,
             output_follow_mode: true,
+            output_stderr_only: false,
+            output_timestamps: false,
+            output_search: None,
+            output_search_origin: 0,
+            output_search_error: None,
+            error_navigator: None,
+            editor: editor_from_env(),
+            notify: NotifyConfig::from_env(),
+            scrollback,
             pending_delete_task: None,
             delete_dialog_area: None,
             rename_input: None,
             original_task_name: None,
+            sequence_store: SequenceStore::new(SEQUENCES_FILE),
+            current_sequence_name: None,
+            source_task_name: None,
+            selected_saved_sequence: 0,
+            pending_delete_sequence: None,
+            prompt: None,
+            args_form: None,
+            task_freshness: HashMap::new(),
+            freshness_checked_at: None,
+            freshness_refreshing: false,
+            task_arg_specs: HashMap::new(),
+            last_task_args: HashMap::new(),
+            // Kept in memory only, unless a history is given with `with_run_history`
+            run_history: RunHistory::disabled(std::env::current_dir().unwrap_or_default()),
+            run_recording: None,
+            selected_history_run: 0,
+            history_filter: String::new(),
+            tab_id: 0,
+            tabs: vec![SequenceTab::new(0, scrollback)],
+            active_tab: 0,
+            next_tab_id: 1,
+            tab_bar_area: None,
         }
     }
 
+    /// Record runs in `run_history` instead of in memory only
+    pub fn with_run_history(mut self, run_history: RunHistory) -> Self {
+        self.run_history = run_history;
+        self
+    }
+
     pub async fn initialize(&mut self) -> Result<()> {
+        self.load_saved_sequences();
         self.refresh_tasks().await?;
         Ok(())
     }
@@ -84,12 +246,35 @@ impl App {
         self.should_quit
     }
 
-    // Check for new task output
-    pub fn poll_task_output(&mut self) {
-        if let Some(ref mut rx) = self.task_output_rx {
-            while let Ok(output) = rx.try_recv() {
-                if self.event_tx.send(AppEvent::TaskOutput(output)).is_err() {
-                    eprintln!("Warning: Failed to send TaskOutput event");
+    /// Append a line to the output pane, which keeps as many lines as the scrollback allows.
+    /// Lines that arrive while a sequence runs are marked with the current step
+    pub fn push_task_output(&mut self, mut output: OutputLine) {
+        if self.sequence_state.is_running && output.step.is_none() {
+            output.step = self.sequence_state.current_step;
+        }
+        if let Some(recording) = &mut self.run_recording {
+            recording.push(&output);
+        }
+        // Keep the scroll position on the same lines when the oldest ones are dropped
+        let shown = self.output_view_len() + usize::from(self.is_shown_in_output(&output));
+        self.task_output.push_back(output);
+        let dropped = shown - self.output_view_len();
+        self.output_scroll_offset = self.output_scroll_offset.saturating_sub(dropped);
+
+        // Auto-scroll to bottom if follow mode is enabled and we're at or near the bottom
+        if self.show_output_pane
+            && self.task_running
+            && self.output_follow_mode
+            && self.current_output_visible_height > 0
+        {
+            let visible_height = self.current_output_visible_height;
+            let total_lines = self.output_view_len();
+
+            // If we're within a few lines of the bottom, keep following
+            if total_lines > visible_height {
+                let max_scroll = total_lines - visible_height;
+                if self.output_scroll_offset >= max_scroll.saturating_sub(3) {
+                    self.output_scroll_offset = max_scroll;
                 }
             }
         }
@@ -162,10 +347,14 @@ impl App {
         self.task_info = None;
         self.task_output.clear();
         self.task_output_rx = None;
+        self.clear_task_usage();
+        self.terminal = None;
+        self.task_input = None;
         self.show_output_pane = false;
         self.task_running = false;
         self.running_task_name = None;
         self.running_task_handle = None;
+        self.running_task_cancel = None;
         self.button_hover_state = None;
         self.current_visible_height = 0;
         self.output_scroll_offset = 0;
@@ -181,11 +370,11 @@ impl App {
 
     pub fn scroll_output_down(&mut self, lines: usize) {
         let visible_height = self.current_output_visible_height;
-        if self.task_output.len() <= visible_height || visible_height == 0 {
+        if self.output_view_len() <= visible_height || visible_height == 0 {
             return;
         }
 
-        let max_scroll = self.task_output.len().saturating_sub(visible_height);
+        let max_scroll = self.output_view_len().saturating_sub(visible_height);
         self.output_scroll_offset = (self.output_scroll_offset + lines).min(max_scroll);
     }
 
@@ -201,8 +390,8 @@ impl App {
 
     pub fn auto_scroll_output_to_bottom(&mut self) {
         let visible_height = self.current_output_visible_height;
-        if self.task_output.len() > visible_height && visible_height > 0 {
-            self.output_scroll_offset = self.task_output.len() - visible_height;
+        if self.output_view_len() > visible_height && visible_height > 0 {
+            self.output_scroll_offset = self.output_view_len() - visible_height;
         } else {
             self.output_scroll_offset = 0;
         }
@@ -272,16 +461,17 @@ impl App {
                         .unwrap_or(&new_name);
 
                     if final_name != new_name {
-                        self.task_output.push_back(format!("Task '{original_name}' renamed to '{final_name}' (name adjusted to avoid conflicts)"));
+                        self.task_output.push_notice(format!("Task '{original_name}' renamed to '{final_name}' (name adjusted to avoid conflicts)"));
                     } else {
-                        self.task_output
-                            .push_back(format!("Task '{original_name}' renamed to '{final_name}'"));
+                        self.task_output.push_notice(format!(
+                            "Task '{original_name}' renamed to '{final_name}'"
+                        ));
                     }
                     self.show_output_pane = true;
                 }
                 Err(e) => {
                     self.task_output
-                        .push_back(format!("Failed to rename task '{original_name}': {e}"));
+                        .push_notice(format!("Failed to rename task '{original_name}': {e}"));
                     self.show_output_pane = true;
                 }
             }
@@ -298,6 +488,37 @@ impl App {
         self.original_task_name = None;
     }
 
+    /// Start tracking a new run and return the receiver its processes watch for cancellation
+    pub fn new_run_cancel(&mut self) -> watch::Receiver<bool> {
+        let (cancel_tx, cancel_rx) = watch::channel(false);
+        self.running_task_cancel = Some(cancel_tx);
+        cancel_rx
+    }
+
+    /// Ask the running task or sequence to shut down its processes.
+    /// The run reports back through its usual completion events once they have exited
+    pub fn cancel_running_task(&self) {
+        if let Some(cancel_tx) = &self.running_task_cancel {
+            cancel_tx.send_replace(true);
+        }
+    }
+
+    /// Cancel the runs of every tab and wait until their processes have shut down, so
+    /// quitting leaves nothing running
+    pub async fn shutdown(&mut self) {
+        self.cancel_running_task();
+        let mut handles: Vec<_> = self.running_task_handle.take().into_iter().collect();
+        for tab in &mut self.tabs {
+            if let Some(cancel_tx) = &tab.running_task_cancel {
+                cancel_tx.send_replace(true);
+            }
+            handles.extend(tab.running_task_handle.take());
+        }
+        for handle in handles {
+            let _ = handle.await;
+        }
+    }
+
     pub fn is_task_running(&self, task_name: &str) -> bool {
         self.running_task_name
             .as_ref()
@@ -312,7 +533,53 @@ impl App {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::models::MiseTask;
+    use crate::models::mise_task::RunEntry;
+    use crate::models::{
+        LineSource, MiseTask, PromptKind, SequenceEvent, StepFailurePolicy, TaskExit,
+    };
+    use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+    fn app_with_temp_store(name: &str) -> (App, std::path::PathBuf) {
+        let path = std::env::temp_dir().join(format!(
+            "mise-sequences-app-{}-{name}.toml",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+        let mut app = create_test_app();
+        app.sequence_store = SequenceStore::new(&path);
+        (app, path)
+    }
+
+    /// Next event sent for the first tab, unwrapped
+    fn next_tab_event(rx: &mut mpsc::UnboundedReceiver<AppEvent>) -> Option<AppEvent> {
+        match rx.try_recv() {
+            Ok(AppEvent::ForTab(0, event)) => Some(*event),
+            _ => None,
+        }
+    }
+
+    async fn press(app: &mut App, code: KeyCode) {
+        app.handle_key(KeyEvent::new(code, KeyModifiers::NONE))
+            .await
+            .unwrap();
+    }
+
+    fn task_result(task_name: &str, exit: TaskExit) -> TaskResult {
+        let started_at = std::time::SystemTime::now();
+        TaskResult {
+            task_name: task_name.to_string(),
+            exit,
+            started_at,
+            finished_at: started_at + std::time::Duration::from_millis(1500),
+            stdout_lines: 3,
+            stderr_lines: 0,
+            peak_usage: None,
+        }
+    }
+
+    fn failed_step() -> SequenceEvent {
+        SequenceEvent::StepFailed(vec![task_result("build", TaskExit::Code(1))])
+    }
 
     fn create_test_app() -> App {
         let (tx, _rx) = mpsc::unbounded_channel();
@@ -332,12 +599,63 @@ mod tests {
         assert_eq!(app.task_output.len(), 0);
         assert!(!app.should_quit);
         assert!(app.task_output_rx.is_none());
+        // Nothing is written to the real history directory unless asked for
+        assert_eq!(app.run_history.dir(), None);
         assert_eq!(app.sequence_state.num_steps, 3);
+        assert_eq!(app.selected_step, 0);
+        assert_eq!(app.step_scroll_offset, 0);
         assert!(app.table_layout.is_none());
         assert!(app.button_hover_state.is_none());
         assert_eq!(app.current_visible_height, 0);
     }
 
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_shutdown_stops_running_tasks() {
+        use crate::mise::client::MiseCli;
+        use std::os::unix::fs::PermissionsExt;
+        use std::time::Duration;
+
+        let dir =
+            std::env::temp_dir().join(format!("mise-sequencer-shutdown-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        // Stands in for `mise run`, recording its pid before waiting on a child
+        let program = dir.join("mise");
+        std::fs::write(
+            &program,
+            "#!/bin/sh\necho $$ > \"$(dirname \"$0\")/pid\"\nsleep 30\n",
+        )
+        .unwrap();
+        std::fs::set_permissions(&program, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let mut app = create_test_app();
+        app.client = MiseClient::new(
+            MiseCli::new()
+                .with_program(program.display().to_string())
+                .with_kill_grace_period(Duration::from_secs(1)),
+        );
+
+        app.run_task_in_view("build", Vec::new()).await.unwrap();
+        let mut pid = None;
+        for _ in 0..100 {
+            pid = std::fs::read_to_string(dir.join("pid"))
+                .ok()
+                .and_then(|pid| pid.trim().parse::<libc::pid_t>().ok());
+            if pid.is_some() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        let pid = pid.expect("the task never started");
+
+        app.shutdown().await;
+
+        assert!(app.running_task_handle.is_none());
+        // SAFETY: signal 0 only checks whether any process of the group is left
+        assert_ne!(unsafe { libc::killpg(pid, 0) }, 0);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
     #[test]
     fn test_should_quit() {
         let mut app = create_test_app();
@@ -419,6 +737,34 @@ mod tests {
         assert_eq!(app.selected_task, 0);
     }
 
+    #[test]
+    fn test_set_tasks_keeps_selection_by_name() {
+        let task = |name: &str| MiseTask::new(name.to_string(), "mise.toml".to_string());
+        let mut app = create_test_app();
+        app.tasks = vec![task("build"), task("lint"), task("test")];
+        app.selected_task = 1;
+
+        // A task added before the selected one moves it down
+        app.set_tasks(vec![
+            task("audit"),
+            task("build"),
+            task("lint"),
+            task("test"),
+        ]);
+        assert_eq!(app.selected_task, 2);
+
+        // A removed task leaves the selection where it was, within the list
+        app.selected_task = 3;
+        app.scroll_offset = 3;
+        app.set_tasks(vec![task("audit"), task("build")]);
+        assert_eq!(app.selected_task, 1);
+        assert_eq!(app.scroll_offset, 1);
+        assert_eq!(app.get_visible_tasks(10).0.len(), 1);
+
+        app.set_tasks(Vec::new());
+        assert_eq!(app.selected_task, 0);
+    }
+
     #[test]
     fn test_back_to_list() {
         let mut app = create_test_app();
@@ -447,8 +793,8 @@ mod tests {
             file: None,
             usage_spec: serde_json::Value::Null,
         });
-        app.task_output.push_back("output1".to_string());
-        app.task_output.push_back("output2".to_string());
+        app.task_output.push_notice("output1".to_string());
+        app.task_output.push_notice("output2".to_string());
 
         app.back_to_list();
 
@@ -458,6 +804,549 @@ mod tests {
         assert!(app.task_output_rx.is_none());
     }
 
+    #[test]
+    fn test_select_step_stays_in_bounds() {
+        let mut app = create_test_app();
+        app.current_visible_steps = 3;
+
+        app.select_previous_step();
+        assert_eq!(app.selected_step, 0);
+
+        app.select_next_step();
+        app.select_next_step();
+        app.select_next_step();
+        assert_eq!(app.selected_step, 2);
+    }
+
+    #[test]
+    fn test_selected_step_scrolls_into_view() {
+        let mut app = create_test_app();
+        app.sequence_state = SequenceState::new(10);
+        app.current_visible_steps = 3;
+
+        for _ in 0..5 {
+            app.select_next_step();
+        }
+        assert_eq!(app.selected_step, 5);
+        assert_eq!(app.step_scroll_offset, 3);
+
+        for _ in 0..5 {
+            app.select_previous_step();
+        }
+        assert_eq!(app.selected_step, 0);
+        assert_eq!(app.step_scroll_offset, 0);
+    }
+
+    #[test]
+    fn test_scroll_steps_clamped() {
+        let mut app = create_test_app();
+        app.sequence_state = SequenceState::new(6);
+        app.current_visible_steps = 4;
+
+        app.scroll_steps_right(10);
+        assert_eq!(app.step_scroll_offset, 2);
+
+        app.scroll_steps_left(10);
+        assert_eq!(app.step_scroll_offset, 0);
+    }
+
+    #[tokio::test]
+    async fn test_add_insert_remove_step_events() {
+        let mut app = create_test_app();
+        app.current_visible_steps = 10;
+
+        app.handle_sequence_event(SequenceEvent::AddStep)
+            .await
+            .unwrap();
+        assert_eq!(app.sequence_state.num_steps, 4);
+        assert_eq!(app.selected_step, 3);
+
+        app.handle_sequence_event(SequenceEvent::InsertStep(0))
+            .await
+            .unwrap();
+        assert_eq!(app.sequence_state.num_steps, 5);
+        assert_eq!(app.selected_step, 4);
+
+        // Steps inserted after the selection leave it where it is
+        app.handle_sequence_event(SequenceEvent::InsertStep(5))
+            .await
+            .unwrap();
+        assert_eq!(app.sequence_state.num_steps, 6);
+        assert_eq!(app.selected_step, 4);
+
+        app.selected_step = 5;
+        app.handle_sequence_event(SequenceEvent::RemoveStep(5))
+            .await
+            .unwrap();
+        assert_eq!(app.sequence_state.num_steps, 5);
+        assert_eq!(app.selected_step, 4);
+    }
+
+    #[tokio::test]
+    async fn test_step_failed_with_stop_policy_fails_sequence() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let mut app = App::new(tx);
+        app.sequence_state.start_execution();
+
+        app.handle_sequence_event(failed_step()).await.unwrap();
+
+        match next_tab_event(&mut rx) {
+            Some(AppEvent::Sequence(SequenceEvent::SequenceFailed(error))) => {
+                assert_eq!(error, "step 1: build failed with exit code 1");
+            }
+            other => panic!("Expected SequenceFailed event, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_step_failed_with_continue_policy_moves_on() {
+        let (tx, _rx) = mpsc::unbounded_channel();
+        let mut app = App::new(tx);
+        app.sequence_state.step_policies[0] = StepFailurePolicy::Continue;
+        app.sequence_state.start_execution();
+
+        app.handle_sequence_event(failed_step()).await.unwrap();
+
+        assert_eq!(app.sequence_state.current_step, Some(1));
+        assert!(app.sequence_state.failed_steps[0]);
+        assert_eq!(
+            app.task_output.back().unwrap().text,
+            "Step 1 failed, continuing"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_cleanup_step_completion_fails_sequence() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let mut app = App::new(tx);
+        app.sequence_state.step_policies[0] = StepFailurePolicy::JumpTo(2);
+        app.sequence_state.start_execution();
+
+        app.handle_sequence_event(failed_step()).await.unwrap();
+        assert_eq!(app.sequence_state.current_step, Some(2));
+
+        // The empty cleanup step completes straight away
+        let Some(AppEvent::Sequence(SequenceEvent::StepCompleted(results))) =
+            next_tab_event(&mut rx)
+        else {
+            panic!("Expected cleanup step to complete");
+        };
+        app.handle_sequence_event(SequenceEvent::StepCompleted(results))
+            .await
+            .unwrap();
+
+        match next_tab_event(&mut rx) {
+            Some(AppEvent::Sequence(SequenceEvent::SequenceFailed(error))) => {
+                assert_eq!(error, "build failed with exit code 1");
+            }
+            other => panic!("Expected SequenceFailed event, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stopped_sequence_runs_until_its_step_exits() {
+        let mut app = create_test_app();
+        app.sequence_state.start_execution();
+        app.task_running = true;
+        let cancel = app.new_run_cancel();
+
+        app.stop_sequence().await.unwrap();
+        assert!(*cancel.borrow());
+        assert!(!app.sequence_state.is_running);
+        assert!(app.is_any_task_running());
+
+        // The cancelled step reports back once its processes are gone
+        app.handle_sequence_event(SequenceEvent::StepFailed(vec![task_result(
+            "build",
+            TaskExit::Cancelled,
+        )]))
+        .await
+        .unwrap();
+        assert!(!app.is_any_task_running());
+        assert!(app.running_task_cancel.is_none());
+        assert_eq!(
+            app.task_output.back().unwrap().text,
+            "Sequence stopped by user"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_stopped_sequence_is_not_completed_by_its_last_step() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let mut app = App::new(tx);
+        app.sequence_state.start_execution();
+        app.task_running = true;
+        app.new_run_cancel();
+
+        app.stop_sequence().await.unwrap();
+        // The step finished before the cancellation reached it
+        app.handle_sequence_event(SequenceEvent::StepCompleted(vec![task_result(
+            "build",
+            TaskExit::Code(0),
+        )]))
+        .await
+        .unwrap();
+
+        assert!(next_tab_event(&mut rx).is_none());
+        assert!(!app.is_any_task_running());
+        assert_eq!(app.sequence_state.current_step, None);
+        assert_eq!(
+            app.task_output.back().unwrap().text,
+            "Sequence stopped by user"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_step_results_are_reported_and_kept() {
+        let mut app = create_test_app();
+        app.sequence_state.start_execution();
+
+        app.handle_sequence_event(SequenceEvent::StepCompleted(vec![
+            task_result("build", TaskExit::Code(0)),
+            task_result("lint", TaskExit::Code(0)),
+        ]))
+        .await
+        .unwrap();
+
+        let output: Vec<_> = app
+            .task_output
+            .iter()
+            .map(|line| line.text.clone())
+            .collect();
+        assert_eq!(
+            output,
+            vec![
+                "✓ build finished in 1.5s (exit code 0, 3 lines)",
+                "✓ lint finished in 1.5s (exit code 0, 3 lines)",
+                "✓ Step 1 took 1.5s",
+            ]
+        );
+        assert_eq!(app.sequence_state.step_outcome(0), Some(true));
+        assert!(app.last_task_results["lint"].success());
+    }
+
+    #[tokio::test]
+    async fn test_task_completed_reports_result() {
+        let mut app = create_test_app();
+        app.task_running = true;
+
+        app.handle_tab_scoped_event(AppEvent::TaskCompleted(task_result(
+            "build",
+            TaskExit::Signal(9),
+        )))
+        .await
+        .unwrap();
+
+        assert!(!app.task_running);
+        assert_eq!(
+            app.task_output.back().unwrap().text,
+            "✗ build failed in 1.5s (signal 9, 3 lines)"
+        );
+        assert_eq!(app.last_task_results["build"].exit, TaskExit::Signal(9));
+    }
+
+    #[tokio::test]
+    async fn test_sequence_runs_against_fake_backend() {
+        use crate::mise::fake::ScriptedRun;
+        use crate::mise::FakeMise;
+
+        let dir =
+            std::env::temp_dir().join(format!("mise-sequencer-app-fake-{}", std::process::id()));
+        let fake = FakeMise::new()
+            .with_task(
+                MiseTask::new("build".to_string(), "mise.toml".to_string()),
+                ScriptedRun::new().stdout("compiling"),
+            )
+            .with_task(
+                MiseTask::new("lint".to_string(), "mise.toml".to_string()),
+                ScriptedRun::new().stderr("src/lib.rs:3:1: missing docs"),
+            );
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let mut app = App::new(tx).with_run_history(RunHistory::new(&dir, "/project"));
+        app.client = MiseClient::new(fake.clone());
+
+        app.refresh_tasks().await.unwrap();
+        let event = rx.recv().await.unwrap();
+        app.handle_event(event).await.unwrap();
+        assert_eq!(app.tasks.len(), 2);
+
+        app.sequence_state =
+            SequenceState::from_steps(&[vec!["lint".to_string()], vec!["build".to_string()]]);
+        app.handle_sequence_event(SequenceEvent::RunSequence)
+            .await
+            .unwrap();
+        while app.task_running {
+            let event = rx.recv().await.unwrap();
+            app.handle_event(event).await.unwrap();
+            app.poll_task_output();
+        }
+
+        let ran: Vec<String> = fake.run_log().into_iter().map(|(name, _)| name).collect();
+        assert_eq!(ran, vec!["lint".to_string(), "build".to_string()]);
+        assert!(app.last_task_results["lint"].success());
+        assert_eq!(app.last_task_results["build"].stdout_lines, 1);
+        assert!(app
+            .task_output
+            .iter()
+            .any(|line| line.text == "compiling" && line.task.as_deref() == Some("build")));
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_finished_run_is_recorded_and_reopened() {
+        let dir =
+            std::env::temp_dir().join(format!("mise-sequencer-app-history-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let (tx, _rx) = mpsc::unbounded_channel();
+        let mut app = App::new(tx).with_run_history(RunHistory::new(&dir, "/project"));
+
+        app.task_running = true;
+        app.begin_task_recording("build", &[]);
+        app.push_task_output(OutputLine::stdout("compiling").with_task("build"));
+        app.handle_tab_scoped_event(AppEvent::TaskCompleted(task_result(
+            "build",
+            TaskExit::Code(2),
+        )))
+        .await
+        .unwrap();
+        assert!(app.run_recording.is_none());
+
+        press(&mut app, KeyCode::Char('H')).await;
+        assert_eq!(app.state, AppState::History);
+        let runs = app.visible_history_runs();
+        assert_eq!(runs.len(), 1);
+        assert_eq!(runs[0].command, "mise run build");
+        assert!(!runs[0].success());
+
+        // Filter on the status, then clear the filter with an empty answer
+        press(&mut app, KeyCode::Char('/')).await;
+        for c in "ok".chars() {
+            press(&mut app, KeyCode::Char(c)).await;
+        }
+        press(&mut app, KeyCode::Enter).await;
+        assert!(app.visible_history_runs().is_empty());
+        press(&mut app, KeyCode::Char('/')).await;
+        press(&mut app, KeyCode::Backspace).await;
+        press(&mut app, KeyCode::Backspace).await;
+        press(&mut app, KeyCode::Enter).await;
+        assert_eq!(app.visible_history_runs().len(), 1);
+
+        press(&mut app, KeyCode::Enter).await;
+        assert_eq!(app.state, AppState::SequenceBuilder);
+        let output: Vec<_> = app.task_output.iter().collect();
+        assert_eq!(output[1].text, "compiling");
+        assert_eq!(output[1].source, LineSource::Stdout);
+        assert_eq!(output[1].task.as_deref(), Some("build"));
+        assert_eq!(
+            output[2].text,
+            "✗ build failed in 1.5s (exit code 2, 3 lines)"
+        );
+        assert_eq!(output[2].source, LineSource::Notice);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_save_sequence_through_prompt() {
+        let (mut app, path) = app_with_temp_store("prompt");
+        app.sequence_state.set_task_step("build", 0, true);
+
+        press(&mut app, KeyCode::Char('s')).await;
+        assert!(app.prompt.is_some());
+        for c in "ci".chars() {
+            press(&mut app, KeyCode::Char(c)).await;
+        }
+        press(&mut app, KeyCode::Enter).await;
+
+        assert!(app.prompt.is_none());
+        assert_eq!(app.current_sequence_name, Some("ci".to_string()));
+        let reloaded = SequenceStore::load(&path).unwrap();
+        assert_eq!(reloaded.get("ci").unwrap().steps[0].tasks, vec!["build"]);
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn test_add_as_task_prompts_for_name() {
+        let mut app = create_test_app();
+        app.current_sequence_name = Some("release".to_string());
+
+        app.handle_sequence_event(SequenceEvent::AddAsTask)
+            .await
+            .unwrap();
+        assert!(app.prompt.is_none());
+        assert_eq!(
+            app.task_output.back().unwrap().text,
+            "No tasks enabled in sequence. Enable some tasks first!"
+        );
+
+        app.sequence_state.set_task_step("build", 0, true);
+        app.handle_sequence_event(SequenceEvent::AddAsTask)
+            .await
+            .unwrap();
+        let prompt = app.prompt.as_ref().unwrap();
+        assert_eq!(prompt.kind, PromptKind::AddAsTask);
+        assert_eq!(prompt.value(), "release");
+    }
+
+    #[tokio::test]
+    async fn test_open_task_in_builder_and_write_back() {
+        let mut app = create_test_app();
+        let mut task_info = MiseTaskInfo {
+            name: "release".to_string(),
+            aliases: Vec::new(),
+            description: String::new(),
+            source: "mise.toml".to_string(),
+            depends: vec!["lint".to_string()],
+            depends_post: Vec::new(),
+            wait_for: Vec::new(),
+            env: Vec::new(),
+            dir: None,
+            hide: false,
+            raw: false,
+            sources: Vec::new(),
+            outputs: Vec::new(),
+            shell: None,
+            quiet: false,
+            silent: false,
+            tools: std::collections::HashMap::new(),
+            run: vec![RunEntry::Task {
+                task: "deploy".to_string(),
+            }],
+            file: None,
+            usage_spec: serde_json::Value::Null,
+        };
+
+        app.open_task_info_in_builder(&task_info);
+        assert_eq!(app.sequence_state.num_steps, 2);
+        assert!(app.sequence_state.is_task_enabled_for_step("lint", 0));
+        assert!(app.sequence_state.is_task_enabled_for_step("deploy", 1));
+        assert_eq!(app.source_task_name, Some("release".to_string()));
+
+        // "Add as task" offers to overwrite the task the sequence came from
+        app.handle_sequence_event(SequenceEvent::AddAsTask)
+            .await
+            .unwrap();
+        assert_eq!(app.prompt.as_ref().unwrap().value(), "release");
+
+        // Tasks that run their own scripts leave the builder alone
+        app.prompt = None;
+        task_info.name = "script".to_string();
+        task_info.run = vec![RunEntry::Script("cargo build".to_string())];
+        app.open_task_info_in_builder(&task_info);
+        assert_eq!(app.source_task_name, Some("release".to_string()));
+        assert!(app
+            .task_output
+            .back()
+            .unwrap()
+            .text
+            .starts_with("Cannot open task in the builder"));
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_prompt_saves_nothing() {
+        let (mut app, path) = app_with_temp_store("cancel");
+
+        press(&mut app, KeyCode::Char('s')).await;
+        press(&mut app, KeyCode::Char('x')).await;
+        press(&mut app, KeyCode::Esc).await;
+
+        assert!(app.prompt.is_none());
+        assert!(app.sequence_store.sequences.is_empty());
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_load_saved_sequence_replaces_builder_state() {
+        let (mut app, path) = app_with_temp_store("load");
+        app.sequence_state.add_step();
+        app.sequence_state.set_task_step("deploy", 3, true);
+        app.sequence_state.step_policies[3] = StepFailurePolicy::Continue;
+        app.save_sequence_as("release");
+
+        app.sequence_state = SequenceState::new(DEFAULT_NUM_STEPS);
+        app.current_sequence_name = None;
+        app.selected_step = 2;
+        app.open_saved_sequences();
+        assert_eq!(app.state, AppState::Sequences);
+        app.load_selected_saved_sequence();
+
+        assert_eq!(app.state, AppState::SequenceBuilder);
+        assert_eq!(app.current_sequence_name, Some("release".to_string()));
+        assert_eq!(app.selected_step, 0);
+        assert_eq!(app.sequence_state.num_steps, 4);
+        assert!(app.sequence_state.is_task_enabled_for_step("deploy", 3));
+        assert_eq!(
+            app.sequence_state.step_policy(3),
+            StepFailurePolicy::Continue
+        );
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_load_saved_sequence_warns_about_unknown_tasks() {
+        let (mut app, path) = app_with_temp_store("unknown");
+        app.tasks = vec![MiseTask::new("build".to_string(), "mise.toml".to_string())];
+        app.sequence_state.set_task_step("build", 0, true);
+        app.sequence_state.set_task_step("gone", 1, true);
+        app.sequence_state.set_task_step("old", 1, true);
+        app.sequence_state.set_task_step("gone", 2, true);
+        app.save_sequence_as("old");
+
+        app.open_saved_sequences();
+        app.load_selected_saved_sequence();
+
+        assert_eq!(
+            app.task_output.back().unwrap().text,
+            "Warning: sequence refers to unknown tasks: gone, old"
+        );
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn test_rename_and_delete_saved_sequence() {
+        let (mut app, path) = app_with_temp_store("manage");
+        app.save_sequence_as("first");
+        app.save_sequence_as("second");
+        app.open_saved_sequences();
+        assert_eq!(app.selected_saved_sequence, 1);
+
+        press(&mut app, KeyCode::Char('R')).await;
+        press(&mut app, KeyCode::Char('!')).await;
+        press(&mut app, KeyCode::Enter).await;
+        assert_eq!(app.current_sequence_name, Some("second!".to_string()));
+
+        press(&mut app, KeyCode::Char('D')).await;
+        assert_eq!(app.pending_delete_sequence, Some("second!".to_string()));
+        press(&mut app, KeyCode::Char('y')).await;
+
+        assert_eq!(app.current_sequence_name, None);
+        assert_eq!(app.selected_saved_sequence, 0);
+        let reloaded = SequenceStore::load(&path).unwrap();
+        let names: Vec<&str> = reloaded.sequences.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["first"]);
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn test_saved_sequences_keys_while_a_task_runs() {
+        let (mut app, path) = app_with_temp_store("running");
+        app.save_sequence_as("first");
+        app.open_saved_sequences();
+        app.task_running = true;
+        let cancel = app.new_run_cancel();
+
+        // Renaming does not get in the way of cancelling the run
+        press(&mut app, KeyCode::Char('R')).await;
+        assert!(app.prompt.is_some());
+        assert!(!*cancel.borrow());
+        press(&mut app, KeyCode::Esc).await;
+
+        press(&mut app, KeyCode::Char('c')).await;
+        assert!(app.prompt.is_none());
+        assert!(*cancel.borrow());
+        let _ = std::fs::remove_file(&path);
+    }
+
     #[test]
     fn test_poll_task_output_no_receiver() {
         let mut app = create_test_app();
@@ -471,20 +1360,22 @@ mod tests {
         let mut app = create_test_app();
 
         // Simulate colored STDOUT/STDERR output that could cause bleeding
-        let colored_stdout = "STDOUT: \x1b[32mSuccess message";
-        let colored_stderr = "STDERR: \x1b[31mError message";
+        let colored_stdout = "\x1b[32mSuccess message";
+        let colored_stderr = "\x1b[31mError message";
         let plain_text = "Plain text line";
 
         // Add the output lines directly to simulate the event handling
-        app.task_output.push_back(colored_stdout.to_string());
-        app.task_output.push_back(colored_stderr.to_string());
-        app.task_output.push_back(plain_text.to_string());
+        app.task_output
+            .push_back(OutputLine::stdout(colored_stdout));
+        app.task_output
+            .push_back(OutputLine::stderr(colored_stderr));
+        app.task_output.push_notice(plain_text);
 
         // Verify the lines were added
         assert_eq!(app.task_output.len(), 3);
-        assert_eq!(app.task_output[0], colored_stdout);
-        assert_eq!(app.task_output[1], colored_stderr);
-        assert_eq!(app.task_output[2], plain_text);
+        assert_eq!(app.task_output.get(0).unwrap().text, colored_stdout);
+        assert_eq!(app.task_output.get(1).unwrap().text, colored_stderr);
+        assert_eq!(app.task_output.get(2).unwrap().text, plain_text);
 
         // The actual bleeding prevention is tested in the UI layer
         // through the ensure_ansi_reset function tests
@@ -496,20 +1387,20 @@ mod tests {
 
         // Simulate a sequence of colored lines like what might come from a build process
         let lines = vec![
-            "STDOUT: \x1b[36mBuilding project...",
-            "STDOUT: \x1b[32m✓ Compiled successfully",
-            "STDERR: \x1b[33mWarning: deprecated function",
-            "STDOUT: \x1b[32m✓ Tests passed",
-            "Plain summary line",
+            OutputLine::stdout("\x1b[36mBuilding project..."),
+            OutputLine::stdout("\x1b[32m✓ Compiled successfully"),
+            OutputLine::stderr("\x1b[33mWarning: deprecated function"),
+            OutputLine::stdout("\x1b[32m✓ Tests passed"),
+            OutputLine::notice("Plain summary line"),
         ];
 
         for line in &lines {
-            app.task_output.push_back(line.to_string());
+            app.task_output.push_back(line.clone());
         }
 
         assert_eq!(app.task_output.len(), 5);
         for (i, expected_line) in lines.iter().enumerate() {
-            assert_eq!(app.task_output[i], *expected_line);
+            assert_eq!(app.task_output.get(i).unwrap().as_ref(), expected_line);
         }
     }
 }
@@ -0,0 +1,96 @@
+use std::time::SystemTime;
+
+use super::App;
+use crate::models::notification::Notification;
+use crate::models::task_run::result_badge;
+
+impl App {
+    /// The notification for the end of this tab's run, unless it was too quick to need one.
+    /// Called before the run is finished, while it is still being recorded
+    pub fn run_notification(&self, success: bool, message: String) -> Option<Notification> {
+        let record = &self.run_recording.as_ref()?.record;
+        let elapsed = SystemTime::now()
+            .duration_since(record.started_at)
+            .unwrap_or_default();
+        if !self.notify.should_notify(elapsed) {
+            return None;
+        }
+
+        let verb = if success { "finished" } else { "failed" };
+        // Unnamed sequences are recorded as "sequence"
+        let title = format!("{} {} {verb}", result_badge(success), record.name);
+        Some(Notification {
+            title,
+            message,
+            success,
+        })
+    }
+
+    /// Tell the user a long run finished, see `NotifyConfig`
+    pub fn notify_run_finished(&self, success: bool, message: String) {
+        if let Some(notification) = self.run_notification(success, message) {
+            self.notify.send(&notification);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::notification::NotifyConfig;
+    use std::time::Duration;
+    use tokio::sync::mpsc;
+
+    fn create_test_app() -> App {
+        let (tx, _rx) = mpsc::unbounded_channel();
+        let mut app = App::new(tx);
+        app.notify = NotifyConfig {
+            min_duration: Duration::from_secs(60),
+            ..NotifyConfig::default()
+        };
+        app
+    }
+
+    #[test]
+    fn test_long_task_is_notified() {
+        let mut app = create_test_app();
+        app.begin_task_recording("build", &[]);
+        assert_eq!(app.run_notification(true, "done".to_string()), None);
+
+        let recording = app.run_recording.as_mut().unwrap();
+        recording.record.started_at -= Duration::from_secs(90);
+        assert_eq!(
+            app.run_notification(false, "exit code 1".to_string()),
+            Some(Notification {
+                title: "✗ build failed".to_string(),
+                message: "exit code 1".to_string(),
+                success: false,
+            })
+        );
+    }
+
+    #[test]
+    fn test_sequence_title() {
+        let mut app = create_test_app();
+        app.notify.min_duration = Duration::ZERO;
+        app.begin_sequence_recording();
+        assert_eq!(
+            app.run_notification(true, String::new()).unwrap().title,
+            "✓ sequence finished"
+        );
+
+        app.current_sequence_name = Some("release".to_string());
+        app.begin_sequence_recording();
+        assert_eq!(
+            app.run_notification(true, String::new()).unwrap().title,
+            "✓ release finished"
+        );
+    }
+
+    #[test]
+    fn test_no_notification_without_run() {
+        let mut app = create_test_app();
+        app.notify.min_duration = Duration::ZERO;
+        assert_eq!(app.run_notification(true, String::new()), None);
+    }
+}
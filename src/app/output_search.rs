@@ -0,0 +1,162 @@
+use super::App;
+use crate::models::{OutputSearch, Prompt, PromptKind};
+
+impl App {
+    /// Open the search prompt over the output pane, starting from the previous pattern
+    pub fn start_output_search(&mut self) {
+        let pattern = self
+            .output_search
+            .as_ref()
+            .map(|search| search.pattern.clone())
+            .unwrap_or_default();
+        self.prompt = Some(Prompt::new(PromptKind::SearchOutput, &pattern));
+        self.output_search_origin = self.output_scroll_offset;
+        self.output_search_error = None;
+    }
+
+    pub fn is_searching_output(&self) -> bool {
+        self.prompt
+            .as_ref()
+            .is_some_and(|prompt| prompt.kind == PromptKind::SearchOutput)
+    }
+
+    /// Search for the pattern typed so far, showing the first match from where the
+    /// search started. An invalid pattern keeps the previous results until it is fixed
+    pub fn update_output_search(&mut self) {
+        let Some(prompt) = &self.prompt else {
+            return;
+        };
+        let pattern = prompt.input.value().to_string();
+        if pattern.is_empty() {
+            self.output_search = None;
+            self.output_search_error = None;
+            self.output_scroll_offset = self.output_search_origin;
+            return;
+        }
+
+        match OutputSearch::new(&pattern) {
+            Ok(mut search) => {
+                search.update(&self.task_output);
+                let found = search.jump_from(&self.task_output, self.output_search_origin);
+                self.output_search = Some(search);
+                self.output_search_error = None;
+                match found {
+                    Some(index) => self.scroll_output_to_line(index),
+                    None => self.output_scroll_offset = self.output_search_origin,
+                }
+            }
+            Err(e) => {
+                // The last line of a regex error says what is wrong, e.g. "unclosed group"
+                let reason = e.to_string();
+                let reason = reason.lines().last().unwrap_or_default();
+                self.output_search_error = Some(reason.trim_start_matches("error: ").to_string());
+            }
+        }
+    }
+
+    /// Close the search prompt, going back to where the search started
+    pub fn cancel_output_search(&mut self) {
+        self.prompt = None;
+        self.clear_output_search();
+        self.output_scroll_offset = self.output_search_origin;
+    }
+
+    pub fn clear_output_search(&mut self) {
+        self.output_search = None;
+        self.output_search_error = None;
+    }
+
+    /// Show the next match of the search, or the previous one with `forward` unset
+    pub fn jump_to_search_match(&mut self, forward: bool) {
+        let Some(search) = &mut self.output_search else {
+            return;
+        };
+        search.update(&self.task_output);
+        if let Some(index) = search.jump_next(&self.task_output, forward) {
+            self.scroll_output_to_line(index);
+        }
+    }
+
+    /// Scroll the output so the line at `index` is in the middle of the pane, which stops
+    /// following new output. With only stderr shown, the next stderr line is used
+    pub fn scroll_output_to_line(&mut self, index: usize) {
+        self.output_follow_mode = false;
+        let visible_height = self.current_output_visible_height;
+        let max_scroll = self.output_view_len().saturating_sub(visible_height);
+        let position = self.output_view_position(index);
+        self.output_scroll_offset = position.saturating_sub(visible_height / 2).min(max_scroll);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::OutputLine;
+    use tokio::sync::mpsc;
+    use tui_input::Input;
+
+    fn create_test_app() -> App {
+        let (tx, _rx) = mpsc::unbounded_channel();
+        let mut app = App::new(tx);
+        app.show_output_pane = true;
+        app.current_output_visible_height = 4;
+        for i in 0..20 {
+            let line = if i % 5 == 3 {
+                OutputLine::stderr(format!("\x1b[31merror\x1b[0m {i}"))
+            } else {
+                OutputLine::stdout(format!("line {i}"))
+            };
+            app.push_task_output(line);
+        }
+        app
+    }
+
+    fn type_pattern(app: &mut App, pattern: &str) {
+        app.prompt.as_mut().unwrap().input = Input::new(pattern.to_string());
+        app.update_output_search();
+    }
+
+    #[test]
+    fn test_search_jumps_to_matches() {
+        let mut app = create_test_app();
+        app.output_scroll_offset = 5;
+        app.start_output_search();
+
+        type_pattern(&mut app, "ERR");
+        assert_eq!(app.output_search.as_ref().unwrap().match_count(), 0);
+        assert_eq!(app.output_scroll_offset, 5);
+
+        // Matching starts from where the search was opened
+        type_pattern(&mut app, "error");
+        assert_eq!(app.output_search.as_ref().unwrap().status(), "2/4");
+        assert_eq!(app.output_scroll_offset, 6);
+        assert!(!app.output_follow_mode);
+
+        app.prompt = None;
+        app.jump_to_search_match(true);
+        assert_eq!(app.output_scroll_offset, 11);
+        app.jump_to_search_match(true);
+        app.jump_to_search_match(true);
+        assert_eq!(app.output_search.as_ref().unwrap().status(), "1/4");
+        app.jump_to_search_match(false);
+        assert_eq!(app.output_scroll_offset, 16);
+    }
+
+    #[test]
+    fn test_invalid_pattern_and_cancel() {
+        let mut app = create_test_app();
+        app.output_scroll_offset = 2;
+        app.start_output_search();
+        assert!(app.is_searching_output());
+
+        type_pattern(&mut app, "line 1");
+        type_pattern(&mut app, "line (");
+        assert_eq!(app.output_search_error.as_deref(), Some("unclosed group"));
+        assert_eq!(app.output_search.as_ref().unwrap().pattern, "line 1");
+
+        app.cancel_output_search();
+        assert!(app.output_search.is_none());
+        assert!(app.prompt.is_none());
+        assert_eq!(app.output_scroll_offset, 2);
+    }
+}
@@ -0,0 +1,116 @@
+use std::borrow::Cow;
+use std::ops::Range;
+
+use super::App;
+use crate::models::{LineSource, OutputLine};
+
+impl App {
+    /// Whether the output pane shows `line` with the current filter
+    pub fn is_shown_in_output(&self, line: &OutputLine) -> bool {
+        !self.output_stderr_only || line.source == LineSource::Stderr
+    }
+
+    /// Number of lines the output pane can scroll through
+    pub fn output_view_len(&self) -> usize {
+        if self.output_stderr_only {
+            self.task_output.stderr_len()
+        } else {
+            self.task_output.len()
+        }
+    }
+
+    /// Lines at `range` of the output pane, each with its index in the output
+    pub fn output_view_lines(&self, range: Range<usize>) -> Vec<(usize, Cow<'_, OutputLine>)> {
+        if !self.output_stderr_only {
+            let start = range.start;
+            return (start..).zip(self.task_output.lines(range)).collect();
+        }
+        range
+            .filter_map(|position| self.task_output.stderr_index(position))
+            .filter_map(|index| Some((index, self.task_output.get(index)?)))
+            .collect()
+    }
+
+    /// Position in the output pane of the line at `index`, or of the next line shown
+    pub fn output_view_position(&self, index: usize) -> usize {
+        if self.output_stderr_only {
+            self.task_output.stderr_position(index)
+        } else {
+            index
+        }
+    }
+
+    /// Switch between showing all output and only the stderr lines, following the end
+    pub fn toggle_stderr_only(&mut self) {
+        self.output_stderr_only = !self.output_stderr_only;
+        self.output_follow_mode = true;
+        self.auto_scroll_output_to_bottom();
+    }
+
+    pub fn toggle_output_timestamps(&mut self) {
+        self.output_timestamps = !self.output_timestamps;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::sync::mpsc;
+
+    fn create_test_app() -> App {
+        let (tx, _rx) = mpsc::unbounded_channel();
+        let mut app = App::new(tx);
+        app.show_output_pane = true;
+        app.current_output_visible_height = 2;
+        for i in 0..6 {
+            let line = if i % 3 == 0 {
+                OutputLine::stderr(format!("error {i}"))
+            } else {
+                OutputLine::stdout(format!("line {i}"))
+            };
+            app.push_task_output(line);
+        }
+        app
+    }
+
+    fn view_texts(app: &App) -> Vec<(usize, String)> {
+        app.output_view_lines(0..app.output_view_len())
+            .into_iter()
+            .map(|(index, line)| (index, line.text.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn test_stderr_only_view() {
+        let mut app = create_test_app();
+        assert_eq!(app.output_view_len(), 6);
+
+        app.toggle_stderr_only();
+        assert_eq!(
+            view_texts(&app),
+            vec![(0, "error 0".to_string()), (3, "error 3".to_string())]
+        );
+        assert_eq!(app.output_view_position(2), 1);
+        assert_eq!(app.output_scroll_offset, 0);
+
+        app.push_task_output(OutputLine::stdout("line 6"));
+        app.push_task_output(OutputLine::stderr("error 7"));
+        app.scroll_output_to_bottom();
+        assert_eq!(app.output_scroll_offset, 1);
+        assert_eq!(app.output_view_lines(1..3)[1].1.text, "error 7");
+
+        app.toggle_stderr_only();
+        assert_eq!(app.output_view_len(), 8);
+    }
+
+    #[test]
+    fn test_lines_are_marked_with_running_step() {
+        let mut app = create_test_app();
+        app.sequence_state.is_running = true;
+        app.sequence_state.current_step = Some(1);
+        app.push_task_output(OutputLine::stdout("built"));
+
+        assert_eq!(app.task_output.back().unwrap().step, Some(1));
+        assert_eq!(app.task_output.get(0).unwrap().step, None);
+    }
+}
@@ -0,0 +1,100 @@
+use tokio::sync::mpsc;
+
+use super::App;
+use crate::mise::MiseClient;
+use crate::models::ResourceUsage;
+
+impl App {
+    /// A client for a new run in this tab, whose tasks report their CPU and memory use to
+    /// the output pane
+    pub fn run_client(&mut self) -> MiseClient {
+        let (usage_tx, usage_rx) = mpsc::unbounded_channel();
+        self.task_usage_rx = Some(usage_rx);
+        self.task_usage.clear();
+        self.client.with_usage_reporting(usage_tx)
+    }
+
+    /// Keep the latest usage each running task reported
+    pub fn drain_task_usage(&mut self) {
+        while let Some(sample) = self
+            .task_usage_rx
+            .as_mut()
+            .and_then(|rx| rx.try_recv().ok())
+        {
+            self.task_usage.insert(sample.task_name, sample.usage);
+        }
+    }
+
+    /// Stop showing usage, once the run is over
+    pub fn clear_task_usage(&mut self) {
+        self.task_usage_rx = None;
+        self.task_usage.clear();
+    }
+
+    /// Usage of every task running in this tab together, once one has been sampled
+    pub fn running_usage(&self) -> Option<ResourceUsage> {
+        if self.task_usage.is_empty() {
+            return None;
+        }
+        Some(ResourceUsage::sum(self.task_usage.values().copied()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{TaskExit, TaskResult, UsageSample};
+    use std::time::SystemTime;
+
+    fn usage(cpu_percent: f64, children: usize) -> ResourceUsage {
+        ResourceUsage {
+            cpu_percent,
+            rss_bytes: 1024,
+            children,
+        }
+    }
+
+    #[test]
+    fn test_usage_of_parallel_tasks_adds_up() {
+        let (tx, _rx) = mpsc::unbounded_channel();
+        let mut app = App::new(tx);
+        app.task_usage_rx = Some({
+            let (usage_tx, usage_rx) = mpsc::unbounded_channel();
+            for (task_name, usage) in [
+                ("build", usage(10.0, 1)),
+                ("lint", usage(30.0, 2)),
+                ("build", usage(50.0, 3)),
+            ] {
+                let sample = UsageSample {
+                    task_name: task_name.to_string(),
+                    usage,
+                };
+                usage_tx.send(sample).unwrap();
+            }
+            usage_rx
+        });
+        assert_eq!(app.running_usage(), None);
+
+        app.drain_task_usage();
+        let total = app.running_usage().unwrap();
+        assert_eq!(total.cpu_percent, 80.0);
+        assert_eq!(total.children, 5);
+
+        // A finished task no longer counts
+        let now = SystemTime::now();
+        app.record_task_results(&[TaskResult {
+            task_name: "lint".to_string(),
+            exit: TaskExit::Code(0),
+            started_at: now,
+            finished_at: now,
+            stdout_lines: 0,
+            stderr_lines: 0,
+            peak_usage: Some(usage(30.0, 2)),
+        }]);
+        assert_eq!(app.running_usage(), Some(usage(50.0, 3)));
+
+        app.finish_run();
+        assert_eq!(app.running_usage(), None);
+        assert!(app.task_usage_rx.is_none());
+    }
+}
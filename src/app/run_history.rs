@@ -0,0 +1,174 @@
+use anyhow::Result;
+
+use super::App;
+use crate::models::run_history::RunKind;
+use crate::models::task_run::{format_elapsed, result_badge};
+use crate::models::{AppState, Prompt, PromptKind, RunRecord, SavedSequence, SequenceEvent};
+
+impl App {
+    /// Re-read the history from disk, runs from other instances included
+    pub fn load_run_history(&mut self) {
+        match self.run_history.reload() {
+            Ok(history) => self.run_history = history,
+            Err(e) => {
+                self.task_output
+                    .push_notice(format!("Failed to load run history: {e:#}"));
+                self.show_output_pane = true;
+            }
+        }
+    }
+
+    pub fn open_run_history(&mut self) {
+        self.load_run_history();
+        self.state = AppState::History;
+        self.button_hover_state = None;
+        self.selected_history_run = 0;
+    }
+
+    pub fn close_run_history(&mut self) {
+        self.state = AppState::SequenceBuilder;
+    }
+
+    /// Runs shown in the history view, newest first
+    pub fn visible_history_runs(&self) -> Vec<&RunRecord> {
+        self.run_history.filtered(&self.history_filter)
+    }
+
+    fn selected_history_run(&self) -> Option<RunRecord> {
+        self.visible_history_runs()
+            .get(self.selected_history_run)
+            .map(|run| (*run).clone())
+    }
+
+    pub fn select_next_history_run(&mut self) {
+        let count = self.visible_history_runs().len();
+        if count > 0 {
+            self.selected_history_run = (self.selected_history_run + 1) % count;
+        }
+    }
+
+    pub fn select_previous_history_run(&mut self) {
+        let count = self.visible_history_runs().len();
+        if count > 0 {
+            self.selected_history_run = (self.selected_history_run + count - 1) % count;
+        }
+    }
+
+    pub fn start_filter_history(&mut self) {
+        self.prompt = Some(Prompt::new(PromptKind::FilterHistory, &self.history_filter));
+    }
+
+    pub fn set_history_filter(&mut self, filter: &str) {
+        self.history_filter = filter.to_string();
+        self.selected_history_run = 0;
+    }
+
+    /// Show the captured output of the selected run in the output pane, in a new tab
+    /// if the current one is busy
+    pub fn open_selected_run_output(&mut self) {
+        let Some(run) = self.selected_history_run() else {
+            return;
+        };
+        let log = match self.run_history.read_log(&run) {
+            Ok(log) => log,
+            Err(e) => {
+                self.task_output.push_notice(format!(
+                    "Failed to open the output of '{}': {e:#}",
+                    run.name
+                ));
+                self.show_output_pane = true;
+                return;
+            }
+        };
+
+        if self.is_any_task_running() {
+            self.open_new_tab();
+        }
+        self.close_run_history();
+        self.task_output.clear();
+        self.task_output_rx = None;
+        self.output_scroll_offset = 0;
+        self.output_follow_mode = true;
+        self.show_output_pane = true;
+
+        self.task_output.push_notice(format!(
+            "{} Output of '{}' ({}), took {}",
+            result_badge(run.success()),
+            run.name,
+            run.command,
+            format_elapsed(run.elapsed())
+        ));
+        for line in log {
+            self.push_task_output(line);
+        }
+    }
+
+    /// Run the selected task or sequence again, in a new tab if the current one is busy
+    pub async fn rerun_selected_run(&mut self) -> Result<()> {
+        let Some(run) = self.selected_history_run() else {
+            return Ok(());
+        };
+
+        if self.is_any_task_running() {
+            self.open_new_tab();
+        }
+        self.close_run_history();
+
+        match run.kind {
+            RunKind::Task => {
+                if let Some(index) = self.tasks.iter().position(|task| task.name == run.name) {
+                    self.selected_task = index;
+                }
+                let args = run
+                    .steps
+                    .first()
+                    .and_then(|step| step.args.get(&run.name))
+                    .cloned()
+                    .unwrap_or_default();
+                self.run_task_by_name(&run.name, args).await?;
+            }
+            RunKind::Sequence => {
+                self.sequence_state = run.to_sequence().to_state();
+                self.current_sequence_name = None;
+                self.source_task_name = None;
+                self.selected_step = 0;
+                self.step_scroll_offset = 0;
+                self.handle_sequence_event(SequenceEvent::RunSequence)
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Start capturing a run of a single task for the history
+    pub fn begin_task_recording(&mut self, task_name: &str, args: &[String]) {
+        let record = RunRecord::task(task_name, args, self.run_history.project_dir());
+        self.run_recording = Some(self.run_history.start_recording(record));
+    }
+
+    /// Start capturing a run of the current sequence for the history
+    pub fn begin_sequence_recording(&mut self) {
+        let name = self
+            .current_sequence_name
+            .as_deref()
+            .or(self.source_task_name.as_deref())
+            .unwrap_or_default();
+        let record = RunRecord::sequence(
+            SavedSequence::from_state(name, &self.sequence_state),
+            &self.sequence_state.summary(),
+            self.run_history.project_dir(),
+        );
+        self.run_recording = Some(self.run_history.start_recording(record));
+    }
+
+    /// Write the finished run of this tab to the history
+    pub fn finish_recording(&mut self) {
+        let Some(recording) = self.run_recording.take() else {
+            return;
+        };
+        if let Err(e) = self.run_history.record(recording.finish()) {
+            self.task_output
+                .push_notice(format!("Failed to record run in history: {e:#}"));
+        }
+    }
+}
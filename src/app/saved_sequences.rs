@@ -0,0 +1,211 @@
+use anyhow::Result;
+use std::collections::BTreeSet;
+
+use super::App;
+use crate::models::{AppState, Prompt, PromptKind, SavedSequence, SequenceStore};
+
+impl App {
+    /// Read the saved sequences from the store's file, reporting problems in the output pane
+    pub fn load_saved_sequences(&mut self) {
+        match SequenceStore::load(self.sequence_store.path()) {
+            Ok(store) => self.sequence_store = store,
+            Err(e) => {
+                self.task_output
+                    .push_notice(format!("Failed to load saved sequences: {e:#}"));
+                self.show_output_pane = true;
+            }
+        }
+    }
+
+    pub fn open_saved_sequences(&mut self) {
+        self.state = AppState::Sequences;
+        self.button_hover_state = None;
+        self.selected_saved_sequence = self
+            .current_sequence_name
+            .as_ref()
+            .and_then(|name| {
+                self.sequence_store
+                    .sequences
+                    .iter()
+                    .position(|sequence| &sequence.name == name)
+            })
+            .unwrap_or(0);
+    }
+
+    pub fn close_saved_sequences(&mut self) {
+        self.state = AppState::SequenceBuilder;
+        self.pending_delete_sequence = None;
+    }
+
+    pub fn select_next_saved_sequence(&mut self) {
+        let count = self.sequence_store.sequences.len();
+        if count > 0 {
+            self.selected_saved_sequence = (self.selected_saved_sequence + 1) % count;
+        }
+    }
+
+    pub fn select_previous_saved_sequence(&mut self) {
+        let count = self.sequence_store.sequences.len();
+        if count > 0 {
+            self.selected_saved_sequence = (self.selected_saved_sequence + count - 1) % count;
+        }
+    }
+
+    fn selected_saved_sequence_name(&self) -> Option<String> {
+        self.sequence_store
+            .sequences
+            .get(self.selected_saved_sequence)
+            .map(|sequence| sequence.name.clone())
+    }
+
+    /// Ask for a name to save the current sequence under
+    pub fn start_save_sequence(&mut self) {
+        let name = self.current_sequence_name.clone().unwrap_or_default();
+        self.prompt = Some(Prompt::new(PromptKind::SaveSequence, &name));
+    }
+
+    pub fn start_rename_saved_sequence(&mut self) {
+        if let Some(name) = self.selected_saved_sequence_name() {
+            self.prompt = Some(Prompt::new(PromptKind::RenameSequence(name.clone()), &name));
+        }
+    }
+
+    pub async fn submit_prompt(&mut self) -> Result<()> {
+        let Some(prompt) = self.prompt.take() else {
+            return Ok(());
+        };
+        let value = prompt.value().to_string();
+        // Clearing the filter or the search are the answers that may be empty
+        let may_be_empty = matches!(
+            prompt.kind,
+            PromptKind::FilterHistory | PromptKind::SearchOutput
+        );
+        if value.is_empty() && !may_be_empty {
+            return Ok(());
+        }
+
+        match prompt.kind {
+            PromptKind::SaveSequence => self.save_sequence_as(&value),
+            PromptKind::RenameSequence(old_name) => self.rename_saved_sequence(&old_name, &value),
+            PromptKind::AddAsTask => self.add_sequence_as_task(&value).await?,
+            PromptKind::FilterHistory => self.set_history_filter(&value),
+            // The search is already applied while typing
+            PromptKind::SearchOutput => self.output_search_error = None,
+        }
+        Ok(())
+    }
+
+    pub fn cancel_prompt(&mut self) {
+        if self.is_searching_output() {
+            self.cancel_output_search();
+        }
+        self.prompt = None;
+    }
+
+    /// Save the current step assignments and policies under `name`, overwriting any
+    /// sequence already saved with that name
+    pub fn save_sequence_as(&mut self, name: &str) {
+        let saved = SavedSequence::from_state(name, &self.sequence_state);
+        match self.sequence_store.upsert(saved) {
+            Ok(()) => {
+                self.current_sequence_name = Some(name.to_string());
+                self.task_output.push_notice(format!(
+                    "Saved sequence '{name}' to {}",
+                    self.sequence_store.path().display()
+                ));
+            }
+            Err(e) => {
+                self.task_output
+                    .push_notice(format!("Failed to save sequence '{name}': {e:#}"));
+            }
+        }
+        self.show_output_pane = true;
+    }
+
+    /// Replace the sequence in the builder with the selected saved sequence
+    pub fn load_selected_saved_sequence(&mut self) {
+        if self.is_any_task_running() {
+            return;
+        }
+        let Some(saved) = self
+            .sequence_store
+            .sequences
+            .get(self.selected_saved_sequence)
+            .cloned()
+        else {
+            return;
+        };
+
+        self.sequence_state = saved.to_state();
+        self.current_sequence_name = Some(saved.name.clone());
+        self.source_task_name = None;
+        self.selected_step = 0;
+        self.step_scroll_offset = 0;
+        self.close_saved_sequences();
+
+        self.task_output
+            .push_notice(format!("Loaded sequence '{}'", saved.name));
+
+        // Tasks may have been renamed or removed since the sequence was saved
+        if !self.tasks.is_empty() {
+            let unknown_tasks: BTreeSet<&str> = saved
+                .task_names()
+                .into_iter()
+                .filter(|name| !self.tasks.iter().any(|task| task.name == *name))
+                .collect();
+            if !unknown_tasks.is_empty() {
+                self.task_output.push_notice(format!(
+                    "Warning: sequence refers to unknown tasks: {}",
+                    unknown_tasks.into_iter().collect::<Vec<_>>().join(", ")
+                ));
+            }
+        }
+        self.show_output_pane = true;
+    }
+
+    pub fn rename_saved_sequence(&mut self, old_name: &str, new_name: &str) {
+        match self.sequence_store.rename(old_name, new_name) {
+            Ok(()) => {
+                if self.current_sequence_name.as_deref() == Some(old_name) {
+                    self.current_sequence_name = Some(new_name.to_string());
+                }
+                self.task_output
+                    .push_notice(format!("Sequence '{old_name}' renamed to '{new_name}'"));
+            }
+            Err(e) => {
+                self.task_output
+                    .push_notice(format!("Failed to rename sequence '{old_name}': {e:#}"));
+            }
+        }
+        self.show_output_pane = true;
+    }
+
+    /// Ask for confirmation before deleting the selected saved sequence
+    pub fn request_delete_saved_sequence(&mut self) {
+        self.pending_delete_sequence = self.selected_saved_sequence_name();
+    }
+
+    pub fn confirm_delete_saved_sequence(&mut self) {
+        let Some(name) = self.pending_delete_sequence.take() else {
+            return;
+        };
+
+        match self.sequence_store.delete(&name) {
+            Ok(()) => {
+                if self.current_sequence_name.as_deref() == Some(name.as_str()) {
+                    self.current_sequence_name = None;
+                }
+                let count = self.sequence_store.sequences.len();
+                self.selected_saved_sequence =
+                    self.selected_saved_sequence.min(count.saturating_sub(1));
+                self.task_output
+                    .push_notice(format!("Deleted sequence '{name}'"));
+            }
+            Err(e) => {
+                self.task_output
+                    .push_notice(format!("Failed to delete sequence '{name}': {e:#}"));
+            }
+        }
+        self.show_output_pane = true;
+    }
+}
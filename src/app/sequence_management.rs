@@ -1,8 +1,49 @@
 use anyhow::Result;
-use tokio::sync::mpsc;
+use std::collections::HashMap;
+use std::process::Stdio;
+use tokio::sync::{mpsc, watch};
+use tokio::task::{Id, JoinSet};
 
+use super::terminal::TaskOutput;
 use super::App;
-use crate::models::{AppEvent, SequenceEvent};
+use crate::mise::MiseClient;
+use crate::models::editor::editor_command;
+use crate::models::task_args::ArgsTarget;
+use crate::models::task_run::{failure_reason, format_elapsed, result_badge, total_elapsed};
+use crate::models::{
+    AppEvent, MiseTaskInfo, OutputLine, Prompt, PromptKind, SequenceEvent, SequenceState,
+    StepFailureAction, TaskResult,
+};
+
+/// Run a task and tag each of its output lines with the task name,
+/// so output from tasks running in parallel can be told apart
+async fn run_tagged_task(
+    client: &MiseClient,
+    task_name: &str,
+    args: &[String],
+    output_tx: mpsc::UnboundedSender<OutputLine>,
+    cancel: watch::Receiver<bool>,
+) -> Result<TaskResult> {
+    let (task_output_tx, mut task_output_rx) = mpsc::unbounded_channel();
+
+    let forward_output = async {
+        while let Some(line) = task_output_rx.recv().await {
+            let line = OutputLine {
+                tagged: true,
+                ..line
+            };
+            if output_tx.send(line).is_err() {
+                break;
+            }
+        }
+    };
+
+    let (result, ()) = tokio::join!(
+        client.run_task(task_name, args, task_output_tx, None, cancel),
+        forward_output
+    );
+    result
+}
 
 impl App {
     pub async fn handle_sequence_event(&mut self, event: SequenceEvent) -> Result<()> {
@@ -19,34 +60,179 @@ impl App {
                 self.start_sequence_execution()?;
             }
             SequenceEvent::AddAsTask => {
-                self.add_sequence_as_task().await?;
+                self.start_add_sequence_as_task();
             }
             SequenceEvent::ClearSequence => {
                 self.sequence_state.clear_all();
             }
-            SequenceEvent::StepCompleted => {
-                if !self.sequence_state.advance_step() {
-                    let _ = self
-                        .event_tx
-                        .send(AppEvent::Sequence(SequenceEvent::SequenceCompleted));
-                } else {
-                    self.execute_current_step()?;
+            SequenceEvent::AddStep => {
+                self.sequence_state.add_step();
+                self.selected_step = self.sequence_state.num_steps - 1;
+                self.ensure_selected_step_visible();
+            }
+            SequenceEvent::InsertStep(index) => {
+                let num_steps = self.sequence_state.num_steps;
+                self.sequence_state.insert_step(index);
+                // The selection stays on the same step, which moved right
+                if self.sequence_state.num_steps > num_steps && index <= self.selected_step {
+                    self.selected_step += 1;
+                }
+                self.ensure_selected_step_visible();
+            }
+            SequenceEvent::RemoveStep(index) => {
+                if self.sequence_state.remove_step(index) {
+                    self.selected_step = self.selected_step.min(self.sequence_state.num_steps - 1);
+                    self.ensure_selected_step_visible();
+                }
+            }
+            SequenceEvent::CycleStepPolicy(step) => {
+                self.sequence_state.cycle_step_policy(step);
+            }
+            SequenceEvent::ToggleOnlyStale => {
+                self.sequence_state.only_stale = !self.sequence_state.only_stale;
+            }
+            SequenceEvent::StepFreshnessChecked { step, fresh } => {
+                self.handle_step_freshness(step, fresh)?
+            }
+            SequenceEvent::StepCompleted(results) => {
+                if self.sequence_state.current_step.is_none() {
+                    // Stopped while the step ran, so it must not go on or count as done
+                    self.finish_stopped_sequence(&results);
+                    return Ok(());
+                }
+                self.record_step_results(results);
+                self.sequence_state.record_step_success();
+                self.advance_sequence()?;
+            }
+            SequenceEvent::StepFailed(results) => {
+                let error = failure_reason(&results);
+                let Some(step) = self.sequence_state.current_step else {
+                    self.finish_stopped_sequence(&results);
+                    return Ok(());
+                };
+                let step_number = step + 1;
+                self.record_step_results(results);
+
+                match self.sequence_state.record_step_failure(&error) {
+                    StepFailureAction::Retry {
+                        attempt,
+                        max_attempts,
+                    } => {
+                        self.task_output.push_notice(format!(
+                            "Step {step_number} failed, retrying (attempt {attempt}/{max_attempts})"
+                        ));
+                        self.execute_current_step()?;
+                    }
+                    StepFailureAction::Continue => {
+                        self.task_output
+                            .push_notice(format!("Step {step_number} failed, continuing"));
+                        self.advance_sequence()?;
+                    }
+                    StepFailureAction::Cleanup(cleanup_step) => {
+                        self.task_output.push_notice(format!(
+                            "Step {step_number} failed, running cleanup step {}",
+                            cleanup_step + 1
+                        ));
+                        self.execute_current_step()?;
+                    }
+                    StepFailureAction::Abort => {
+                        let error = match self.sequence_state.pending_failure.take() {
+                            Some(original) => format!("{original} (cleanup failed: {error})"),
+                            None => format!("step {step_number}: {error}"),
+                        };
+                        self.send_tab_event(AppEvent::Sequence(SequenceEvent::SequenceFailed(
+                            error,
+                        )));
+                    }
                 }
             }
             SequenceEvent::SequenceCompleted => {
+                self.notify_run_finished(true, self.sequence_state.summary());
                 self.sequence_state.reset_execution();
-                self.task_running = false;
+                self.finish_run();
             }
             SequenceEvent::SequenceFailed(error) => {
                 self.task_output
-                    .push_back(format!("Sequence failed: {error}"));
+                    .push_notice(format!("Sequence failed: {error}"));
+                self.notify_run_finished(false, error);
                 self.sequence_state.reset_execution();
-                self.task_running = false;
+                self.finish_run();
             }
         }
         Ok(())
     }
 
+    /// Report finished tasks in the output pane and remember them for the matrix badges
+    pub fn record_task_results(&mut self, results: &[TaskResult]) {
+        // Output still queued in the channel belongs before the report
+        self.drain_task_output();
+        self.close_terminal();
+        if let Some(recording) = &mut self.run_recording {
+            recording.record.results.extend(results.iter().cloned());
+        }
+        for result in results {
+            self.task_usage.remove(&result.task_name);
+            self.push_task_output(OutputLine::notice(result.summary()));
+            self.last_task_results
+                .insert(result.task_name.clone(), result.clone());
+        }
+    }
+
+    /// Report the current step's tasks and its overall outcome and elapsed time
+    fn record_step_results(&mut self, results: Vec<TaskResult>) {
+        let Some(step) = self.sequence_state.current_step else {
+            return;
+        };
+        if !results.is_empty() {
+            self.record_task_results(&results);
+            self.push_task_output(OutputLine::notice(format!(
+                "{} Step {} took {}",
+                result_badge(results.iter().all(TaskResult::success)),
+                step + 1,
+                format_elapsed(total_elapsed(&results))
+            )));
+        }
+        self.sequence_state.record_step_results(results);
+    }
+
+    /// Clear the running state once a task or sequence is done and its processes have exited,
+    /// and record the run in the history
+    pub fn finish_run(&mut self) {
+        self.finish_recording();
+        self.clear_task_usage();
+        self.task_input = None;
+        self.task_running = false;
+        self.running_task_name = None;
+        self.running_task_handle = None;
+        self.running_task_cancel = None;
+        // The run may have rebuilt outputs, check them again on the next tick
+        self.freshness_checked_at = None;
+        self.resume_watch();
+    }
+
+    /// Move on after the current step finished: run the next step, or finish the sequence.
+    /// After a cleanup step the sequence ends with the failure that triggered the cleanup.
+    /// The last step of a stopped sequence has shut down
+    fn finish_stopped_sequence(&mut self, results: &[TaskResult]) {
+        if self.task_running {
+            self.record_task_results(results);
+            self.task_output
+                .push_notice("Sequence stopped by user".to_string());
+            self.finish_run();
+        }
+    }
+
+    fn advance_sequence(&mut self) -> Result<()> {
+        if let Some(error) = self.sequence_state.pending_failure.take() {
+            self.send_tab_event(AppEvent::Sequence(SequenceEvent::SequenceFailed(error)));
+        } else if !self.sequence_state.advance_step() {
+            self.send_tab_event(AppEvent::Sequence(SequenceEvent::SequenceCompleted));
+        } else {
+            self.execute_current_step()?;
+        }
+        Ok(())
+    }
+
     pub async fn toggle_current_task_step(&mut self, step: usize) -> Result<()> {
         if let Some(selected_task) = self.tasks.get(self.selected_task) {
             let task_name = selected_task.name.clone();
@@ -56,7 +242,42 @@ impl App {
         Ok(())
     }
 
-    fn start_sequence_execution(&mut self) -> Result<()> {
+    pub fn select_next_step(&mut self) {
+        if self.selected_step + 1 < self.sequence_state.num_steps {
+            self.selected_step += 1;
+        }
+        self.ensure_selected_step_visible();
+    }
+
+    pub fn select_previous_step(&mut self) {
+        self.selected_step = self.selected_step.saturating_sub(1);
+        self.ensure_selected_step_visible();
+    }
+
+    pub fn ensure_selected_step_visible(&mut self) {
+        let visible_steps = self.current_visible_steps.max(1);
+
+        if self.selected_step < self.step_scroll_offset {
+            self.step_scroll_offset = self.selected_step;
+        } else if self.selected_step >= self.step_scroll_offset + visible_steps {
+            self.step_scroll_offset = self.selected_step + 1 - visible_steps;
+        }
+
+        let max_offset = self.sequence_state.num_steps.saturating_sub(visible_steps);
+        self.step_scroll_offset = self.step_scroll_offset.min(max_offset);
+    }
+
+    pub fn scroll_steps_left(&mut self, steps: usize) {
+        self.step_scroll_offset = self.step_scroll_offset.saturating_sub(steps);
+    }
+
+    pub fn scroll_steps_right(&mut self, steps: usize) {
+        let visible_steps = self.current_visible_steps.max(1);
+        let max_offset = self.sequence_state.num_steps.saturating_sub(visible_steps);
+        self.step_scroll_offset = (self.step_scroll_offset + steps).min(max_offset);
+    }
+
+    pub fn start_sequence_execution(&mut self) -> Result<()> {
         if self.sequence_state.is_running || self.is_any_task_running() {
             return Ok(());
         }
@@ -66,6 +287,8 @@ impl App {
         self.show_output_pane = true;
         self.task_running = true;
         self.running_task_name = Some("sequence".to_string());
+        self.new_run_cancel();
+        self.begin_sequence_recording();
         self.execute_current_step()?;
         Ok(())
     }
@@ -76,62 +299,113 @@ impl App {
 
             if tasks_for_step.is_empty() {
                 // No tasks for this step, advance to next
-                let _ = self
-                    .event_tx
-                    .send(AppEvent::Sequence(SequenceEvent::StepCompleted));
+                self.send_tab_event(AppEvent::Sequence(SequenceEvent::StepCompleted(Vec::new())));
                 return Ok(());
             }
 
-            // Execute all tasks for this step sequentially
-            self.execute_tasks_for_step(tasks_for_step)?;
+            if self.sequence_state.only_stale {
+                // The step goes on once its freshness is known
+                self.check_step_freshness(current_step);
+                return Ok(());
+            }
+            self.run_step_tasks(current_step)?;
         }
         Ok(())
     }
 
-    fn execute_tasks_for_step(&mut self, tasks: Vec<String>) -> Result<()> {
-        let event_tx = self.event_tx.clone();
-        let client = self.client.clone();
+    /// Go on with `step` once its freshness is known: skip it when fresh, run it otherwise
+    fn handle_step_freshness(&mut self, step: usize, fresh: bool) -> Result<()> {
+        match self.sequence_state.current_step {
+            None => self.finish_stopped_sequence(&[]),
+            Some(current_step) if current_step != step => {}
+            Some(_) if fresh => {
+                self.task_output.push_notice(format!(
+                    "Step {} skipped, its outputs are up to date",
+                    step + 1
+                ));
+                self.send_tab_event(AppEvent::Sequence(SequenceEvent::StepCompleted(Vec::new())));
+            }
+            Some(_) => self.run_step_tasks(step)?,
+        }
+        Ok(())
+    }
 
-        // Create a channel for task output
-        let (output_tx, output_rx) = mpsc::unbounded_channel();
-        self.task_output_rx = Some(output_rx);
+    /// Execute all tasks of `step`, each with the arguments saved for it
+    fn run_step_tasks(&mut self, step: usize) -> Result<()> {
+        let runs = self
+            .sequence_state
+            .get_tasks_for_step(step)
+            .into_iter()
+            .map(|task_name| {
+                let args = self.sequence_state.task_args(&task_name, step).to_vec();
+                (task_name, args)
+            })
+            .collect();
+        self.execute_tasks_for_step(runs)
+    }
 
-        // Spawn task execution for all tasks in sequence
-        let handle = tokio::spawn(async move {
-            let mut all_success = true;
+    fn execute_tasks_for_step(&mut self, tasks: Vec<(String, Vec<String>)>) -> Result<()> {
+        let event_tx = self.event_tx.clone();
+        let tab_id = self.tab_id;
+        let client = self.run_client();
+        let cancel = match &self.running_task_cancel {
+            Some(cancel_tx) => cancel_tx.subscribe(),
+            None => self.new_run_cancel(),
+        };
+
+        // Tag output lines with the task name when several tasks share the step,
+        // only a task running alone can have the output pane as its terminal and keyboard
+        let tag_output = tasks.len() > 1;
+        let (output, mut input_rx) = match tasks.as_slice() {
+            [(task_name, _)] => (
+                self.open_task_output(task_name),
+                Some(self.open_task_input()),
+            ),
+            _ => {
+                self.task_input = None;
+                (self.open_line_output(), None)
+            }
+        };
 
-            for task_name in tasks {
-                match client.run_task(&task_name, &[], output_tx.clone()).await {
-                    Ok(_) => {
-                        // Task completed successfully
-                    }
-                    Err(e) => {
-                        if output_tx
-                            .send(format!("Task '{task_name}' failed: {e}"))
-                            .is_err()
-                        {
-                            eprintln!("Warning: Failed to send task failure message");
+        // Spawn all tasks of the step concurrently; the step finishes once every task has
+        let handle = tokio::spawn(async move {
+            let mut runs = JoinSet::new();
+            let mut task_names = HashMap::new();
+
+            for (task_name, args) in tasks {
+                let client = client.clone();
+                let output = output.clone();
+                let input_rx = input_rx.take();
+                let cancel = cancel.clone();
+                let name = task_name.clone();
+
+                let run = runs.spawn(async move {
+                    let result = match output {
+                        TaskOutput::Lines(output_tx) if tag_output => {
+                            run_tagged_task(&client, &task_name, &args, output_tx, cancel).await
                         }
-                        all_success = false;
-                        break;
-                    }
-                }
+                        output => {
+                            output
+                                .run(&client, &task_name, &args, input_rx, cancel)
+                                .await
+                        }
+                    };
+                    result.unwrap_or_else(|e| TaskResult::error(&task_name, e))
+                });
+                task_names.insert(run.id(), name);
             }
 
-            if all_success {
-                if event_tx
-                    .send(AppEvent::Sequence(SequenceEvent::StepCompleted))
-                    .is_err()
-                {
-                    eprintln!("Warning: Failed to send StepCompleted event");
-                }
-            } else if event_tx
-                .send(AppEvent::Sequence(SequenceEvent::SequenceFailed(
-                    "One or more tasks failed".to_string(),
-                )))
+            let results = join_step_runs(runs, task_names).await;
+            let event = if results.iter().all(TaskResult::success) {
+                SequenceEvent::StepCompleted(results)
+            } else {
+                SequenceEvent::StepFailed(results)
+            };
+            if event_tx
+                .send(AppEvent::for_tab(tab_id, AppEvent::Sequence(event)))
                 .is_err()
             {
-                eprintln!("Warning: Failed to send SequenceFailed event");
+                eprintln!("Warning: Failed to send step completion event");
             }
         });
 
@@ -140,116 +414,168 @@ impl App {
         Ok(())
     }
 
-    async fn add_sequence_as_task(&mut self) -> Result<()> {
-        if let Some(command) = self.sequence_state.generate_mise_task_command() {
-            // Generate a task name based on current timestamp
-            let timestamp = std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap_or_default()
-                .as_secs();
-            let task_name = format!("sequence-{timestamp}");
-
-            // Add task directly to mise.toml using TOML parsing
-            let add_result = self.add_task_to_mise_toml(&task_name, &command).await;
-
-            // Show feedback to user
+    /// Ask for the name of the mise task to create from the current sequence
+    fn start_add_sequence_as_task(&mut self) {
+        if self.sequence_state.summary().is_empty() {
             self.task_output.clear();
             self.show_output_pane = true;
             self.task_running = false;
+            self.task_output
+                .push_notice("No tasks enabled in sequence. Enable some tasks first!".to_string());
+            return;
+        }
 
-            match add_result {
-                Ok(()) => {
-                    self.task_output
-                        .push_back(format!("✓ Created task '{task_name}' successfully!"));
-                    self.task_output.push_back(format!("Command: {command}"));
+        let name = self
+            .source_task_name
+            .clone()
+            .or_else(|| self.current_sequence_name.clone())
+            .unwrap_or_default();
+        self.prompt = Some(Prompt::new(PromptKind::AddAsTask, &name));
+    }
 
-                    // Refresh task list to show the new task
-                    self.refresh_tasks().await?;
-                }
-                Err(e) => {
-                    self.task_output
-                        .push_back(format!("✗ Error adding task to mise.toml: {e}"));
-                }
+    /// Write the current sequence to mise.toml as `[tasks.<task_name>]`,
+    /// replacing a task that already has that name
+    pub async fn add_sequence_as_task(&mut self, task_name: &str) -> Result<()> {
+        let summary = self.sequence_state.summary();
+        let Some(task) = self
+            .sequence_state
+            .to_mise_task(&format!("Sequence: {summary}"))
+        else {
+            return Ok(());
+        };
+        let replaced = self.tasks.iter().any(|existing| existing.name == task_name);
+
+        let add_result = self.client.add_task(task_name, task).await;
+
+        // Show feedback to user
+        self.task_output.clear();
+        self.show_output_pane = true;
+        self.task_running = false;
+
+        match add_result {
+            Ok(()) => {
+                let action = if replaced { "Updated" } else { "Created" };
+                self.task_output
+                    .push_notice(format!("✓ {action} task '{task_name}' successfully!"));
+                self.task_output.push_notice(format!("Runs: {summary}"));
+
+                self.source_task_name = Some(task_name.to_string());
+
+                // Refresh task list to show the new task
+                self.refresh_tasks().await?;
+            }
+            Err(e) => {
+                self.task_output
+                    .push_notice(format!("✗ Error adding task to mise.toml: {e}"));
             }
-        } else {
-            // No tasks enabled
-            self.task_output.clear();
-            self.show_output_pane = true;
-            self.task_running = false;
-            self.task_output
-                .push_back("No tasks enabled in sequence. Enable some tasks first!".to_string());
         }
         Ok(())
     }
 
-    pub async fn run_current_task(&mut self) -> Result<()> {
-        // Prevent running multiple tasks simultaneously
+    /// Load the selected mise task into the builder so it can be edited and written back
+    pub async fn open_current_task_in_builder(&mut self) -> Result<()> {
         if self.is_any_task_running() {
             return Ok(());
         }
+        let Some(task) = self.tasks.get(self.selected_task) else {
+            return Ok(());
+        };
+        let task_name = task.name.clone();
 
-        if let Some(task) = self.tasks.get(self.selected_task) {
-            let (output_tx, output_rx) = mpsc::unbounded_channel();
-            self.task_output_rx = Some(output_rx);
-            self.task_output.clear();
-            self.show_output_pane = true;
-            self.task_running = true;
-            self.running_task_name = Some(task.name.clone());
-
-            let client = self.client.clone();
-            let task_name = task.name.clone();
-            let event_tx = self.event_tx.clone();
+        match self.client.get_task_info(&task_name).await {
+            Ok(task_info) => self.open_task_info_in_builder(&task_info),
+            Err(e) => {
+                self.task_output
+                    .push_notice(format!("Failed to get task info: {e}"));
+                self.show_output_pane = true;
+            }
+        }
+        Ok(())
+    }
 
-            let handle = tokio::spawn(async move {
-                if let Err(e) = client.run_task(&task_name, &[], output_tx).await {
-                    eprintln!("Failed to run task: {e}");
-                }
-                if event_tx.send(AppEvent::TaskCompleted).is_err() {
-                    eprintln!("Warning: Failed to send TaskCompleted event");
-                }
-            });
+    /// Replace the sequence in the builder with the steps rebuilt from `task_info`
+    pub fn open_task_info_in_builder(&mut self, task_info: &MiseTaskInfo) {
+        match task_info.sequence_steps() {
+            Ok(steps) => {
+                self.sequence_state = SequenceState::from_steps(&steps);
+                self.source_task_name = Some(task_info.name.clone());
+                self.current_sequence_name = None;
+                self.selected_step = 0;
+                self.step_scroll_offset = 0;
+                self.task_output.push_notice(format!(
+                    "Opened task '{}' in the builder, add it as a task again to update it",
+                    task_info.name
+                ));
+            }
+            Err(e) => {
+                self.task_output
+                    .push_notice(format!("Cannot open task in the builder: {e}"));
+            }
+        }
+        self.show_output_pane = true;
+    }
 
-            self.running_task_handle = Some(handle);
+    /// Run the selected task, asking for its arguments first if it declares any
+    pub async fn run_current_task(&mut self) -> Result<()> {
+        if let Some(task) = self.tasks.get(self.selected_task) {
+            self.request_task_run(&task.name.clone(), ArgsTarget::Run)
+                .await?;
         }
         Ok(())
     }
 
-    pub async fn stop_current_task(&mut self) -> Result<()> {
-        if let Some(handle) = self.running_task_handle.take() {
-            handle.abort();
+    /// Run a task with `args` in the builder's output pane
+    pub async fn run_task_by_name(&mut self, task_name: &str, args: Vec<String>) -> Result<()> {
+        // Prevent running multiple tasks simultaneously
+        if self.is_any_task_running() {
+            return Ok(());
         }
 
-        // Reset task running state
-        self.task_running = false;
-        self.running_task_name = None;
+        let output = self.open_task_output(task_name);
+        let input_rx = self.open_task_input();
+        self.task_output.clear();
+        self.show_output_pane = true;
+        self.task_running = true;
+        self.running_task_name = Some(task_name.to_string());
+        self.begin_task_recording(task_name, &args);
 
-        // Drop the output receiver to signal completion
-        self.task_output_rx = None;
+        let client = self.run_client();
+        let task_name = task_name.to_string();
+        let event_tx = self.event_tx.clone();
+        let tab_id = self.tab_id;
+        let cancel = self.new_run_cancel();
 
-        // Add feedback to user
-        self.task_output
-            .push_back("Task stopped by user".to_string());
+        let handle = tokio::spawn(async move {
+            let result = output
+                .run(&client, &task_name, &args, Some(input_rx), cancel)
+                .await
+                .unwrap_or_else(|e| TaskResult::error(&task_name, e));
+            if event_tx
+                .send(AppEvent::for_tab(tab_id, AppEvent::TaskCompleted(result)))
+                .is_err()
+            {
+                eprintln!("Warning: Failed to send TaskCompleted event");
+            }
+        });
+
+        self.running_task_handle = Some(handle);
+        Ok(())
+    }
 
+    /// Stop the running task. It stays marked as running until its processes have exited
+    pub async fn stop_current_task(&mut self) -> Result<()> {
+        self.cancel_running_task();
+        self.task_output.push_notice("Stopping task...".to_string());
         Ok(())
     }
 
+    /// Stop the running sequence. No further steps start, and the sequence stays marked as
+    /// running until the processes of the current step have exited
     pub async fn stop_sequence(&mut self) -> Result<()> {
-        if let Some(handle) = self.running_task_handle.take() {
-            handle.abort();
-        }
-
-        // Reset sequence and task running state
         self.sequence_state.reset_execution();
-        self.task_running = false;
-        self.running_task_name = None;
-
-        // Drop the output receiver to signal completion
-        self.task_output_rx = None;
-
-        // Add feedback to user
+        self.cancel_running_task();
         self.task_output
-            .push_back("Sequence stopped by user".to_string());
-
+            .push_notice("Stopping sequence...".to_string());
         Ok(())
     }
 
@@ -261,25 +587,33 @@ impl App {
     }
 
     pub async fn edit_task(&self, task_name: String) -> Result<()> {
-        // Get task info to find the file path
-        match self.client.get_task_info(&task_name).await {
-            Ok(task_info) => {
-                if let Some(file_path) = task_info.file {
-                    // Open in VSCode
-                    let _ = tokio::process::Command::new("code").arg(&file_path).spawn();
-                } else {
-                    // Try to open the current directory if no specific file
-                    let _ = tokio::process::Command::new("code").arg(".").spawn();
-                }
-            }
-            Err(_) => {
-                // Fallback to opening current directory
-                let _ = tokio::process::Command::new("code").arg(".").spawn();
-            }
-        }
+        // Open the file defining the task, falling back to the current directory
+        let file_path = match self.client.get_task_info(&task_name).await {
+            Ok(task_info) => task_info.file.unwrap_or_else(|| ".".to_string()),
+            Err(_) => ".".to_string(),
+        };
+        let _ = self.launch_editor(&file_path, None, None);
         Ok(())
     }
 
+    /// Start the configured editor on `path`, at `line` and `column` if given. It does not
+    /// get the terminal, which belongs to the app
+    pub fn launch_editor(
+        &self,
+        path: &str,
+        line: Option<usize>,
+        column: Option<usize>,
+    ) -> std::io::Result<()> {
+        let (program, args) = editor_command(&self.editor, path, line, column);
+        tokio::process::Command::new(program)
+            .args(args)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .map(drop)
+    }
+
     pub async fn show_current_task_content(&mut self) -> Result<()> {
         if let Some(selected_task) = self.tasks.get(self.selected_task) {
             self.show_task_content(selected_task.name.clone()).await?;
@@ -295,26 +629,28 @@ impl App {
                 self.show_output_pane = true;
                 self.task_running = false;
                 self.task_output
-                    .push_back(format!("=== Task: {task_name} ==="));
+                    .push_notice(format!("=== Task: {task_name} ==="));
 
                 if !task_info.description.is_empty() {
                     self.task_output
-                        .push_back(format!("Description: {}", task_info.description));
+                        .push_notice(format!("Description: {}", task_info.description));
                 }
 
                 if let Some(file) = &task_info.file {
-                    self.task_output.push_back(format!("File: {file}"));
+                    self.task_output.push_notice(format!("File: {file}"));
                 }
 
                 if !task_info.run.is_empty() {
-                    self.task_output.push_back("Run configuration:".to_string());
-                    let run_string = task_info.run.join(" ");
-                    self.task_output.push_back(run_string);
+                    self.task_output
+                        .push_notice("Run configuration:".to_string());
+                    for entry in &task_info.run {
+                        self.task_output.push_notice(entry.to_string());
+                    }
                 }
 
                 if !task_info.depends.is_empty() {
                     self.task_output
-                        .push_back(format!("Dependencies: {}", task_info.depends.join(", ")));
+                        .push_notice(format!("Dependencies: {}", task_info.depends.join(", ")));
                 }
             }
             Err(e) => {
@@ -322,47 +658,50 @@ impl App {
                 self.show_output_pane = true;
                 self.task_running = false;
                 self.task_output
-                    .push_back(format!("Failed to get task info: {e}"));
+                    .push_notice(format!("Failed to get task info: {e}"));
             }
         }
         Ok(())
     }
+}
 
-    async fn add_task_to_mise_toml(&self, task_name: &str, command: &str) -> Result<()> {
-        use std::fs;
-        use toml::Value;
-
-        // Read the current mise.toml file
-        let toml_path = "mise.toml";
-        let toml_content = fs::read_to_string(toml_path)
-            .map_err(|e| anyhow::anyhow!("Failed to read mise.toml: {}", e))?;
-
-        // Parse the TOML content
-        let mut toml_value: Value = toml::from_str(&toml_content)
-            .map_err(|e| anyhow::anyhow!("Failed to parse mise.toml: {}", e))?;
-
-        // Ensure the [tasks] table exists
-        let tasks_table = toml_value
-            .as_table_mut()
-            .and_then(|table| {
-                table
-                    .entry("tasks")
-                    .or_insert_with(|| Value::Table(toml::Table::new()))
-                    .as_table_mut()
-            })
-            .ok_or_else(|| anyhow::anyhow!("Could not access or create tasks table"))?;
-
-        // Add the new task
-        tasks_table.insert(task_name.to_string(), Value::String(command.to_string()));
-
-        // Serialize back to TOML string
-        let updated_toml = toml::to_string(&toml_value)
-            .map_err(|e| anyhow::anyhow!("Failed to serialize TOML: {}", e))?;
-
-        // Write back to file
-        fs::write(toml_path, updated_toml)
-            .map_err(|e| anyhow::anyhow!("Failed to write mise.toml: {}", e))?;
+/// Results of the tasks of a step, in the same order as the matrix rows. A task that
+/// panicked or was aborted fails, under the name it was spawned for
+async fn join_step_runs(
+    mut runs: JoinSet<TaskResult>,
+    task_names: HashMap<Id, String>,
+) -> Vec<TaskResult> {
+    let mut results = Vec::new();
+    while let Some(result) = runs.join_next().await {
+        results.push(result.unwrap_or_else(|e| {
+            let task_name = task_names
+                .get(&e.id())
+                .map_or("unknown task", String::as_str);
+            TaskResult::error(task_name, e)
+        }));
+    }
+    results.sort_by(|a, b| a.task_name.cmp(&b.task_name));
+    results
+}
 
-        Ok(())
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::TaskExit;
+
+    #[tokio::test]
+    async fn test_panicked_task_fails_its_step() {
+        let mut runs = JoinSet::new();
+        let mut task_names = HashMap::new();
+        let run = runs.spawn(async { TaskResult::error("lint", "exit code 1") });
+        task_names.insert(run.id(), "lint".to_string());
+        let run = runs.spawn(async { panic!("boom") });
+        task_names.insert(run.id(), "build".to_string());
+
+        let results = join_step_runs(runs, task_names).await;
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].task_name, "build");
+        assert!(matches!(&results[0].exit, TaskExit::Error(error) if error.contains("panicked")));
+        assert_eq!(results[1].task_name, "lint");
     }
 }
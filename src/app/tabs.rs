@@ -0,0 +1,427 @@
+use anyhow::Result;
+use std::collections::BTreeMap;
+use std::mem::swap;
+use tokio::sync::{mpsc, watch};
+use tokio::task::JoinHandle;
+
+use super::file_watch::FileWatch;
+use super::App;
+use crate::models::{
+    AppEvent, ErrorNavigator, OutputBuffer, OutputLine, OutputSearch, ResourceUsage, RunRecording,
+    ScrollbackConfig, SequenceState, TaskInput, TerminalSession, UsageSample,
+};
+
+/// State owned by one sequence tab. The active tab's state lives directly on `App`;
+/// every other tab is parked in a `SequenceTab` until it is switched to.
+pub struct SequenceTab {
+    pub id: usize,
+    pub sequence_state: SequenceState,
+    pub current_sequence_name: Option<String>,
+    pub source_task_name: Option<String>,
+    pub selected_step: usize,
+    pub step_scroll_offset: usize,
+    pub task_output: OutputBuffer,
+    pub task_output_rx: Option<mpsc::UnboundedReceiver<OutputLine>>,
+    pub watch: Option<FileWatch>,
+    pub task_usage_rx: Option<mpsc::UnboundedReceiver<UsageSample>>,
+    pub task_usage: BTreeMap<String, ResourceUsage>,
+    pub terminal: Option<TerminalSession>,
+    pub task_input: Option<TaskInput>,
+    pub show_output_pane: bool,
+    pub output_scroll_offset: usize,
+    pub output_follow_mode: bool,
+    pub output_stderr_only: bool,
+    pub output_timestamps: bool,
+    pub output_search: Option<OutputSearch>,
+    pub output_search_origin: usize,
+    pub output_search_error: Option<String>,
+    pub error_navigator: Option<ErrorNavigator>,
+    pub task_running: bool,
+    pub running_task_name: Option<String>,
+    pub running_task_handle: Option<JoinHandle<()>>,
+    pub running_task_cancel: Option<watch::Sender<bool>>,
+    pub run_recording: Option<RunRecording>,
+}
+
+impl SequenceTab {
+    pub fn new(id: usize, scrollback: ScrollbackConfig) -> Self {
+        Self {
+            id,
+            sequence_state: SequenceState::default(),
+            current_sequence_name: None,
+            source_task_name: None,
+            selected_step: 0,
+            step_scroll_offset: 0,
+            task_output: OutputBuffer::new(scrollback),
+            task_output_rx: None,
+            watch: None,
+            task_usage_rx: None,
+            task_usage: BTreeMap::new(),
+            terminal: None,
+            task_input: None,
+            show_output_pane: false,
+            output_scroll_offset: 0,
+            output_follow_mode: true,
+            output_stderr_only: false,
+            output_timestamps: false,
+            output_search: None,
+            output_search_origin: 0,
+            output_search_error: None,
+            error_navigator: None,
+            task_running: false,
+            running_task_name: None,
+            running_task_handle: None,
+            running_task_cancel: None,
+            run_recording: None,
+        }
+    }
+
+    fn is_busy(&self) -> bool {
+        self.task_running || self.sequence_state.is_running
+    }
+}
+
+/// Label shown in the tab bar, e.g. `release` or `Sequence 2`
+fn tab_title(id: usize, sequence_name: Option<&str>) -> String {
+    sequence_name
+        .map(str::to_string)
+        .unwrap_or_else(|| format!("Sequence {}", id + 1))
+}
+
+impl App {
+    /// Exchange the state on `App` with the tab parked at `index`
+    fn swap_tab_state(&mut self, index: usize) {
+        let tab = &mut self.tabs[index];
+        swap(&mut self.tab_id, &mut tab.id);
+        swap(&mut self.sequence_state, &mut tab.sequence_state);
+        swap(
+            &mut self.current_sequence_name,
+            &mut tab.current_sequence_name,
+        );
+        swap(&mut self.source_task_name, &mut tab.source_task_name);
+        swap(&mut self.selected_step, &mut tab.selected_step);
+        swap(&mut self.step_scroll_offset, &mut tab.step_scroll_offset);
+        swap(&mut self.task_output, &mut tab.task_output);
+        swap(&mut self.task_output_rx, &mut tab.task_output_rx);
+        swap(&mut self.watch, &mut tab.watch);
+        swap(&mut self.task_usage_rx, &mut tab.task_usage_rx);
+        swap(&mut self.task_usage, &mut tab.task_usage);
+        swap(&mut self.terminal, &mut tab.terminal);
+        swap(&mut self.task_input, &mut tab.task_input);
+        swap(&mut self.show_output_pane, &mut tab.show_output_pane);
+        swap(
+            &mut self.output_scroll_offset,
+            &mut tab.output_scroll_offset,
+        );
+        swap(&mut self.output_follow_mode, &mut tab.output_follow_mode);
+        swap(&mut self.output_stderr_only, &mut tab.output_stderr_only);
+        swap(&mut self.output_timestamps, &mut tab.output_timestamps);
+        swap(&mut self.output_search, &mut tab.output_search);
+        swap(
+            &mut self.output_search_origin,
+            &mut tab.output_search_origin,
+        );
+        swap(&mut self.output_search_error, &mut tab.output_search_error);
+        swap(&mut self.error_navigator, &mut tab.error_navigator);
+        swap(&mut self.task_running, &mut tab.task_running);
+        swap(&mut self.running_task_name, &mut tab.running_task_name);
+        swap(&mut self.running_task_handle, &mut tab.running_task_handle);
+        swap(&mut self.running_task_cancel, &mut tab.running_task_cancel);
+        swap(&mut self.run_recording, &mut tab.run_recording);
+    }
+
+    /// Load the state of the tab at `index` onto `App`, parking the active one
+    fn load_tab_state(&mut self, index: usize) {
+        self.swap_tab_state(self.active_tab);
+        self.swap_tab_state(index);
+    }
+
+    /// Undo `load_tab_state(index)`: park that tab again and restore the active one
+    fn unload_tab_state(&mut self, index: usize) {
+        self.swap_tab_state(index);
+        self.swap_tab_state(self.active_tab);
+    }
+
+    pub fn switch_to_tab(&mut self, index: usize) {
+        if index == self.active_tab || index >= self.tabs.len() {
+            return;
+        }
+        self.load_tab_state(index);
+        self.active_tab = index;
+        self.button_hover_state = None;
+    }
+
+    pub fn next_tab(&mut self) {
+        self.switch_to_tab((self.active_tab + 1) % self.tabs.len());
+    }
+
+    pub fn previous_tab(&mut self) {
+        let count = self.tabs.len();
+        self.switch_to_tab((self.active_tab + count - 1) % count);
+    }
+
+    /// Open an empty sequence in a new tab and switch to it
+    pub fn open_new_tab(&mut self) {
+        self.tabs
+            .push(SequenceTab::new(self.next_tab_id, self.scrollback));
+        self.next_tab_id += 1;
+        self.switch_to_tab(self.tabs.len() - 1);
+    }
+
+    /// Close the active tab. The last tab and tabs with something running stay open
+    pub fn close_active_tab(&mut self) {
+        if self.tabs.len() <= 1 {
+            return;
+        }
+        if self.is_any_task_running() {
+            self.task_output
+                .push_notice("Stop the running sequence before closing its tab".to_string());
+            self.show_output_pane = true;
+            return;
+        }
+
+        let closing = self.active_tab;
+        let next = if closing + 1 < self.tabs.len() {
+            closing + 1
+        } else {
+            closing - 1
+        };
+        self.load_tab_state(next);
+        self.tabs.remove(closing);
+        self.active_tab = if next > closing { next - 1 } else { next };
+        self.button_hover_state = None;
+    }
+
+    /// Drop a deleted task from the sequences of every tab
+    pub fn remove_task_from_sequences(&mut self, task_name: &str) {
+        self.sequence_state.remove_task(task_name);
+        for tab in self.tabs.iter_mut() {
+            tab.sequence_state.remove_task(task_name);
+        }
+    }
+
+    /// Titles of all tabs in display order, with running tabs marked
+    pub fn tab_titles(&self) -> Vec<String> {
+        self.tabs
+            .iter()
+            .enumerate()
+            .map(|(index, tab)| {
+                let (id, name, busy) = if index == self.active_tab {
+                    (
+                        self.tab_id,
+                        self.current_sequence_name.as_deref(),
+                        self.is_any_task_running(),
+                    )
+                } else {
+                    (tab.id, tab.current_sequence_name.as_deref(), tab.is_busy())
+                };
+                let title = tab_title(id, name);
+                if busy {
+                    format!("▶ {title}")
+                } else {
+                    title
+                }
+            })
+            .collect()
+    }
+
+    /// Send an event scoped to the tab whose state is currently loaded
+    pub fn send_tab_event(&self, event: AppEvent) {
+        if self
+            .event_tx
+            .send(AppEvent::for_tab(self.tab_id, event))
+            .is_err()
+        {
+            eprintln!("Warning: Failed to send tab event");
+        }
+    }
+
+    /// Handle an event from a tab's runner against that tab's state, even when it is
+    /// not the active tab. Events for tabs that have been closed are dropped.
+    pub async fn handle_tab_event(&mut self, tab_id: usize, event: AppEvent) -> Result<()> {
+        if tab_id == self.tab_id {
+            return self.handle_tab_scoped_event(event).await;
+        }
+
+        let Some(index) = (0..self.tabs.len())
+            .find(|&index| index != self.active_tab && self.tabs[index].id == tab_id)
+        else {
+            return Ok(());
+        };
+
+        self.load_tab_state(index);
+        let result = self.handle_tab_scoped_event(event).await;
+        self.unload_tab_state(index);
+        result
+    }
+
+    /// Move output from every tab's runner into that tab's output buffer
+    pub fn poll_task_output(&mut self) {
+        self.drain_task_output();
+
+        for index in 0..self.tabs.len() {
+            let tab = &self.tabs[index];
+            let has_output = tab.task_output_rx.is_some()
+                || tab.task_usage_rx.is_some()
+                || tab.terminal.is_some();
+            if index != self.active_tab && has_output {
+                self.load_tab_state(index);
+                self.drain_task_output();
+                self.unload_tab_state(index);
+            }
+        }
+    }
+
+    pub fn drain_task_output(&mut self) {
+        while let Some(line) = self
+            .task_output_rx
+            .as_mut()
+            .and_then(|rx| rx.try_recv().ok())
+        {
+            self.push_task_output(line);
+        }
+        if let Some(terminal) = &mut self.terminal {
+            terminal.drain();
+        }
+        self.drain_task_usage();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::SequenceEvent;
+
+    fn create_test_app() -> App {
+        let (tx, _rx) = mpsc::unbounded_channel();
+        App::new(tx)
+    }
+
+    #[test]
+    fn test_new_app_has_one_tab() {
+        let app = create_test_app();
+        assert_eq!(app.tabs.len(), 1);
+        assert_eq!(app.active_tab, 0);
+        assert_eq!(app.tab_titles(), vec!["Sequence 1"]);
+    }
+
+    #[test]
+    fn test_tabs_keep_their_own_sequences() {
+        let mut app = create_test_app();
+        app.sequence_state.set_task_step("build", 0, true);
+
+        app.open_new_tab();
+        assert_eq!(app.active_tab, 1);
+        assert!(!app.sequence_state.is_task_enabled_for_step("build", 0));
+        app.sequence_state.set_task_step("deploy", 1, true);
+        app.current_sequence_name = Some("release".to_string());
+
+        app.previous_tab();
+        assert!(app.sequence_state.is_task_enabled_for_step("build", 0));
+        assert!(!app.sequence_state.is_task_enabled_for_step("deploy", 1));
+        assert_eq!(app.tab_titles(), vec!["Sequence 1", "release"]);
+
+        app.next_tab();
+        assert!(app.sequence_state.is_task_enabled_for_step("deploy", 1));
+    }
+
+    #[test]
+    fn test_tabs_keep_their_own_output_view() {
+        let mut app = create_test_app();
+        app.output_stderr_only = true;
+        app.output_timestamps = true;
+        app.output_search_origin = 7;
+        app.error_navigator = Some(ErrorNavigator::new(Vec::new()));
+
+        app.open_new_tab();
+        assert!(!app.output_stderr_only);
+        assert!(!app.output_timestamps);
+        assert_eq!(app.output_search_origin, 0);
+        assert!(app.error_navigator.is_none());
+        app.output_timestamps = true;
+
+        app.previous_tab();
+        assert!(app.output_stderr_only);
+        assert_eq!(app.output_search_origin, 7);
+        assert!(app.error_navigator.is_some());
+        app.output_timestamps = false;
+
+        app.next_tab();
+        assert!(app.output_timestamps);
+        assert!(app.error_navigator.is_none());
+    }
+
+    #[test]
+    fn test_close_active_tab() {
+        let mut app = create_test_app();
+        app.open_new_tab();
+        app.current_sequence_name = Some("second".to_string());
+        app.open_new_tab();
+        app.current_sequence_name = Some("third".to_string());
+
+        app.switch_to_tab(1);
+        app.close_active_tab();
+        assert_eq!(app.active_tab, 1);
+        assert_eq!(app.current_sequence_name, Some("third".to_string()));
+        assert_eq!(app.tab_titles(), vec!["Sequence 1", "third"]);
+
+        app.close_active_tab();
+        assert_eq!(app.active_tab, 0);
+        app.close_active_tab();
+        assert_eq!(app.tabs.len(), 1);
+    }
+
+    #[test]
+    fn test_running_tab_cannot_be_closed() {
+        let mut app = create_test_app();
+        app.open_new_tab();
+        app.sequence_state.start_execution();
+
+        app.close_active_tab();
+        assert_eq!(app.tabs.len(), 2);
+        assert_eq!(app.tab_titles()[1], "▶ Sequence 2");
+    }
+
+    #[tokio::test]
+    async fn test_events_for_background_tab_update_that_tab() {
+        let mut app = create_test_app();
+        app.sequence_state.start_execution();
+        let running_tab_id = app.tab_id;
+
+        app.open_new_tab();
+        app.handle_tab_event(
+            running_tab_id,
+            AppEvent::Sequence(SequenceEvent::SequenceFailed("boom".to_string())),
+        )
+        .await
+        .unwrap();
+
+        // The active tab is untouched
+        assert_eq!(app.active_tab, 1);
+        assert!(app.task_output.is_empty());
+
+        app.switch_to_tab(0);
+        assert!(!app.sequence_state.is_running);
+        assert_eq!(
+            app.task_output.back().unwrap().text,
+            "Sequence failed: boom"
+        );
+    }
+
+    #[test]
+    fn test_poll_task_output_for_background_tab() {
+        let mut app = create_test_app();
+        let (output_tx, output_rx) = mpsc::unbounded_channel();
+        app.task_output_rx = Some(output_rx);
+
+        app.open_new_tab();
+        output_tx
+            .send(OutputLine::stdout("from first tab"))
+            .unwrap();
+        app.poll_task_output();
+
+        assert!(app.task_output.is_empty());
+        app.switch_to_tab(0);
+        assert_eq!(app.task_output.back().unwrap().text, "from first tab");
+    }
+}
@@ -0,0 +1,259 @@
+use anyhow::Result;
+use ratatui::crossterm::event::{Event, KeyCode, KeyEvent};
+use tui_input::backend::crossterm::EventHandler;
+use tui_input::Input;
+
+use super::App;
+use crate::models::task_args::{task_args, task_command_line, ArgsForm, ArgsTarget, TaskArg};
+
+impl App {
+    /// Arguments `task_name` declares in its usage spec, looked up once per task list refresh.
+    /// A task whose info cannot be read is treated as taking no arguments
+    pub async fn declared_args(&mut self, task_name: &str) -> Vec<TaskArg> {
+        if let Some(args) = self.task_arg_specs.get(task_name) {
+            return args.clone();
+        }
+        let args = match self.client.get_task_info(task_name).await {
+            Ok(info) => task_args(&info.usage_spec),
+            Err(_) => return Vec::new(),
+        };
+        self.task_arg_specs
+            .insert(task_name.to_string(), args.clone());
+        args
+    }
+
+    /// Run a task right away if it takes no arguments, otherwise ask for them first,
+    /// starting from the ones it last ran with
+    pub async fn request_task_run(&mut self, task_name: &str, target: ArgsTarget) -> Result<()> {
+        if target == ArgsTarget::Run && self.is_any_task_running() {
+            return Ok(());
+        }
+
+        let args = self.declared_args(task_name).await;
+        if args.is_empty() {
+            return self.run_with_args(task_name, target, Vec::new()).await;
+        }
+
+        let previous = self
+            .last_task_args
+            .get(task_name)
+            .cloned()
+            .unwrap_or_default();
+        self.args_form = Some(ArgsForm::new(task_name, target, args, &previous));
+        Ok(())
+    }
+
+    /// Ask for the arguments the selected task gets in the selected step of the sequence
+    pub async fn edit_step_args(&mut self) -> Result<()> {
+        let Some(task) = self.tasks.get(self.selected_task) else {
+            return Ok(());
+        };
+        let task_name = task.name.clone();
+        let step = self.selected_step;
+        self.show_output_pane = true;
+
+        if !self
+            .sequence_state
+            .is_task_enabled_for_step(&task_name, step)
+        {
+            self.task_output.push_notice(format!(
+                "Add '{task_name}' to step {} before setting its arguments",
+                step + 1
+            ));
+            return Ok(());
+        }
+
+        let args = self.declared_args(&task_name).await;
+        if args.is_empty() {
+            self.task_output
+                .push_notice(format!("Task '{task_name}' takes no arguments"));
+            return Ok(());
+        }
+
+        let saved = self.sequence_state.task_args(&task_name, step).to_vec();
+        self.args_form = Some(ArgsForm::new(
+            &task_name,
+            ArgsTarget::Step(step),
+            args,
+            &saved,
+        ));
+        Ok(())
+    }
+
+    /// The arguments form takes every key until it is submitted or cancelled
+    pub async fn handle_args_form_key(&mut self, key: KeyEvent) -> Result<()> {
+        let Some(form) = &mut self.args_form else {
+            return Ok(());
+        };
+
+        match key.code {
+            KeyCode::Enter => return self.submit_args_form().await,
+            KeyCode::Esc => self.args_form = None,
+            KeyCode::Up | KeyCode::BackTab => form.select_previous(),
+            KeyCode::Down | KeyCode::Tab => form.select_next(),
+            KeyCode::Char(' ') if form.cycle(true) => {}
+            KeyCode::Right if form.cycle(true) => {}
+            KeyCode::Left if form.cycle(false) => {}
+            _ => {
+                if let Some(field) = form.selected_field_mut() {
+                    field.input.handle_event(&Event::Key(key));
+                }
+                form.error = None;
+            }
+        }
+        Ok(())
+    }
+
+    /// Pasted text goes into the selected field, without line breaks
+    pub fn paste_into_args_form(&mut self, text: &str) {
+        let Some(field) = self
+            .args_form
+            .as_mut()
+            .and_then(ArgsForm::selected_field_mut)
+        else {
+            return;
+        };
+        let text: String = text.chars().filter(|c| !matches!(c, '\r' | '\n')).collect();
+        field.input = Input::new(format!("{}{text}", field.input.value()));
+    }
+
+    /// Use the entered arguments, or keep the form open with the reason they were rejected
+    async fn submit_args_form(&mut self) -> Result<()> {
+        let Some(form) = &mut self.args_form else {
+            return Ok(());
+        };
+        let args = match form.to_args() {
+            Ok(args) => args,
+            Err(error) => {
+                form.error = Some(error);
+                return Ok(());
+            }
+        };
+
+        let form = self.args_form.take().expect("form is open");
+        self.run_with_args(&form.task_name, form.target, args).await
+    }
+
+    async fn run_with_args(
+        &mut self,
+        task_name: &str,
+        target: ArgsTarget,
+        args: Vec<String>,
+    ) -> Result<()> {
+        match target {
+            ArgsTarget::Run => {
+                self.last_task_args
+                    .insert(task_name.to_string(), args.clone());
+                self.run_task_by_name(task_name, args).await
+            }
+            ArgsTarget::RunInTaskView => {
+                self.last_task_args
+                    .insert(task_name.to_string(), args.clone());
+                self.run_task_in_view(task_name, args).await
+            }
+            ArgsTarget::Step(step) => {
+                let message = if args.is_empty() {
+                    format!("'{task_name}' runs without arguments in step {}", step + 1)
+                } else {
+                    format!(
+                        "Step {} runs: {}",
+                        step + 1,
+                        task_command_line(task_name, &args)
+                    )
+                };
+                self.sequence_state.set_task_args(task_name, step, args);
+                self.task_output.push_notice(message);
+                self.show_output_pane = true;
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::task_args::ArgKind;
+    use crate::models::MiseTask;
+    use ratatui::crossterm::event::KeyModifiers;
+    use tokio::sync::mpsc;
+
+    fn env_arg() -> TaskArg {
+        TaskArg {
+            name: "env".to_string(),
+            kind: ArgKind::Option,
+            flag: "--env".to_string(),
+            help: String::new(),
+            required: true,
+            variadic: false,
+            choices: vec!["staging".to_string(), "prod".to_string()],
+            default: None,
+        }
+    }
+
+    fn create_test_app() -> App {
+        let (tx, _rx) = mpsc::unbounded_channel();
+        let mut app = App::new(tx);
+        app.tasks = vec![MiseTask::new("deploy".to_string(), "mise.toml".to_string())];
+        app.task_arg_specs
+            .insert("deploy".to_string(), vec![env_arg()]);
+        app
+    }
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    #[tokio::test]
+    async fn test_step_args_are_saved_from_the_form() {
+        let mut app = create_test_app();
+        app.selected_step = 1;
+
+        // Only tasks in the step can have arguments for it
+        app.edit_step_args().await.unwrap();
+        assert!(app.args_form.is_none());
+
+        app.sequence_state.set_task_step("deploy", 1, true);
+        app.edit_step_args().await.unwrap();
+        assert_eq!(app.args_form.as_ref().unwrap().target, ArgsTarget::Step(1));
+
+        // A required field keeps the form open
+        app.handle_args_form_key(key(KeyCode::Enter)).await.unwrap();
+        assert_eq!(
+            app.args_form.as_ref().unwrap().error.as_deref(),
+            Some("--env <env> is required")
+        );
+
+        app.handle_args_form_key(key(KeyCode::Left)).await.unwrap();
+        app.handle_args_form_key(key(KeyCode::Enter)).await.unwrap();
+        assert!(app.args_form.is_none());
+        assert_eq!(app.sequence_state.task_args("deploy", 1), ["--env", "prod"]);
+
+        // Reopening starts from the saved arguments
+        app.edit_step_args().await.unwrap();
+        assert_eq!(app.args_form.unwrap().fields[0].value(), "prod");
+    }
+
+    #[tokio::test]
+    async fn test_run_asks_for_arguments() {
+        let mut app = create_test_app();
+        app.last_task_args.insert(
+            "deploy".to_string(),
+            vec!["--env".to_string(), "staging".to_string()],
+        );
+
+        app.run_current_task().await.unwrap();
+        let form = app.args_form.as_ref().unwrap();
+        assert_eq!(form.target, ArgsTarget::Run);
+        assert_eq!(form.fields[0].value(), "staging");
+        assert!(!app.task_running);
+
+        app.paste_into_args_form("x\n");
+        assert_eq!(
+            app.args_form.as_ref().unwrap().fields[0].value(),
+            "stagingx"
+        );
+        app.handle_args_form_key(key(KeyCode::Esc)).await.unwrap();
+        assert!(app.args_form.is_none());
+    }
+}
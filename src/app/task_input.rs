@@ -0,0 +1,203 @@
+use ratatui::crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+use tokio::sync::mpsc;
+use tui_input::backend::crossterm::EventHandler;
+use tui_input::Input;
+
+use super::App;
+use crate::models::task_input::{is_escape_chord, key_bytes, INPUT_ESCAPE_CHORD};
+use crate::models::{OutputLine, TaskInput};
+
+impl App {
+    /// Give the task about to run in this tab a stdin. Focus carries over from the previous
+    /// step of a sequence, so answering prompts of consecutive steps needs no re-focusing
+    pub fn open_task_input(&mut self) -> mpsc::UnboundedReceiver<Vec<u8>> {
+        let (input_tx, input_rx) = mpsc::unbounded_channel();
+        let mut input = TaskInput::new(input_tx);
+        input.focused = self.is_input_focused();
+        self.task_input = Some(input);
+        input_rx
+    }
+
+    pub fn is_input_focused(&self) -> bool {
+        self.task_input.as_ref().is_some_and(|input| input.focused)
+    }
+
+    /// Send keystrokes to the running task until the escape chord is pressed
+    pub fn focus_task_input(&mut self) {
+        match &mut self.task_input {
+            Some(input) if self.task_running => {
+                input.focused = true;
+                self.show_output_pane = true;
+                self.output_follow_mode = true;
+            }
+            _ => {
+                self.task_output
+                    .push_notice("No running task to send input to".to_string());
+                self.show_output_pane = true;
+            }
+        }
+    }
+
+    pub fn unfocus_task_input(&mut self) {
+        if let Some(input) = &mut self.task_input {
+            input.focused = false;
+        }
+    }
+
+    /// Forward a key to the running task. A task on a pseudo-terminal gets every key as
+    /// a terminal would send it, a task on pipes gets whole lines as Enter is pressed
+    pub fn handle_task_input_key(&mut self, key: KeyEvent) {
+        if is_escape_chord(&key) {
+            self.unfocus_task_input();
+            return;
+        }
+        let in_pty = self.terminal.is_some();
+        let Some(input) = &mut self.task_input else {
+            return;
+        };
+
+        if in_pty {
+            if let Some(bytes) = key_bytes(&key) {
+                input.send(bytes);
+            }
+            return;
+        }
+        match key.code {
+            KeyCode::Enter => {
+                let line = input.submit_line();
+                self.push_typed_line(line);
+            }
+            KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                input.close();
+                self.push_task_output(OutputLine::notice("STDIN closed"));
+            }
+            _ => {
+                input.line.handle_event(&Event::Key(key));
+            }
+        }
+    }
+
+    /// Pasted text goes to the task while it has focus, otherwise to the open text field
+    pub fn handle_paste(&mut self, text: &str) {
+        if self.is_input_focused() {
+            self.paste_task_input(text);
+        } else if self.args_form.is_some() {
+            self.paste_into_args_form(text);
+        } else if let Some(prompt) = &mut self.prompt {
+            prompt.input = append_to_input(&prompt.input, text);
+            if self.is_searching_output() {
+                self.update_output_search();
+            }
+        } else if let Some(input) = &mut self.rename_input {
+            *input = append_to_input(input, text);
+        }
+    }
+
+    fn paste_task_input(&mut self, text: &str) {
+        let in_pty = self.terminal.is_some();
+        let Some(input) = &mut self.task_input else {
+            return;
+        };
+
+        if in_pty {
+            // Terminals send pasted line breaks as carriage returns, like Enter
+            let text = text.replace("\r\n", "\r").replace('\n', "\r");
+            input.send(text.into_bytes());
+        } else {
+            for line in input.paste_into_line(text) {
+                self.push_typed_line(line);
+            }
+        }
+    }
+
+    /// Echo a line sent to the task in the output pane
+    fn push_typed_line(&mut self, line: String) {
+        let line = OutputLine {
+            task: self.running_task_name.clone(),
+            ..OutputLine::stdin(line)
+        };
+        self.push_task_output(line);
+    }
+
+    /// Title suffix of the output pane while keys go to the task
+    pub fn input_focus_hint(&self) -> Option<String> {
+        let input = self.task_input.as_ref().filter(|input| input.focused)?;
+        Some(if input.is_open() {
+            format!(" [typing into task, {INPUT_ESCAPE_CHORD}: Back]")
+        } else {
+            format!(" [stdin closed, {INPUT_ESCAPE_CHORD}: Back]")
+        })
+    }
+}
+
+/// Single-line text fields drop line breaks from pasted text
+fn append_to_input(input: &Input, text: &str) -> Input {
+    let text: String = text.chars().filter(|c| !matches!(c, '\r' | '\n')).collect();
+    Input::new(format!("{}{text}", input.value()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{LineSource, Prompt, PromptKind, PtySize, TerminalSession};
+
+    fn create_test_app() -> (App, mpsc::UnboundedReceiver<Vec<u8>>) {
+        let (tx, _rx) = mpsc::unbounded_channel();
+        let mut app = App::new(tx);
+        let input_rx = app.open_task_input();
+        app.task_running = true;
+        (app, input_rx)
+    }
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn test_piped_task_gets_lines() {
+        let (mut app, mut input_rx) = create_test_app();
+        app.focus_task_input();
+        assert!(app.is_input_focused());
+
+        app.handle_task_input_key(key(KeyCode::Char('y')));
+        assert!(input_rx.try_recv().is_err());
+        app.handle_task_input_key(key(KeyCode::Enter));
+        assert_eq!(input_rx.try_recv().unwrap(), b"y\n");
+        let echoed = app.task_output.back().unwrap();
+        assert_eq!(echoed.text, "y");
+        assert_eq!(echoed.source, LineSource::Stdin);
+
+        app.handle_paste("yes\n");
+        assert_eq!(input_rx.try_recv().unwrap(), b"yes\n");
+
+        app.handle_task_input_key(KeyEvent::new(KeyCode::Char(']'), KeyModifiers::CONTROL));
+        assert!(!app.is_input_focused());
+    }
+
+    #[test]
+    fn test_pty_task_gets_keys() {
+        let (mut app, mut input_rx) = create_test_app();
+        app.terminal = Some(TerminalSession::new(PtySize::default()).0);
+        app.focus_task_input();
+
+        app.handle_task_input_key(key(KeyCode::Char('y')));
+        app.handle_task_input_key(key(KeyCode::Enter));
+        app.handle_paste("a\nb");
+        assert_eq!(input_rx.try_recv().unwrap(), b"y");
+        assert_eq!(input_rx.try_recv().unwrap(), b"\r");
+        assert_eq!(input_rx.try_recv().unwrap(), b"a\rb");
+    }
+
+    #[test]
+    fn test_focus_needs_a_running_task() {
+        let (mut app, _input_rx) = create_test_app();
+        app.task_running = false;
+        app.focus_task_input();
+        assert!(!app.is_input_focused());
+
+        // Without focus, pasted text goes to an open prompt
+        app.prompt = Some(Prompt::new(PromptKind::SaveSequence, "re"));
+        app.handle_paste("lease\n");
+        assert_eq!(app.prompt.unwrap().input.value(), "release");
+    }
+}
@@ -1,55 +1,97 @@
-use anyhow::Result;
-use tokio::sync::mpsc;
-
 use super::App;
-use crate::models::{AppEvent, AppState};
+use crate::models::task_args::ArgsTarget;
+use crate::models::{AppEvent, AppState, MiseTask, TaskResult};
+use anyhow::Result;
 
 impl App {
     pub async fn refresh_tasks(&mut self) -> Result<()> {
         let client = self.client.clone();
         let event_tx = self.event_tx.clone();
 
-        tokio::spawn(async move {
-            match client.list_tasks().await {
-                Ok(tasks) => {
-                    if event_tx.send(AppEvent::TasksRefreshed(tasks)).is_err() {
-                        eprintln!("Warning: Failed to send TasksRefreshed event");
-                    }
-                }
+        // An older refresh would only list outdated tasks and fetch their details
+        if let Some(previous) = self.task_refresh.take() {
+            previous.abort();
+        }
+        self.task_refresh = Some(tokio::spawn(async move {
+            let listed = client.list_tasks().await;
+            if let Ok(tasks) = &listed
+                && event_tx
+                    .send(AppEvent::TasksRefreshed(tasks.clone()))
+                    .is_err()
+            {
+                eprintln!("Warning: Failed to send TasksRefreshed event");
+            }
+            // Sent even when listing failed, so fixing a broken config refreshes the tasks
+            let layout = client.config_layout().await;
+            let _ = event_tx.send(AppEvent::ConfigLayoutLoaded(layout));
+            match listed {
+                // Details are fetched ahead so actions and the detail view need not wait
+                Ok(tasks) => client.prewarm_task_info(&tasks).await,
                 Err(e) => {
                     eprintln!("Failed to refresh tasks: {e}");
                 }
             }
-        });
+        }));
 
         Ok(())
     }
 
+    /// Replace the task list, keeping the same task selected even if it moved
+    pub fn set_tasks(&mut self, tasks: Vec<MiseTask>) {
+        let selected = self
+            .tasks
+            .get(self.selected_task)
+            .map(|task| task.name.clone());
+        self.tasks = tasks;
+        let last = self.tasks.len().saturating_sub(1);
+        self.selected_task = selected
+            .and_then(|name| self.tasks.iter().position(|task| task.name == name))
+            .unwrap_or(self.selected_task.min(last));
+        self.scroll_offset = self.scroll_offset.min(last);
+        self.ensure_selected_task_visible(self.current_visible_height);
+    }
+
+    /// Run the selected task in the task view, asking for its arguments first if it
+    /// declares any
     pub async fn run_selected_task(&mut self) -> Result<()> {
         if let Some(task) = self.tasks.get(self.selected_task) {
-            let (output_tx, output_rx) = mpsc::unbounded_channel();
-            self.task_output_rx = Some(output_rx);
-            self.task_output.clear();
-            self.show_output_pane = true;
-            self.task_running = true;
+            self.request_task_run(&task.name.clone(), ArgsTarget::RunInTaskView)
+                .await?;
+        }
+        Ok(())
+    }
 
-            let client = self.client.clone();
-            let task_name = task.name.clone();
-            let event_tx = self.event_tx.clone();
+    /// Run a task with `args` in the full-screen task view
+    pub async fn run_task_in_view(&mut self, task_name: &str, args: Vec<String>) -> Result<()> {
+        let output = self.open_task_output(task_name);
+        let input_rx = self.open_task_input();
+        self.task_output.clear();
+        self.show_output_pane = true;
+        self.task_running = true;
 
-            self.state = AppState::Running(task_name.clone());
+        let client = self.run_client();
+        self.begin_task_recording(task_name, &args);
+        let task_name = task_name.to_string();
+        let event_tx = self.event_tx.clone();
+        let tab_id = self.tab_id;
+        let cancel = self.new_run_cancel();
 
-            let handle = tokio::spawn(async move {
-                if let Err(e) = client.run_task(&task_name, &[], output_tx).await {
-                    eprintln!("Failed to run task: {e}");
-                }
-                if event_tx.send(AppEvent::TaskCompleted).is_err() {
-                    eprintln!("Warning: Failed to send TaskCompleted event");
-                }
-            });
+        self.state = AppState::Running(task_name.clone());
 
-            self.running_task_handle = Some(handle);
-        }
+        let handle = tokio::spawn(async move {
+            let result = output
+                .run(&client, &task_name, &args, Some(input_rx), cancel)
+                .await
+                .unwrap_or_else(|e| TaskResult::error(&task_name, e));
+            if event_tx
+                .send(AppEvent::for_tab(tab_id, AppEvent::TaskCompleted(result)))
+                .is_err()
+            {
+                eprintln!("Warning: Failed to send TaskCompleted event");
+            }
+        });
+
+        self.running_task_handle = Some(handle);
         Ok(())
     }
 }
@@ -0,0 +1,145 @@
+use anyhow::Result;
+use tokio::sync::{mpsc, watch};
+
+use super::App;
+use crate::mise::MiseClient;
+use crate::models::{OutputLine, PtySize, TaskResult, TerminalSession};
+
+/// Where a running task's output goes: lines for the output pane, or the raw bytes of the
+/// pseudo-terminal it runs on, together with the terminal's size
+#[derive(Clone)]
+pub enum TaskOutput {
+    Lines(mpsc::UnboundedSender<OutputLine>),
+    Terminal(mpsc::UnboundedSender<Vec<u8>>, watch::Receiver<PtySize>),
+}
+
+impl TaskOutput {
+    pub async fn run(
+        self,
+        client: &MiseClient,
+        task_name: &str,
+        args: &[String],
+        input_rx: Option<mpsc::UnboundedReceiver<Vec<u8>>>,
+        cancel: watch::Receiver<bool>,
+    ) -> Result<TaskResult> {
+        match self {
+            Self::Lines(output_tx) => {
+                client
+                    .run_task(task_name, args, output_tx, input_rx, cancel)
+                    .await
+            }
+            Self::Terminal(output_tx, size) => {
+                client
+                    .run_task_in_pty(task_name, args, output_tx, input_rx, size, cancel)
+                    .await
+            }
+        }
+    }
+}
+
+impl App {
+    /// Whether `task_name` runs on a pseudo-terminal instead of pipes
+    pub fn runs_in_pty(&self, task_name: &str) -> bool {
+        self.pty_tasks.contains(task_name)
+    }
+
+    /// Switch the selected task between pipe and PTY mode
+    pub fn toggle_pty_mode(&mut self) {
+        let Some(task) = self.tasks.get(self.selected_task) else {
+            return;
+        };
+        let task_name = task.name.clone();
+        let message = if self.pty_tasks.remove(&task_name) {
+            format!("Task '{task_name}' will run with pipes")
+        } else {
+            self.pty_tasks.insert(task_name.clone());
+            format!("Task '{task_name}' will run in a pseudo-terminal")
+        };
+        self.task_output.push_notice(message);
+        self.show_output_pane = true;
+    }
+
+    /// Route the output of a task about to run in this tab to the output pane, through a
+    /// terminal if the task runs in PTY mode
+    pub fn open_task_output(&mut self, task_name: &str) -> TaskOutput {
+        if !self.runs_in_pty(task_name) {
+            return self.open_line_output();
+        }
+        let (session, output_tx, size) = TerminalSession::new(self.output_pane_size);
+        self.task_output_rx = None;
+        self.terminal = Some(session);
+        TaskOutput::Terminal(output_tx, size)
+    }
+
+    pub fn open_line_output(&mut self) -> TaskOutput {
+        let (output_tx, output_rx) = mpsc::unbounded_channel();
+        self.task_output_rx = Some(output_rx);
+        self.terminal = None;
+        TaskOutput::Lines(output_tx)
+    }
+
+    /// Keep the final screen of a finished PTY task as ordinary output lines
+    pub fn close_terminal(&mut self) {
+        if let Some(mut terminal) = self.terminal.take() {
+            terminal.drain();
+            for line in terminal.screen_lines() {
+                self.push_task_output(OutputLine::stdout(line));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::MiseTask;
+
+    fn create_test_app() -> App {
+        let (tx, _rx) = mpsc::unbounded_channel();
+        let mut app = App::new(tx);
+        app.tasks = vec![MiseTask::new("login".to_string(), "mise.toml".to_string())];
+        app
+    }
+
+    #[test]
+    fn test_toggle_pty_mode() {
+        let mut app = create_test_app();
+        assert!(matches!(
+            app.open_task_output("login"),
+            TaskOutput::Lines(_)
+        ));
+
+        app.toggle_pty_mode();
+        assert!(app.runs_in_pty("login"));
+        assert!(matches!(
+            app.open_task_output("login"),
+            TaskOutput::Terminal(..)
+        ));
+        assert!(app.terminal.is_some());
+        assert!(app.task_output_rx.is_none());
+
+        app.toggle_pty_mode();
+        assert!(!app.runs_in_pty("login"));
+    }
+
+    #[test]
+    fn test_closed_terminal_keeps_its_screen() {
+        let mut app = create_test_app();
+        app.toggle_pty_mode();
+        app.task_output.clear();
+        let TaskOutput::Terminal(output_tx, _size) = app.open_task_output("login") else {
+            panic!("expected PTY output");
+        };
+        output_tx.send(b"Username: admin\r\n".to_vec()).unwrap();
+
+        app.close_terminal();
+        assert!(app.terminal.is_none());
+        assert_eq!(
+            app.task_output
+                .iter()
+                .map(|line| line.text.clone())
+                .collect::<Vec<_>>(),
+            vec!["Username: admin"]
+        );
+    }
+}
@@ -8,6 +8,8 @@ mod terminal;
 mod ui;
 
 use app::App;
+use mise::{FakeMise, MiseClient};
+use models::RunHistory;
 use terminal::{cleanup_terminal, setup_terminal, spawn_input_handler, spawn_tick_handler};
 
 #[tokio::main]
@@ -20,8 +22,13 @@ async fn main() -> Result<()> {
     // Create event channel
     let (event_tx, mut event_rx) = mpsc::unbounded_channel();
 
-    // Create app
-    let mut app = App::new(event_tx.clone());
+    // Create app, playing scripted tasks instead of running mise with --demo
+    let project_dir = std::env::current_dir().unwrap_or_default();
+    let mut app =
+        App::new(event_tx.clone()).with_run_history(RunHistory::in_default_dir(project_dir));
+    if std::env::args().any(|arg| arg == "--demo") {
+        app.client = MiseClient::new(FakeMise::demo());
+    }
     app.initialize().await.context("Failed to initialize app")?;
 
     // Spawn input and tick handlers
@@ -29,19 +36,26 @@ async fn main() -> Result<()> {
     spawn_tick_handler(event_tx.clone());
 
     // Main event loop
-    while let Some(event) = event_rx.recv().await {
-        app.handle_event(event).await?;
-        app.poll_task_output();
+    let result = async {
+        while let Some(event) = event_rx.recv().await {
+            app.handle_event(event).await?;
+            app.poll_task_output();
 
-        terminal.draw(|f| app.draw(f))?;
+            terminal.draw(|f| app.draw(f))?;
 
-        if app.should_quit() {
-            break;
+            if app.should_quit() {
+                break;
+            }
         }
+        anyhow::Ok(())
     }
+    .await;
+
+    // Stop whatever is still running before the runtime drops it
+    app.shutdown().await;
 
     // Cleanup
     cleanup_terminal(terminal)?;
 
-    Ok(())
+    result
 }
@@ -0,0 +1,112 @@
+use anyhow::Result;
+use futures_util::stream::{self, StreamExt};
+use std::future::Future;
+use std::ops::Deref;
+use std::pin::Pin;
+use std::sync::Arc;
+use tokio::sync::{mpsc, watch};
+
+use super::client::MiseCli;
+use crate::models::config_layout::ConfigLayout;
+use crate::models::{MiseTask, MiseTaskInfo, OutputLine, PtySize, TaskResult, UsageSample};
+
+/// Task details fetched at the same time while prewarming
+pub const PREWARM_CONCURRENCY: usize = 4;
+
+/// Future returned by the methods of `MiseBackend`, boxed so backends can be picked at runtime
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// The mise operations the app needs: listing, inspecting, running and editing tasks
+pub trait MiseBackend: Send + Sync {
+    /// List all available tasks
+    fn list_tasks(&self) -> BoxFuture<'_, Result<Vec<MiseTask>>>;
+
+    /// Get detailed information about a specific task
+    fn get_task_info<'a>(&'a self, task_name: &'a str) -> BoxFuture<'a, Result<MiseTaskInfo>>;
+
+    /// Run a task and stream its output lines. The result records how the task exited,
+    /// including non-zero exits and cancellation; an error means it could not be started.
+    /// Setting `cancel` to `true` (or dropping its sender) stops the task, and bytes sent
+    /// through `input_rx` go to its stdin
+    fn run_task<'a>(
+        &'a self,
+        task_name: &'a str,
+        args: &'a [String],
+        output_tx: mpsc::UnboundedSender<OutputLine>,
+        input_rx: Option<mpsc::UnboundedReceiver<Vec<u8>>>,
+        cancel: watch::Receiver<bool>,
+    ) -> BoxFuture<'a, Result<TaskResult>>;
+
+    /// Run a task on a pseudo-terminal of `size`, streaming the raw terminal output.
+    /// Results, input and cancellation work as for `run_task`
+    fn run_task_in_pty<'a>(
+        &'a self,
+        task_name: &'a str,
+        args: &'a [String],
+        output_tx: mpsc::UnboundedSender<Vec<u8>>,
+        input_rx: Option<mpsc::UnboundedReceiver<Vec<u8>>>,
+        size: watch::Receiver<PtySize>,
+        cancel: watch::Receiver<bool>,
+    ) -> BoxFuture<'a, Result<TaskResult>>;
+
+    /// Delete a task from the config or file defining it
+    fn delete_task<'a>(&'a self, task_name: &'a str) -> BoxFuture<'a, Result<()>>;
+
+    /// Rename a task, adding a numeric suffix if the new name is taken
+    fn rename_task<'a>(&'a self, old_name: &'a str, new_name: &'a str)
+        -> BoxFuture<'a, Result<()>>;
+
+    /// Add `task` to mise.toml as `[tasks.<task_name>]`, replacing a task of that name
+    fn add_task<'a>(&'a self, task_name: &'a str, task: toml::Table) -> BoxFuture<'a, Result<()>>;
+
+    /// Details of a task if they are at hand without asking mise, for views that cannot wait
+    fn cached_task_info(&self, task_name: &str) -> Option<MiseTaskInfo>;
+
+    /// Where the config files and task directories defining the tasks are. Backends
+    /// without files on disk have none
+    fn config_layout(&self) -> BoxFuture<'_, ConfigLayout> {
+        Box::pin(async { ConfigLayout::default() })
+    }
+
+    /// Fetch the details of `tasks` ahead of time, a few at once, so later lookups are
+    /// answered from the backend's cache
+    fn prewarm_task_info<'a>(&'a self, tasks: &'a [MiseTask]) -> BoxFuture<'a, ()> {
+        Box::pin(async move {
+            let lookups: Vec<_> = tasks
+                .iter()
+                .map(|task| self.get_task_info(&task.name))
+                .collect();
+            stream::iter(lookups)
+                .buffer_unordered(PREWARM_CONCURRENCY)
+                .count()
+                .await;
+        })
+    }
+
+    /// A copy of this backend that sends the CPU and memory use of the tasks it runs to
+    /// `usage_tx` while they run
+    fn with_usage_reporting(&self, usage_tx: mpsc::UnboundedSender<UsageSample>) -> MiseClient;
+}
+
+/// Shared handle to the backend the app talks to, cheap to clone into spawned runs
+#[derive(Clone)]
+pub struct MiseClient(Arc<dyn MiseBackend>);
+
+impl MiseClient {
+    pub fn new(backend: impl MiseBackend + 'static) -> Self {
+        Self(Arc::new(backend))
+    }
+
+    /// The `mise` binary, configured from the environment, see `MiseCli::from_env`
+    pub fn from_env() -> Self {
+        Self::new(MiseCli::from_env())
+    }
+}
+
+impl Deref for MiseClient {
+    type Target = dyn MiseBackend;
+
+    fn deref(&self) -> &Self::Target {
+        self.0.as_ref()
+    }
+}
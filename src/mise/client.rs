@@ -1,34 +1,123 @@
 use anyhow::{Context, Result};
 use log::{debug, error, info, trace, warn};
-use std::{path::Path, process::Stdio};
+use std::{
+    path::{Path, PathBuf},
+    process::Stdio,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::{Duration, SystemTime},
+};
 use tokio::{
     fs,
-    io::{AsyncBufReadExt, BufReader},
+    io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader},
     process::Command,
-    sync::mpsc,
+    sync::{mpsc, watch},
+    time::timeout,
+};
+
+use super::backend::{BoxFuture, MiseBackend, MiseClient};
+use super::config_edit::ConfigDocument;
+use super::process::{self, TerminateOutcome};
+#[cfg(unix)]
+use super::pty;
+use super::task_cache::TaskCache;
+use super::usage::UsageMonitor;
+use crate::models::config_layout::{parse_config_ls, ConfigLayout};
+use crate::models::{
+    MiseTask, MiseTaskInfo, OutputLine, PtySize, TaskExit, TaskResult, UsageSample,
 };
 
-use crate::models::{MiseTask, MiseTaskInfo};
+/// Environment variable naming the mise binary to run, `mise` from the `PATH` by default
+pub const MISE_BIN_ENV: &str = "MISE_BIN";
+
+/// How long a cancelled task gets after each of SIGINT and SIGTERM before it is escalated
+pub const DEFAULT_KILL_GRACE_PERIOD: Duration = Duration::from_secs(3);
+
+/// Environment variable overriding the kill grace period, in milliseconds
+pub const KILL_GRACE_PERIOD_ENV: &str = "MISE_SEQUENCER_KILL_GRACE_MS";
+
+/// How long a task has to be quiet before an unfinished output line is shown, e.g. a prompt
+const PARTIAL_LINE_DELAY: Duration = Duration::from_millis(200);
 
+/// How long output of a finished task is still read, in case a background process it left
+/// behind keeps its pipes or terminal open
+const OUTPUT_DRAIN_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Backend running the `mise` binary
 #[derive(Clone)]
-pub struct MiseClient;
+pub struct MiseCli {
+    /// Path or name of the mise binary
+    program: String,
+    kill_grace_period: Duration,
+    /// Tasks and task details already fetched, shared by clones
+    cache: TaskCache,
+    /// Where running tasks report their CPU and memory use
+    usage_tx: Option<mpsc::UnboundedSender<UsageSample>>,
+}
 
-impl Default for MiseClient {
+impl Default for MiseCli {
     fn default() -> Self {
         Self::new()
     }
 }
 
-impl MiseClient {
+impl MiseCli {
     pub fn new() -> Self {
-        Self
+        Self {
+            program: "mise".to_string(),
+            kill_grace_period: DEFAULT_KILL_GRACE_PERIOD,
+            cache: TaskCache::default(),
+            usage_tx: None,
+        }
+    }
+
+    /// A client configured from the environment, see `MISE_BIN_ENV` and
+    /// `KILL_GRACE_PERIOD_ENV`
+    pub fn from_env() -> Self {
+        let mut client = Self::new();
+        if let Ok(program) = std::env::var(MISE_BIN_ENV)
+            && !program.is_empty()
+        {
+            client = client.with_program(program);
+        }
+        match std::env::var(KILL_GRACE_PERIOD_ENV) {
+            Ok(value) => match value.parse() {
+                Ok(millis) => client.with_kill_grace_period(Duration::from_millis(millis)),
+                Err(e) => {
+                    warn!("Ignoring invalid {KILL_GRACE_PERIOD_ENV} '{value}': {e}");
+                    client
+                }
+            },
+            Err(_) => client,
+        }
+    }
+
+    pub fn with_program(mut self, program: impl Into<String>) -> Self {
+        self.program = program.into();
+        self
+    }
+
+    pub fn with_kill_grace_period(mut self, kill_grace_period: Duration) -> Self {
+        self.kill_grace_period = kill_grace_period;
+        self
+    }
+
+    fn monitor_usage(
+        &self,
+        child: &tokio::process::Child,
+        task_name: &str,
+    ) -> Option<UsageMonitor> {
+        let pid = child.id()?;
+        Some(UsageMonitor::spawn(pid, task_name, self.usage_tx.clone()))
     }
 
     /// List all available mise tasks
     pub async fn list_tasks(&self) -> Result<Vec<MiseTask>> {
         debug!("Starting mise tasks ls --json command");
 
-        let output = Command::new("mise")
+        let output = Command::new(&self.program)
             .args(["tasks", "ls", "--json"])
             .output()
             .await
@@ -62,14 +151,62 @@ impl MiseClient {
             }
         };
 
+        let layout = self.load_config_layout().await;
+        self.cache.store_tasks(&tasks, &layout);
         Ok(tasks)
     }
 
-    /// Get detailed information about a specific task
+    /// Where the config mise reads here lives, from the cache while the config is unchanged
+    pub async fn config_layout(&self) -> ConfigLayout {
+        match self.cache.layout() {
+            Some(layout) => layout,
+            None => self.load_config_layout().await,
+        }
+    }
+
+    /// Ask mise for the config files it loads, see `ConfigLayout`. Without an answer from
+    /// `mise config ls` only the files mise would pick up if created are known
+    async fn load_config_layout(&self) -> ConfigLayout {
+        let cwd = std::env::current_dir().unwrap_or_default();
+        let loaded = match self.list_config_files().await {
+            Ok(files) => files,
+            Err(e) => {
+                warn!("Failed to list mise config files: {e:#}");
+                Vec::new()
+            }
+        };
+        // The configs are read for their task includes
+        let layout = tokio::task::spawn_blocking(move || ConfigLayout::new(&cwd, loaded)).await;
+        layout.unwrap_or_else(|e| {
+            warn!("Failed to read the mise config layout: {e}");
+            ConfigLayout::default()
+        })
+    }
+
+    async fn list_config_files(&self) -> Result<Vec<PathBuf>> {
+        let output = Command::new(&self.program)
+            .args(["config", "ls", "--json"])
+            .output()
+            .await
+            .context("Failed to execute mise config ls --json")?;
+        if !output.status.success() {
+            let stderr_str = String::from_utf8_lossy(&output.stderr);
+            anyhow::bail!("mise command failed: {}", stderr_str);
+        }
+        let home = std::env::var_os("HOME").map(PathBuf::from);
+        parse_config_ls(&output.stdout, home.as_deref())
+    }
+
+    /// Get detailed information about a specific task, from the cache while the config
+    /// is unchanged
     pub async fn get_task_info(&self, task_name: &str) -> Result<MiseTaskInfo> {
+        if let Some(task_info) = self.cache.info(task_name) {
+            trace!("Using cached task info for '{task_name}'");
+            return Ok(task_info);
+        }
         debug!("Starting mise tasks info for task: {task_name}");
 
-        let output = Command::new("mise")
+        let output = Command::new(&self.program)
             .args(["tasks", "info", task_name, "--json"])
             .output()
             .await
@@ -97,6 +234,7 @@ impl MiseClient {
         let task_info: MiseTaskInfo = match serde_json::from_slice::<MiseTaskInfo>(&output.stdout) {
             Ok(task_info) => {
                 info!("Successfully parsed task info for '{task_name}'");
+                self.cache.store_info(&task_info);
                 task_info
             }
             Err(e) => {
@@ -113,27 +251,127 @@ impl MiseClient {
         Ok(task_info)
     }
 
-    /// Run a specific mise task and stream output
+    /// Run a specific mise task and stream output.
+    /// The returned result records how the task exited, including non-zero exits and
+    /// cancellation; an error means the task could not be started.
+    /// Setting `cancel` to `true` (or dropping its sender) shuts down the task's whole
+    /// process tree, escalating from SIGINT to SIGTERM to SIGKILL.
+    /// Bytes sent through `input_rx` go to the task's stdin, which is closed once the
+    /// sender is dropped; without it the task gets no stdin at all.
     pub async fn run_task(
         &self,
         task_name: &str,
         args: &[String],
-        output_tx: mpsc::UnboundedSender<String>,
-    ) -> Result<()> {
-        let mut cmd = Command::new("mise");
-        cmd.arg("run").arg(task_name);
+        output_tx: mpsc::UnboundedSender<OutputLine>,
+        input_rx: Option<mpsc::UnboundedReceiver<Vec<u8>>>,
+        cancel: watch::Receiver<bool>,
+    ) -> Result<TaskResult> {
+        let mut cmd = mise_run_command(&self.program, task_name, args);
+        cmd.env("FORCE_COLOR", "1").env("CLICOLOR_FORCE", "1");
+
+        self.run_command(cmd, task_name, output_tx, input_rx, cancel)
+            .await
+    }
 
-        for arg in args {
-            cmd.arg(arg);
+    /// Run a specific mise task on a pseudo-terminal, for tasks that prompt or only
+    /// behave properly when attached to a TTY. Raw terminal output goes to `output_tx`,
+    /// and the terminal follows `size` so the task can redraw when the pane is resized.
+    /// Results, input and cancellation work as for `run_task`; only Linux is supported.
+    #[cfg(unix)]
+    pub async fn run_task_in_pty(
+        &self,
+        task_name: &str,
+        args: &[String],
+        output_tx: mpsc::UnboundedSender<Vec<u8>>,
+        input_rx: Option<mpsc::UnboundedReceiver<Vec<u8>>>,
+        mut size: watch::Receiver<PtySize>,
+        mut cancel: watch::Receiver<bool>,
+    ) -> Result<TaskResult> {
+        let mut cmd = mise_run_command(&self.program, task_name, args);
+        let (master, slave) = pty::open(*size.borrow_and_update())?;
+        // The task leads a new session on the terminal, which also makes it a group leader
+        pty::attach(&mut cmd, &slave)?;
+
+        let started_at = SystemTime::now();
+        let mut child = cmd.spawn().context("Failed to spawn mise run command")?;
+        let monitor = self.monitor_usage(&child, task_name);
+        // Once only the task holds the slave end, reads end when it and its children exit
+        drop(cmd);
+        drop(slave);
+        let lines = Arc::new(AtomicUsize::new(0));
+        let mut reader = master.spawn_reader(output_tx.clone(), lines.clone());
+        let writer = input_rx.map(|input_rx| tokio::spawn(forward_input(master.clone(), input_rx)));
+
+        let status = loop {
+            tokio::select! {
+                status = child.wait() => break Some(status?),
+                _ = async { drop(cancel.wait_for(|cancelled| *cancelled).await) } => break None,
+                Ok(()) = size.changed() => master.resize(*size.borrow_and_update()),
+            }
+        };
+        if let Some(writer) = writer {
+            writer.abort();
+        }
+        let peak_usage = monitor.and_then(UsageMonitor::finish);
+        if status.is_none() {
+            info!("Cancelling task '{task_name}'");
+            let outcome = process::terminate(&mut child, self.kill_grace_period).await;
+            let report = format!("\r\n{}\r\n", cancellation_report(task_name, outcome));
+            if output_tx.send(report.into_bytes()).is_err() {
+                eprintln!("Warning: Failed to send task cancellation message");
+            }
         }
+        // A background process may keep the terminal open after the task exits
+        if timeout(OUTPUT_DRAIN_TIMEOUT, &mut reader).await.is_err() {
+            reader.abort();
+        }
+
+        Ok(TaskResult {
+            task_name: task_name.to_string(),
+            exit: status.map_or(TaskExit::Cancelled, exit_of),
+            started_at,
+            finished_at: SystemTime::now(),
+            stdout_lines: lines.load(Ordering::Relaxed),
+            stderr_lines: 0,
+            peak_usage,
+        })
+    }
+
+    #[cfg(not(unix))]
+    pub async fn run_task_in_pty(
+        &self,
+        _task_name: &str,
+        _args: &[String],
+        _output_tx: mpsc::UnboundedSender<Vec<u8>>,
+        _input_rx: Option<mpsc::UnboundedReceiver<Vec<u8>>>,
+        _size: watch::Receiver<PtySize>,
+        _cancel: watch::Receiver<bool>,
+    ) -> Result<TaskResult> {
+        anyhow::bail!("PTY mode is only supported on Linux")
+    }
 
-        cmd.stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .env("FORCE_COLOR", "1")
-            .env("CLICOLOR_FORCE", "1")
-            .env("TERM", "xterm-256color");
+    async fn run_command(
+        &self,
+        mut cmd: Command,
+        task_name: &str,
+        output_tx: mpsc::UnboundedSender<OutputLine>,
+        input_rx: Option<mpsc::UnboundedReceiver<Vec<u8>>>,
+        mut cancel: watch::Receiver<bool>,
+    ) -> Result<TaskResult> {
+        let stdin = if input_rx.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        };
+        cmd.stdin(stdin)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        // Give the task its own process group so cancelling reaches everything it started
+        process::spawn_in_new_group(&mut cmd);
 
+        let started_at = SystemTime::now();
         let mut child = cmd.spawn().context("Failed to spawn mise run command")?;
+        let monitor = self.monitor_usage(&child, task_name);
 
         let stdout = child
             .stdout
@@ -144,47 +382,90 @@ impl MiseClient {
             .take()
             .context("Failed to capture stderr from mise command")?;
 
-        // Spawn tasks to read stdout and stderr
+        let writer = match (child.stdin.take(), input_rx) {
+            (Some(stdin), Some(input_rx)) => Some(tokio::spawn(forward_input(stdin, input_rx))),
+            _ => None,
+        };
+
+        // Spawn tasks to read stdout and stderr, counting lines as they go so the counts
+        // survive readers that have to be abandoned
+        let stdout_count = Arc::new(AtomicUsize::new(0));
         let output_tx_clone = output_tx.clone();
-        tokio::spawn(async move {
-            let reader = BufReader::new(stdout);
-            let mut lines = reader.lines();
-            while let Ok(Some(line)) = lines.next_line().await {
-                if output_tx_clone.send(format!("STDOUT: {line}")).is_err() {
+        let name = task_name.to_string();
+        let count = stdout_count.clone();
+        let mut stdout_reader = tokio::spawn(async move {
+            let mut lines = OutputLines::new(stdout);
+            while let Some((line, complete)) = lines.next().await {
+                count.fetch_add(usize::from(complete), Ordering::Relaxed);
+                let line = OutputLine::stdout(line).with_task(&name);
+                if output_tx_clone.send(line).is_err() {
                     break;
                 }
             }
         });
 
+        let stderr_count = Arc::new(AtomicUsize::new(0));
         let output_tx_clone = output_tx.clone();
-        tokio::spawn(async move {
-            let reader = BufReader::new(stderr);
-            let mut lines = reader.lines();
-            while let Ok(Some(line)) = lines.next_line().await {
-                if output_tx_clone.send(format!("STDERR: {line}")).is_err() {
+        let name = task_name.to_string();
+        let count = stderr_count.clone();
+        let mut stderr_reader = tokio::spawn(async move {
+            let mut lines = OutputLines::new(stderr);
+            while let Some((line, complete)) = lines.next().await {
+                count.fetch_add(usize::from(complete), Ordering::Relaxed);
+                let line = OutputLine::stderr(line).with_task(&name);
+                if output_tx_clone.send(line).is_err() {
                     break;
                 }
             }
         });
 
-        // Wait for the process to complete
-        let status = child.wait().await?;
-
-        let final_message = if status.success() {
-            format!("Task '{task_name}' completed successfully")
-        } else {
-            format!(
-                "Task '{}' failed with exit code: {:?}",
-                task_name,
-                status.code()
-            )
+        // Wait for the process to complete and for its output to be fully read,
+        // so the completion event always comes after the last output line
+        let status = tokio::select! {
+            status = child.wait() => Some(status?),
+            // A dropped sender cancels as well. Quitting cancels runs explicitly, see
+            // `App::shutdown`, since a run dropped with the runtime never gets here
+            _ = async { drop(cancel.wait_for(|cancelled| *cancelled).await) } => None,
+        };
+        if let Some(writer) = writer {
+            writer.abort();
+        }
+        let peak_usage = monitor.and_then(UsageMonitor::finish);
+        let outcome = match status {
+            Some(_) => None,
+            None => {
+                info!("Cancelling task '{task_name}'");
+                Some(process::terminate(&mut child, self.kill_grace_period).await)
+            }
         };
+        // A background process may keep the pipes open after the task exits, or after the
+        // group outlived SIGKILL
+        let drain = async { tokio::join!(&mut stdout_reader, &mut stderr_reader) };
+        if timeout(OUTPUT_DRAIN_TIMEOUT, drain).await.is_err() {
+            stdout_reader.abort();
+            stderr_reader.abort();
+        }
 
-        if output_tx.send(final_message).is_err() {
-            eprintln!("Warning: Failed to send task completion message");
+        if let Some(outcome) = outcome
+            && output_tx
+                .send(
+                    OutputLine::notice(cancellation_report(task_name, outcome))
+                        .with_task(task_name),
+                )
+                .is_err()
+        {
+            eprintln!("Warning: Failed to send task cancellation message");
         }
 
-        Ok(())
+        Ok(TaskResult {
+            task_name: task_name.to_string(),
+            exit: status.map_or(TaskExit::Cancelled, exit_of),
+            started_at,
+            finished_at: SystemTime::now(),
+            stdout_lines: stdout_count.load(Ordering::Relaxed),
+            stderr_lines: stderr_count.load(Ordering::Relaxed),
+            peak_usage,
+        })
     }
 
     /// Delete a mise task
@@ -200,6 +481,7 @@ impl MiseClient {
             // File-based task - delete the file
             self.delete_task_file(source).await?;
         }
+        self.cache.invalidate();
 
         Ok(())
     }
@@ -221,15 +503,18 @@ impl MiseClient {
 
         // Get all existing tasks to check for conflicts
         debug!("Fetching existing tasks for conflict check");
-        let existing_tasks = match self.list_tasks().await {
-            Ok(tasks) => {
-                debug!("Found {} existing tasks", tasks.len());
-                tasks
-            }
-            Err(e) => {
-                error!("Failed to fetch existing tasks: {e}");
-                return Err(e);
-            }
+        let existing_tasks = match self.cache.tasks() {
+            Some(tasks) => tasks,
+            None => match self.list_tasks().await {
+                Ok(tasks) => {
+                    debug!("Found {} existing tasks", tasks.len());
+                    tasks
+                }
+                Err(e) => {
+                    error!("Failed to fetch existing tasks: {e}");
+                    return Err(e);
+                }
+            },
         };
 
         // Find a unique name if there's a conflict
@@ -267,10 +552,18 @@ impl MiseClient {
                 .await?;
         }
 
+        self.cache.invalidate();
         info!("Rename operation completed successfully: '{old_name}' -> '{final_new_name}'");
         Ok(())
     }
 
+    /// Add `task` to mise.toml as `[tasks.<task_name>]`, replacing a task of that name
+    pub async fn add_task(&self, task_name: &str, task: toml::Table) -> Result<()> {
+        edit_config("mise.toml", |config| config.set_task(task_name, &task)).await?;
+        self.cache.invalidate();
+        Ok(())
+    }
+
     /// Find a unique task name by appending -1, -2, etc. if needed
     fn find_unique_task_name(
         &self,
@@ -294,36 +587,7 @@ impl MiseClient {
     }
 
     async fn delete_task_from_config(&self, config_path: &str, task_name: &str) -> Result<()> {
-        // Read the TOML file
-        let content = fs::read_to_string(config_path)
-            .await
-            .context("Failed to read mise.toml file")?;
-
-        // Parse the TOML
-        let mut config: toml::Table = content.parse().context("Failed to parse mise.toml file")?;
-
-        // Remove the task from the [tasks] section
-        if let Some(tasks) = config.get_mut("tasks") {
-            if let Some(tasks_table) = tasks.as_table_mut() {
-                if tasks_table.remove(task_name).is_some() {
-                    // Write the updated content back
-                    let updated_content =
-                        toml::to_string(&config).context("Failed to serialize updated TOML")?;
-
-                    fs::write(config_path, updated_content)
-                        .await
-                        .context("Failed to write updated mise.toml file")?;
-                } else {
-                    anyhow::bail!("Task '{}' not found in tasks section", task_name);
-                }
-            } else {
-                anyhow::bail!("Tasks section is not a table");
-            }
-        } else {
-            anyhow::bail!("No tasks section found in mise.toml");
-        }
-
-        Ok(())
+        edit_config(config_path, |config| config.remove_task(task_name)).await
     }
 
     async fn delete_task_file(&self, file_path: &str) -> Result<()> {
@@ -344,39 +608,7 @@ impl MiseClient {
         old_name: &str,
         new_name: &str,
     ) -> Result<()> {
-        // Read the TOML file
-        let content = fs::read_to_string(config_path)
-            .await
-            .context("Failed to read mise.toml file")?;
-
-        // Parse the TOML
-        let mut config: toml::Table = content.parse().context("Failed to parse mise.toml file")?;
-
-        // Rename the task in the [tasks] section
-        if let Some(tasks) = config.get_mut("tasks") {
-            if let Some(tasks_table) = tasks.as_table_mut() {
-                if let Some(task_config) = tasks_table.remove(old_name) {
-                    // Add the task with the new name
-                    tasks_table.insert(new_name.to_string(), task_config);
-
-                    // Write the updated content back
-                    let updated_content =
-                        toml::to_string(&config).context("Failed to serialize updated TOML")?;
-
-                    fs::write(config_path, updated_content)
-                        .await
-                        .context("Failed to write updated mise.toml file")?;
-                } else {
-                    anyhow::bail!("Task '{}' not found in tasks section", old_name);
-                }
-            } else {
-                anyhow::bail!("Tasks section is not a table");
-            }
-        } else {
-            anyhow::bail!("No tasks section found in mise.toml");
-        }
-
-        Ok(())
+        edit_config(config_path, |config| config.rename_task(old_name, new_name)).await
     }
 
     async fn rename_task_file(
@@ -422,3 +654,366 @@ impl MiseClient {
         Ok(())
     }
 }
+
+// The inherent methods of the same names do the work, and take precedence over these
+impl MiseBackend for MiseCli {
+    fn list_tasks(&self) -> BoxFuture<'_, Result<Vec<MiseTask>>> {
+        Box::pin(MiseCli::list_tasks(self))
+    }
+
+    fn get_task_info<'a>(&'a self, task_name: &'a str) -> BoxFuture<'a, Result<MiseTaskInfo>> {
+        Box::pin(MiseCli::get_task_info(self, task_name))
+    }
+
+    fn run_task<'a>(
+        &'a self,
+        task_name: &'a str,
+        args: &'a [String],
+        output_tx: mpsc::UnboundedSender<OutputLine>,
+        input_rx: Option<mpsc::UnboundedReceiver<Vec<u8>>>,
+        cancel: watch::Receiver<bool>,
+    ) -> BoxFuture<'a, Result<TaskResult>> {
+        Box::pin(MiseCli::run_task(
+            self, task_name, args, output_tx, input_rx, cancel,
+        ))
+    }
+
+    fn run_task_in_pty<'a>(
+        &'a self,
+        task_name: &'a str,
+        args: &'a [String],
+        output_tx: mpsc::UnboundedSender<Vec<u8>>,
+        input_rx: Option<mpsc::UnboundedReceiver<Vec<u8>>>,
+        size: watch::Receiver<PtySize>,
+        cancel: watch::Receiver<bool>,
+    ) -> BoxFuture<'a, Result<TaskResult>> {
+        Box::pin(MiseCli::run_task_in_pty(
+            self, task_name, args, output_tx, input_rx, size, cancel,
+        ))
+    }
+
+    fn delete_task<'a>(&'a self, task_name: &'a str) -> BoxFuture<'a, Result<()>> {
+        Box::pin(MiseCli::delete_task(self, task_name))
+    }
+
+    fn rename_task<'a>(
+        &'a self,
+        old_name: &'a str,
+        new_name: &'a str,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(MiseCli::rename_task(self, old_name, new_name))
+    }
+
+    fn add_task<'a>(&'a self, task_name: &'a str, task: toml::Table) -> BoxFuture<'a, Result<()>> {
+        Box::pin(MiseCli::add_task(self, task_name, task))
+    }
+
+    fn cached_task_info(&self, task_name: &str) -> Option<MiseTaskInfo> {
+        self.cache.info(task_name)
+    }
+
+    fn config_layout(&self) -> BoxFuture<'_, ConfigLayout> {
+        Box::pin(MiseCli::config_layout(self))
+    }
+
+    fn with_usage_reporting(&self, usage_tx: mpsc::UnboundedSender<UsageSample>) -> MiseClient {
+        MiseClient::new(Self {
+            usage_tx: Some(usage_tx),
+            ..self.clone()
+        })
+    }
+}
+
+/// Apply `edit` to the config file at `path`, leaving everything it does not touch as is
+async fn edit_config(
+    path: &str,
+    edit: impl FnOnce(&mut ConfigDocument) -> Result<()>,
+) -> Result<()> {
+    let content = fs::read_to_string(path)
+        .await
+        .with_context(|| format!("Failed to read {path}"))?;
+    let mut config =
+        ConfigDocument::parse(&content).with_context(|| format!("Failed to parse {path}"))?;
+    edit(&mut config)?;
+    fs::write(path, config.to_string())
+        .await
+        .with_context(|| format!("Failed to write updated {path}"))
+}
+
+/// `mise run <task> <args>`, with a terminal type tasks can rely on for colors
+fn mise_run_command(program: &str, task_name: &str, args: &[String]) -> Command {
+    let mut cmd = Command::new(program);
+    cmd.arg("run").arg(task_name).args(args);
+    cmd.env("TERM", "xterm-256color");
+    // Backstop for runs dropped without being cancelled: at least mise itself goes down
+    cmd.kill_on_drop(true);
+    cmd
+}
+
+/// Lines a task writes to a pipe. A line still being written is passed on as well once the
+/// task goes quiet, so prompts without a trailing newline show up while they wait
+struct OutputLines<R> {
+    reader: BufReader<R>,
+    line: Vec<u8>,
+}
+
+impl<R: AsyncRead + Unpin> OutputLines<R> {
+    fn new(reader: R) -> Self {
+        Self {
+            reader: BufReader::new(reader),
+            line: Vec::new(),
+        }
+    }
+
+    /// The next line, or part of one, and whether it completes a line
+    async fn next(&mut self) -> Option<(String, bool)> {
+        loop {
+            // Partly read bytes stay in `line` when the read times out
+            let read = timeout(
+                PARTIAL_LINE_DELAY,
+                self.reader.read_until(b'\n', &mut self.line),
+            )
+            .await;
+            match read {
+                Ok(Ok(0)) | Ok(Err(_)) if self.line.is_empty() => return None,
+                Ok(_) => return Some((self.take_line(), true)),
+                Err(_) if !self.line.is_empty() => return Some((self.take_line(), false)),
+                Err(_) => {}
+            }
+        }
+    }
+
+    fn take_line(&mut self) -> String {
+        let mut line = std::mem::take(&mut self.line);
+        if line.ends_with(b"\n") {
+            line.pop();
+            if line.ends_with(b"\r") {
+                line.pop();
+            }
+        }
+        String::from_utf8_lossy(&line).into_owned()
+    }
+}
+
+/// Copy input for a task to its stdin until the input is closed, which closes stdin
+async fn forward_input(
+    mut stdin: impl AsyncWrite + Unpin,
+    mut input_rx: mpsc::UnboundedReceiver<Vec<u8>>,
+) {
+    while let Some(bytes) = input_rx.recv().await {
+        if stdin.write_all(&bytes).await.is_err() || stdin.flush().await.is_err() {
+            break;
+        }
+    }
+}
+
+/// What happened to the process tree of a cancelled task, for its output
+fn cancellation_report(task_name: &str, outcome: TerminateOutcome) -> String {
+    match outcome {
+        TerminateOutcome::Exited(signal) => {
+            format!("Task '{task_name}' and its subprocesses exited after {signal}")
+        }
+        TerminateOutcome::StillRunning => format!(
+            "Warning: processes started by task '{task_name}' are still running after SIGKILL"
+        ),
+    }
+}
+
+/// Map a process exit status onto a task exit, keeping the signal that killed it
+fn exit_of(status: std::process::ExitStatus) -> TaskExit {
+    #[cfg(unix)]
+    if let Some(signal) = std::os::unix::process::ExitStatusExt::signal(&status) {
+        return TaskExit::Signal(signal);
+    }
+    TaskExit::Code(status.code().unwrap_or(-1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mise::task_cache::STAMP_CHECK_INTERVAL;
+    use crate::models::LineSource;
+
+    #[tokio::test]
+    async fn test_run_task_uses_configured_binary() {
+        let (output_tx, mut output_rx) = mpsc::unbounded_channel();
+        let (_cancel_tx, cancel) = watch::channel(false);
+
+        let result = MiseCli::new()
+            .with_program("echo")
+            .run_task("build", &["--release".to_string()], output_tx, None, cancel)
+            .await
+            .unwrap();
+
+        assert!(result.success());
+        assert_eq!(output_rx.try_recv().unwrap().text, "run build --release");
+    }
+
+    #[tokio::test]
+    async fn test_task_info_is_cached_until_config_changes() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir =
+            std::env::temp_dir().join(format!("mise-sequencer-cli-cache-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let config = dir.join("mise.toml");
+        std::fs::write(&config, "").unwrap();
+        let task = MiseTask::new("build".to_string(), config.display().to_string());
+        let ls = serde_json::to_string(std::slice::from_ref(&task)).unwrap();
+        std::fs::write(dir.join("tasks-ls.json"), ls).unwrap();
+        let info = serde_json::to_string(&MiseTaskInfo::from(task)).unwrap();
+        std::fs::write(dir.join("tasks-info.json"), info).unwrap();
+        let configs = format!(r#"[{{"path": "{}"}}]"#, config.display());
+        std::fs::write(dir.join("config-ls.json"), configs).unwrap();
+        // Stands in for `mise <tasks|config> <ls|info> ...`, logging the task commands
+        let program = dir.join("mise");
+        std::fs::write(
+            &program,
+            "#!/bin/sh\ncd \"$(dirname \"$0\")\"\n[ \"$1\" = tasks ] && echo \"$2\" >> calls\ncat \"$1-$2.json\"\n",
+        )
+        .unwrap();
+        std::fs::set_permissions(&program, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let calls = || std::fs::read_to_string(dir.join("calls")).unwrap();
+
+        let cli = MiseCli::new().with_program(program.display().to_string());
+        cli.list_tasks().await.unwrap();
+        cli.prewarm_task_info(&cli.list_tasks().await.unwrap())
+            .await;
+        assert_eq!(cli.get_task_info("build").await.unwrap().name, "build");
+        assert!(cli.cached_task_info("build").is_some());
+        assert_eq!(calls(), "ls\nls\ninfo\n");
+
+        // Editing the config makes the next lookup ask mise again
+        let file = std::fs::File::options().write(true).open(&config).unwrap();
+        file.set_modified(SystemTime::now() + Duration::from_secs(10))
+            .unwrap();
+        tokio::time::sleep(STAMP_CHECK_INTERVAL).await;
+        assert!(cli.cached_task_info("build").is_none());
+        cli.get_task_info("build").await.unwrap();
+        assert_eq!(calls(), "ls\nls\ninfo\ninfo\n");
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_run_command_forwards_input() {
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c")
+            .arg("read answer; echo \"got $answer\"; cat >/dev/null; echo eof");
+        let (output_tx, mut output_rx) = mpsc::unbounded_channel();
+        let (input_tx, input_rx) = mpsc::unbounded_channel();
+        let (_cancel_tx, cancel) = watch::channel(false);
+
+        input_tx.send(b"yes\n".to_vec()).unwrap();
+        // Closing the input closes stdin, so `cat` finishes
+        drop(input_tx);
+        let result = MiseCli::new()
+            .run_command(cmd, "prompt", output_tx, Some(input_rx), cancel)
+            .await
+            .unwrap();
+
+        assert!(result.success());
+        let line = output_rx.try_recv().unwrap();
+        assert_eq!(line.text, "got yes");
+        assert_eq!(line.source, LineSource::Stdout);
+        assert_eq!(line.task.as_deref(), Some("prompt"));
+        assert_eq!(output_rx.try_recv().unwrap().text, "eof");
+    }
+
+    #[tokio::test]
+    async fn test_run_command_does_not_wait_for_background_output() {
+        // The background sleep keeps stdout open after the shell exits
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c").arg("sleep 3 & echo started");
+        let (output_tx, mut output_rx) = mpsc::unbounded_channel();
+        let (_cancel_tx, cancel) = watch::channel(false);
+
+        let started = std::time::Instant::now();
+        let result = MiseCli::new()
+            .run_command(cmd, "daemon", output_tx, None, cancel)
+            .await
+            .unwrap();
+
+        assert!(started.elapsed() < Duration::from_secs(2));
+        assert!(result.success());
+        assert_eq!(result.stdout_lines, 1);
+        assert_eq!(output_rx.try_recv().unwrap().text, "started");
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn test_pty_run_does_not_wait_for_background_processes() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir =
+            std::env::temp_dir().join(format!("mise-sequencer-pty-daemon-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        // Stands in for `mise run`, leaving a process that holds the terminal behind
+        let program = dir.join("mise");
+        std::fs::write(&program, "#!/bin/sh\nsleep 3 &\necho started\n").unwrap();
+        std::fs::set_permissions(&program, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let (output_tx, _output_rx) = mpsc::unbounded_channel();
+        let (_size_tx, size) = watch::channel(PtySize { rows: 24, cols: 80 });
+        let (_cancel_tx, cancel) = watch::channel(false);
+
+        let started = std::time::Instant::now();
+        let result = MiseCli::new()
+            .with_program(program.display().to_string())
+            .run_task_in_pty("daemon", &[], output_tx, None, size, cancel)
+            .await
+            .unwrap();
+
+        assert!(started.elapsed() < Duration::from_secs(2));
+        assert!(result.success());
+        assert_eq!(result.stdout_lines, 1);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_unfinished_line_shows_while_waiting_for_input() {
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c")
+            .arg("printf 'Continue? [y/N] '; read answer; echo \"$answer\"");
+        let (output_tx, mut output_rx) = mpsc::unbounded_channel();
+        let (input_tx, input_rx) = mpsc::unbounded_channel();
+        let (_cancel_tx, cancel) = watch::channel(false);
+
+        let client = MiseCli::new();
+        let run = client.run_command(cmd, "prompt", output_tx, Some(input_rx), cancel);
+        let answer = async {
+            let prompt = output_rx.recv().await.unwrap();
+            input_tx.send(b"y\n".to_vec()).unwrap();
+            prompt
+        };
+        let (result, prompt) = tokio::join!(run, answer);
+
+        assert_eq!(prompt.text, "Continue? [y/N] ");
+        assert_eq!(output_rx.try_recv().unwrap().text, "y");
+        // Only the answer completed a line
+        assert_eq!(result.unwrap().stdout_lines, 1);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn test_run_command_reports_usage() {
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c").arg("sleep 1.3; true");
+        let (output_tx, _output_rx) = mpsc::unbounded_channel();
+        let (usage_tx, mut usage_rx) = mpsc::unbounded_channel();
+        let (_cancel_tx, cancel) = watch::channel(false);
+
+        let client = MiseCli {
+            usage_tx: Some(usage_tx),
+            ..MiseCli::new()
+        };
+        let result = client
+            .run_command(cmd, "sleepy", output_tx, None, cancel)
+            .await
+            .unwrap();
+
+        let sample = usage_rx.try_recv().unwrap();
+        assert_eq!(sample.task_name, "sleepy");
+        assert_eq!(sample.usage.children, 1);
+        assert_eq!(result.peak_usage, Some(sample.usage));
+    }
+}
@@ -0,0 +1,279 @@
+use anyhow::{Context, Result};
+use std::fmt;
+use toml_edit::{DocumentMut, InlineTable, Item, Key, TableLike};
+
+/// A mise config file, edited in place: comments, key order and formatting outside the
+/// changed task are written back exactly as they were read
+#[derive(Debug, Clone)]
+pub struct ConfigDocument(DocumentMut);
+
+impl ConfigDocument {
+    pub fn parse(content: &str) -> Result<Self> {
+        Ok(Self(content.parse()?))
+    }
+
+    fn tasks_mut(&mut self) -> Result<&mut dyn TableLike> {
+        self.0
+            .get_mut("tasks")
+            .context("No tasks section found in mise.toml")?
+            .as_table_like_mut()
+            .context("Tasks section is not a table")
+    }
+
+    /// Remove a task, along with the comments directly above it
+    pub fn remove_task(&mut self, task_name: &str) -> Result<()> {
+        self.tasks_mut()?
+            .remove(task_name)
+            .with_context(|| format!("Task '{task_name}' not found in tasks section"))?;
+        Ok(())
+    }
+
+    /// Rename a task, keeping its place among the other tasks
+    pub fn rename_task(&mut self, old_name: &str, new_name: &str) -> Result<()> {
+        let tasks = self.tasks_mut()?;
+        if !tasks.contains_key(old_name) {
+            anyhow::bail!("Task '{}' not found in tasks section", old_name);
+        }
+
+        // Tables only append, so take every task out and put them back in order
+        let names: Vec<String> = tasks.iter().map(|(name, _)| name.to_string()).collect();
+        let mut entries = Vec::with_capacity(names.len());
+        for name in names {
+            let key = tasks.key(&name).cloned().unwrap_or_else(|| Key::new(&name));
+            if let Some(item) = tasks.remove(&name) {
+                entries.push((key, item));
+            }
+        }
+        for (key, item) in entries {
+            let key = if key.get() == old_name {
+                Key::new(new_name)
+                    .with_leaf_decor(key.leaf_decor().clone())
+                    .with_dotted_decor(key.dotted_decor().clone())
+            } else {
+                key
+            };
+            tasks.entry_format(&key).or_insert(item);
+        }
+        Ok(())
+    }
+
+    /// Set `[tasks.<task_name>]` to `task`. A task of that name is replaced where it
+    /// stands, otherwise the task goes after the others and the tasks section is
+    /// created if needed
+    pub fn set_task(&mut self, task_name: &str, task: &toml::Table) -> Result<()> {
+        let mut table = toml_edit::Table::new();
+        for (key, value) in task {
+            table.insert(key, Item::Value(edit_value(value)));
+        }
+
+        let tasks = self
+            .0
+            .entry("tasks")
+            .or_insert_with(|| {
+                let mut tasks = toml_edit::Table::new();
+                tasks.set_implicit(true);
+                Item::Table(tasks)
+            })
+            .as_table_like_mut()
+            .context("Tasks section is not a table")?;
+        match tasks.get_mut(task_name) {
+            Some(Item::Table(existing)) => {
+                if let Some(position) = existing.position() {
+                    table.set_position(position);
+                }
+                *table.decor_mut() = existing.decor().clone();
+                *existing = table;
+            }
+            Some(existing @ Item::Value(_)) => {
+                let decor = existing.as_value().map(|value| value.decor().clone());
+                let mut value = toml_edit::Value::InlineTable(table.into_inline_table());
+                if let Some(decor) = decor {
+                    *value.decor_mut() = decor;
+                }
+                *existing = Item::Value(value);
+            }
+            Some(existing) => *existing = Item::Table(table),
+            None => {
+                tasks.insert(task_name, Item::Table(table));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// `value` as written in a document, nested tables inline
+fn edit_value(value: &toml::Value) -> toml_edit::Value {
+    match value {
+        toml::Value::String(string) => string.as_str().into(),
+        toml::Value::Integer(integer) => (*integer).into(),
+        toml::Value::Float(float) => (*float).into(),
+        toml::Value::Boolean(boolean) => (*boolean).into(),
+        toml::Value::Datetime(datetime) => (*datetime).into(),
+        toml::Value::Array(items) => items
+            .iter()
+            .map(edit_value)
+            .collect::<toml_edit::Array>()
+            .into(),
+        toml::Value::Table(table) => table
+            .iter()
+            .map(|(key, value)| (key.as_str(), edit_value(value)))
+            .collect::<InlineTable>()
+            .into(),
+    }
+}
+
+impl fmt::Display for ConfigDocument {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::SequenceState;
+
+    const CONFIG: &str = r#"# Project tools
+[tools]
+node = "22"
+
+# Build everything
+[tasks.build]
+run = "cargo build"   # debug build
+description = "Build"
+
+[tasks.test]
+depends = ["build"]
+run = "cargo test"
+
+[env]
+RUST_LOG = "info"
+"#;
+
+    fn edit(content: &str, change: impl FnOnce(&mut ConfigDocument) -> Result<()>) -> String {
+        let mut doc = ConfigDocument::parse(content).unwrap();
+        change(&mut doc).unwrap();
+        doc.to_string()
+    }
+
+    #[test]
+    fn test_remove_task_keeps_the_rest_of_the_file() {
+        let updated = edit(CONFIG, |doc| doc.remove_task("build"));
+        assert_eq!(
+            updated,
+            r#"# Project tools
+[tools]
+node = "22"
+
+[tasks.test]
+depends = ["build"]
+run = "cargo test"
+
+[env]
+RUST_LOG = "info"
+"#
+        );
+
+        let mut doc = ConfigDocument::parse(CONFIG).unwrap();
+        let err = doc.remove_task("deploy").unwrap_err();
+        assert_eq!(err.to_string(), "Task 'deploy' not found in tasks section");
+        let mut doc = ConfigDocument::parse("[env]\n").unwrap();
+        let err = doc.remove_task("build").unwrap_err();
+        assert_eq!(err.to_string(), "No tasks section found in mise.toml");
+    }
+
+    #[test]
+    fn test_rename_task_in_place() {
+        let updated = edit(CONFIG, |doc| doc.rename_task("build", "compile"));
+        assert_eq!(
+            updated,
+            CONFIG.replace("[tasks.build]", "[tasks.compile]"),
+            "only the header changes"
+        );
+
+        let config = "[tasks]\n# Lint first\nlint = \"cargo clippy\"\nfmt = { run = \"cargo fmt\" } # format\n";
+        let updated = edit(config, |doc| doc.rename_task("lint", "clippy"));
+        assert_eq!(updated, config.replace("lint =", "clippy ="));
+        let updated = edit(config, |doc| doc.rename_task("fmt", "my task"));
+        assert_eq!(updated, config.replace("fmt =", "\"my task\" ="));
+    }
+
+    #[test]
+    fn test_set_task() {
+        let mut task = toml::Table::new();
+        task.insert(
+            "depends".to_string(),
+            toml::Value::Array(vec!["build".into(), "test".into()]),
+        );
+        task.insert("description".to_string(), "Ship it".into());
+
+        // New tasks go after the existing ones
+        let updated = edit(CONFIG, |doc| doc.set_task("release", &task));
+        assert_eq!(
+            updated,
+            CONFIG.replace(
+                "[env]",
+                "[tasks.release]\ndepends = [\"build\", \"test\"]\ndescription = \"Ship it\"\n\n[env]"
+            )
+        );
+
+        // Existing ones are replaced where they are
+        let updated = edit(CONFIG, |doc| doc.set_task("build", &task));
+        assert_eq!(
+            updated,
+            CONFIG.replace(
+                "run = \"cargo build\"   # debug build\ndescription = \"Build\"",
+                "depends = [\"build\", \"test\"]\ndescription = \"Ship it\""
+            )
+        );
+        let updated = edit("[tasks]\nbuild = \"make\" # old\n", |doc| {
+            doc.set_task("build", &task)
+        });
+        assert_eq!(
+            updated,
+            "[tasks]\nbuild = { depends = [\"build\", \"test\"], description = \"Ship it\" } # old\n"
+        );
+
+        // The tasks section is created when missing
+        let updated = edit("# Empty\n[env]\nA = \"1\"\n", |doc| {
+            doc.set_task("release", &task)
+        });
+        assert_eq!(
+            updated,
+            "# Empty\n[env]\nA = \"1\"\n\n[tasks.release]\ndepends = [\"build\", \"test\"]\ndescription = \"Ship it\"\n"
+        );
+    }
+
+    #[test]
+    fn test_set_task_with_sequence_steps() {
+        let mut seq = SequenceState::new(2);
+        seq.set_task_step("build", 0, true);
+        seq.set_task_step("lint", 1, true);
+        seq.set_task_step("test", 1, true);
+        let task = seq.to_mise_task("Release").unwrap();
+
+        let updated = edit(CONFIG, |doc| doc.set_task("release", &task));
+        assert_eq!(
+            updated,
+            CONFIG.replace(
+                "[env]",
+                "[tasks.release]\ndescription = \"Release\"\nrun = [{ task = \"build\" }, { tasks = [\"lint\", \"test\"] }]\n\n[env]"
+            )
+        );
+        let reread: toml::Table = toml::from_str(&updated).unwrap();
+        assert_eq!(reread["tasks"]["release"], toml::Value::Table(task));
+    }
+
+    #[test]
+    fn test_set_task_keeps_value_types() {
+        let task: toml::Table =
+            toml::from_str("at = 2024-05-01T10:00:00Z\nquiet = true\nretries = 2\ntimeout = 1.5\n")
+                .unwrap();
+
+        let updated = edit("", |doc| doc.set_task("wait", &task));
+        assert_eq!(
+            updated,
+            "[tasks.wait]\nat = 2024-05-01T10:00:00Z\nquiet = true\nretries = 2\ntimeout = 1.5\n"
+        );
+    }
+}
@@ -0,0 +1,453 @@
+use anyhow::{bail, Context, Result};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, SystemTime};
+use tokio::sync::{mpsc, watch};
+
+use super::backend::{BoxFuture, MiseBackend, MiseClient};
+use crate::models::mise_task::RunEntry;
+use crate::models::{
+    LineSource, MiseTask, MiseTaskInfo, OutputLine, PtySize, TaskExit, TaskResult, UsageSample,
+};
+
+/// What a task of `FakeMise` does when run: the lines it prints, how long it takes
+/// and how it exits
+#[derive(Debug, Clone)]
+pub struct ScriptedRun {
+    output: Vec<OutputLine>,
+    /// Wait before each output line and before exiting
+    delay: Duration,
+    exit_code: i32,
+}
+
+impl Default for ScriptedRun {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ScriptedRun {
+    /// A run printing nothing and exiting with 0 straight away
+    pub fn new() -> Self {
+        Self {
+            output: Vec::new(),
+            delay: Duration::ZERO,
+            exit_code: 0,
+        }
+    }
+
+    pub fn stdout(mut self, line: impl Into<String>) -> Self {
+        self.output.push(OutputLine::stdout(line));
+        self
+    }
+
+    pub fn stderr(mut self, line: impl Into<String>) -> Self {
+        self.output.push(OutputLine::stderr(line));
+        self
+    }
+
+    pub fn delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    pub fn exit_code(mut self, exit_code: i32) -> Self {
+        self.exit_code = exit_code;
+        self
+    }
+}
+
+#[derive(Default)]
+struct FakeState {
+    tasks: Vec<MiseTask>,
+    runs: HashMap<String, ScriptedRun>,
+    /// Tasks run so far, with their arguments
+    run_log: Vec<(String, Vec<String>)>,
+}
+
+/// In-memory backend whose tasks play a `ScriptedRun` instead of running anything, for
+/// tests and `--demo`. Clones share the same tasks
+#[derive(Clone, Default)]
+pub struct FakeMise {
+    state: Arc<Mutex<FakeState>>,
+}
+
+impl FakeMise {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add `task`, which plays `run` when run
+    pub fn with_task(self, task: MiseTask, run: ScriptedRun) -> Self {
+        {
+            let mut state = self.state();
+            state.runs.insert(task.name.clone(), run);
+            state.tasks.push(task);
+        }
+        self
+    }
+
+    /// A small project to try the app on without mise: builds with warnings, a failing
+    /// lint, a slow deploy and a task running the others
+    pub fn demo() -> Self {
+        let task = |name: &str, description: &str| {
+            let mut task = MiseTask::new(name.to_string(), "mise.toml".to_string());
+            task.description = description.to_string();
+            task
+        };
+        let step = Duration::from_millis(400);
+        let mut release = task("release", "Build and deploy");
+        release.run = vec![
+            RunEntry::Task {
+                task: "build".to_string(),
+            },
+            RunEntry::Task {
+                task: "deploy".to_string(),
+            },
+        ];
+        let mut deploy = ScriptedRun::new().delay(Duration::from_secs(1));
+        for server in 1..=5 {
+            deploy = deploy.stdout(format!("Deploying to server {server}/5..."));
+        }
+
+        Self::new()
+            .with_task(
+                task("build", "Compile the project"),
+                ScriptedRun::new()
+                    .delay(step)
+                    .stdout("   Compiling demo v0.1.0")
+                    .stderr("warning: unused variable: `count`")
+                    .stderr("  --> src/main.rs:12:9")
+                    .stdout("    Finished release profile in 2.1s"),
+            )
+            .with_task(
+                task("test", "Run the test suite"),
+                ScriptedRun::new()
+                    .delay(step)
+                    .stdout("running 12 tests")
+                    .stdout("test result: ok. 12 passed; 0 failed"),
+            )
+            .with_task(
+                task("lint", "Check formatting and style"),
+                ScriptedRun::new()
+                    .delay(step)
+                    .stderr("src/lib.rs:3:1: error: missing documentation")
+                    .exit_code(1),
+            )
+            .with_task(
+                task("deploy", "Ship the release"),
+                deploy.stdout("Deployed"),
+            )
+            .with_task(
+                release,
+                ScriptedRun::new()
+                    .delay(step)
+                    .stdout("Running build, then deploy"),
+            )
+    }
+
+    /// Tasks run so far with their arguments, oldest first
+    pub fn run_log(&self) -> Vec<(String, Vec<String>)> {
+        self.state().run_log.clone()
+    }
+
+    fn state(&self) -> MutexGuard<'_, FakeState> {
+        // A panicking test thread leaves nothing half-updated worth refusing
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Play the script of `task_name`, handing each line to `emit` until it returns false
+    async fn play(
+        &self,
+        task_name: &str,
+        args: &[String],
+        mut emit: impl FnMut(OutputLine) -> bool,
+        mut cancel: watch::Receiver<bool>,
+    ) -> Result<TaskResult> {
+        let script = {
+            let mut state = self.state();
+            if !state.tasks.iter().any(|task| task.name == task_name) {
+                bail!("Task '{task_name}' not found");
+            }
+            state.run_log.push((task_name.to_string(), args.to_vec()));
+            state.runs.get(task_name).cloned().unwrap_or_default()
+        };
+
+        let started_at = SystemTime::now();
+        let (mut stdout_lines, mut stderr_lines) = (0, 0);
+        let mut cancelled = false;
+        for line in script.output.iter().map(Some).chain([None]) {
+            tokio::select! {
+                () = tokio::time::sleep(script.delay) => {}
+                // A dropped sender also cancels, as for real tasks
+                _ = async { drop(cancel.wait_for(|cancelled| *cancelled).await) } => {
+                    cancelled = true;
+                    break;
+                }
+            }
+            let Some(line) = line else {
+                break;
+            };
+            match line.source {
+                LineSource::Stderr => stderr_lines += 1,
+                _ => stdout_lines += 1,
+            }
+            if !emit(line.clone().with_task(task_name)) {
+                break;
+            }
+        }
+
+        Ok(TaskResult {
+            task_name: task_name.to_string(),
+            exit: if cancelled {
+                TaskExit::Cancelled
+            } else {
+                TaskExit::Code(script.exit_code)
+            },
+            started_at,
+            finished_at: SystemTime::now(),
+            stdout_lines,
+            stderr_lines,
+            peak_usage: None,
+        })
+    }
+}
+
+impl MiseBackend for FakeMise {
+    fn list_tasks(&self) -> BoxFuture<'_, Result<Vec<MiseTask>>> {
+        let tasks = self.state().tasks.clone();
+        Box::pin(async move { Ok(tasks) })
+    }
+
+    fn get_task_info<'a>(&'a self, task_name: &'a str) -> BoxFuture<'a, Result<MiseTaskInfo>> {
+        let task = self
+            .state()
+            .tasks
+            .iter()
+            .find(|task| task.name == task_name)
+            .cloned();
+        Box::pin(async move {
+            let task = task.with_context(|| format!("Task '{task_name}' not found"))?;
+            Ok(MiseTaskInfo::from(task))
+        })
+    }
+
+    fn run_task<'a>(
+        &'a self,
+        task_name: &'a str,
+        args: &'a [String],
+        output_tx: mpsc::UnboundedSender<OutputLine>,
+        _input_rx: Option<mpsc::UnboundedReceiver<Vec<u8>>>,
+        cancel: watch::Receiver<bool>,
+    ) -> BoxFuture<'a, Result<TaskResult>> {
+        Box::pin(self.play(
+            task_name,
+            args,
+            move |line| output_tx.send(line).is_ok(),
+            cancel,
+        ))
+    }
+
+    fn run_task_in_pty<'a>(
+        &'a self,
+        task_name: &'a str,
+        args: &'a [String],
+        output_tx: mpsc::UnboundedSender<Vec<u8>>,
+        _input_rx: Option<mpsc::UnboundedReceiver<Vec<u8>>>,
+        _size: watch::Receiver<PtySize>,
+        cancel: watch::Receiver<bool>,
+    ) -> BoxFuture<'a, Result<TaskResult>> {
+        Box::pin(async move {
+            let emit = |line: OutputLine| output_tx.send(format!("{}\r\n", line.text).into_bytes());
+            let result = self
+                .play(task_name, args, |line| emit(line).is_ok(), cancel)
+                .await?;
+            // A terminal has a single stream
+            Ok(TaskResult {
+                stdout_lines: result.stdout_lines + result.stderr_lines,
+                stderr_lines: 0,
+                ..result
+            })
+        })
+    }
+
+    fn delete_task<'a>(&'a self, task_name: &'a str) -> BoxFuture<'a, Result<()>> {
+        let result = {
+            let mut state = self.state();
+            let before = state.tasks.len();
+            state.tasks.retain(|task| task.name != task_name);
+            state.runs.remove(task_name);
+            if state.tasks.len() == before {
+                Err(anyhow::anyhow!("Task '{task_name}' not found"))
+            } else {
+                Ok(())
+            }
+        };
+        Box::pin(async move { result })
+    }
+
+    fn rename_task<'a>(
+        &'a self,
+        old_name: &'a str,
+        new_name: &'a str,
+    ) -> BoxFuture<'a, Result<()>> {
+        let result = (|| {
+            if new_name.trim().is_empty() {
+                bail!("New task name cannot be empty");
+            }
+            let mut state = self.state();
+            // Like mise, other tasks keep their names and the new one gets a suffix
+            let taken = |name: &str| {
+                state
+                    .tasks
+                    .iter()
+                    .any(|task| task.name == name && task.name != old_name)
+            };
+            let mut final_name = new_name.to_string();
+            let mut counter = 1;
+            while taken(&final_name) {
+                final_name = format!("{new_name}-{counter}");
+                counter += 1;
+            }
+            let task = state
+                .tasks
+                .iter_mut()
+                .find(|task| task.name == old_name)
+                .with_context(|| format!("Task '{old_name}' not found"))?;
+            task.name = final_name.clone();
+            if let Some(run) = state.runs.remove(old_name) {
+                state.runs.insert(final_name, run);
+            }
+            Ok(())
+        })();
+        Box::pin(async move { result })
+    }
+
+    fn add_task<'a>(&'a self, task_name: &'a str, task: toml::Table) -> BoxFuture<'a, Result<()>> {
+        let result = (|| {
+            let mut added = MiseTask::new(task_name.to_string(), "mise.toml".to_string());
+            if let Some(description) = task.get("description").and_then(|value| value.as_str()) {
+                added.description = description.to_string();
+            }
+            if let Some(depends) = task.get("depends") {
+                added.depends = depends.clone().try_into().context("Invalid depends")?;
+            }
+            if let Some(run) = task.get("run") {
+                added.run = run.clone().try_into().context("Invalid run")?;
+            }
+            let mut state = self.state();
+            state.tasks.retain(|task| task.name != task_name);
+            state.tasks.push(added);
+            Ok(())
+        })();
+        Box::pin(async move { result })
+    }
+
+    fn cached_task_info(&self, task_name: &str) -> Option<MiseTaskInfo> {
+        self.state()
+            .tasks
+            .iter()
+            .find(|task| task.name == task_name)
+            .cloned()
+            .map(MiseTaskInfo::from)
+    }
+
+    fn with_usage_reporting(&self, _usage_tx: mpsc::UnboundedSender<UsageSample>) -> MiseClient {
+        // Scripted runs have no processes to sample
+        MiseClient::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fake() -> FakeMise {
+        FakeMise::new().with_task(
+            MiseTask::new("build".to_string(), "mise.toml".to_string()),
+            ScriptedRun::new()
+                .stdout("compiling")
+                .stderr("warning")
+                .exit_code(2),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_run_plays_script() {
+        let fake = fake();
+        let (output_tx, mut output_rx) = mpsc::unbounded_channel();
+        let (_cancel_tx, cancel) = watch::channel(false);
+        let args = vec!["--release".to_string()];
+
+        let result = fake
+            .run_task("build", &args, output_tx, None, cancel)
+            .await
+            .unwrap();
+        assert_eq!(result.exit, TaskExit::Code(2));
+        assert_eq!((result.stdout_lines, result.stderr_lines), (1, 1));
+        let line = output_rx.try_recv().unwrap();
+        assert_eq!(line.text, "compiling");
+        assert_eq!(line.task.as_deref(), Some("build"));
+        assert_eq!(output_rx.try_recv().unwrap().source, LineSource::Stderr);
+        assert_eq!(fake.run_log(), vec![("build".to_string(), args)]);
+
+        let (output_tx, _output_rx) = mpsc::unbounded_channel();
+        let (_cancel_tx, cancel) = watch::channel(false);
+        assert!(fake
+            .run_task("missing", &[], output_tx, None, cancel)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_cancel_stops_delayed_run() {
+        let fake = FakeMise::new().with_task(
+            MiseTask::new("deploy".to_string(), "mise.toml".to_string()),
+            ScriptedRun::new()
+                .delay(Duration::from_secs(60))
+                .stdout("done"),
+        );
+        let (output_tx, mut output_rx) = mpsc::unbounded_channel();
+        let (cancel_tx, cancel) = watch::channel(false);
+
+        let run = fake.run_task("deploy", &[], output_tx, None, cancel);
+        let (result, _) = tokio::join!(run, async { cancel_tx.send(true).unwrap() });
+        assert_eq!(result.unwrap().exit, TaskExit::Cancelled);
+        assert!(output_rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_edits_change_task_list() {
+        let fake = fake();
+        let mut task = toml::Table::new();
+        task.insert("description".to_string(), "Sequence: build".into());
+        task.insert(
+            "depends".to_string(),
+            toml::Value::Array(vec!["build".into()]),
+        );
+        fake.add_task("ci", task).await.unwrap();
+        fake.rename_task("ci", "check").await.unwrap();
+
+        let info = fake.get_task_info("check").await.unwrap();
+        assert_eq!(info.description, "Sequence: build");
+        assert_eq!(
+            info.sequence_steps().unwrap(),
+            vec![vec!["build".to_string()]]
+        );
+
+        // Taken names get a suffix
+        fake.rename_task("check", "build").await.unwrap();
+        fake.delete_task("build").await.unwrap();
+        let names: Vec<String> = fake
+            .list_tasks()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|task| task.name)
+            .collect();
+        assert_eq!(names, vec!["build-1".to_string()]);
+        assert!(fake.delete_task("build").await.is_err());
+    }
+}
@@ -0,0 +1,213 @@
+use anyhow::Result;
+#[cfg(target_os = "linux")]
+use std::collections::HashMap;
+#[cfg(target_os = "linux")]
+use std::os::fd::OwnedFd;
+#[cfg(target_os = "linux")]
+use std::path::PathBuf;
+use std::time::Duration;
+#[cfg(target_os = "linux")]
+use tokio::io::unix::AsyncFd;
+
+use crate::models::file_watch::{WatchSet, WATCH_DEBOUNCE_MS};
+
+/// Watches the directories of a `WatchSet` with inotify and reports when its files change
+pub struct FileWatcher {
+    set: WatchSet,
+    #[cfg(target_os = "linux")]
+    fd: AsyncFd<OwnedFd>,
+    /// Watched directory of each watch descriptor
+    #[cfg(target_os = "linux")]
+    watches: HashMap<i32, PathBuf>,
+}
+
+/// Why watching fails on other platforms, the same from every entry point
+#[cfg(not(target_os = "linux"))]
+const UNSUPPORTED: &str = "Watching files is only supported on Linux";
+
+#[cfg(target_os = "linux")]
+const WATCH_MASK: u32 = libc::IN_CLOSE_WRITE
+    | libc::IN_CREATE
+    | libc::IN_DELETE
+    | libc::IN_MOVED_FROM
+    | libc::IN_MOVED_TO;
+
+impl FileWatcher {
+    /// Start watching every directory `set` can match files in
+    #[cfg(target_os = "linux")]
+    pub fn new(set: WatchSet) -> Result<Self> {
+        use anyhow::Context;
+        use std::os::fd::FromRawFd;
+
+        // SAFETY: inotify_init1 takes no pointers
+        let fd = unsafe { libc::inotify_init1(libc::IN_NONBLOCK | libc::IN_CLOEXEC) };
+        if fd == -1 {
+            return Err(std::io::Error::last_os_error()).context("Failed to start inotify");
+        }
+        // SAFETY: inotify_init1 succeeded, so the descriptor is open and owned by nobody else
+        let fd = unsafe { OwnedFd::from_raw_fd(fd) };
+        let mut watcher = Self {
+            set,
+            fd: AsyncFd::new(fd)?,
+            watches: HashMap::new(),
+        };
+        watcher.add_watches()?;
+        Ok(watcher)
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn new(_set: WatchSet) -> Result<Self> {
+        anyhow::bail!(UNSUPPORTED)
+    }
+
+    /// Watch the directories of the set not watched yet, e.g. ones created since
+    #[cfg(target_os = "linux")]
+    fn add_watches(&mut self) -> Result<()> {
+        use anyhow::Context;
+        use std::os::fd::AsRawFd;
+        use std::os::unix::ffi::OsStrExt;
+
+        for dir in self.set.directories() {
+            if self.watches.values().any(|watched| *watched == dir) {
+                continue;
+            }
+            let path = std::ffi::CString::new(dir.as_os_str().as_bytes())?;
+            // SAFETY: the path is a valid C string for the duration of the call
+            let wd = unsafe {
+                libc::inotify_add_watch(self.fd.get_ref().as_raw_fd(), path.as_ptr(), WATCH_MASK)
+            };
+            if wd == -1 {
+                return Err(std::io::Error::last_os_error())
+                    .with_context(|| format!("Failed to watch {}", dir.display()));
+            }
+            self.watches.insert(wd, dir);
+        }
+        Ok(())
+    }
+
+    /// Wait for events and tell whether one of them was about a watched file
+    #[cfg(target_os = "linux")]
+    async fn next_change(&mut self) -> Result<bool> {
+        use std::os::fd::AsRawFd;
+        use std::os::unix::ffi::OsStrExt;
+
+        const HEADER: usize = std::mem::size_of::<libc::inotify_event>();
+        let mut buffer = [0u8; 4096];
+        let len = loop {
+            let mut guard = self.fd.readable().await?;
+            let result = guard.try_io(|fd| {
+                // SAFETY: the buffer is valid for writes of its whole length
+                let len =
+                    unsafe { libc::read(fd.as_raw_fd(), buffer.as_mut_ptr().cast(), buffer.len()) };
+                if len < 0 {
+                    Err(std::io::Error::last_os_error())
+                } else {
+                    Ok(len as usize)
+                }
+            });
+            if let Ok(len) = result {
+                break len?;
+            }
+        };
+
+        let mut changed = false;
+        let mut new_dirs = false;
+        let mut offset = 0;
+        while offset + HEADER <= len {
+            // SAFETY: the kernel wrote a whole event header at this offset
+            let event: libc::inotify_event =
+                unsafe { std::ptr::read_unaligned(buffer[offset..].as_ptr().cast()) };
+            let name = &buffer[offset + HEADER..offset + HEADER + event.len as usize];
+            let name = name.split(|&byte| byte == 0).next().unwrap_or_default();
+            offset += HEADER + event.len as usize;
+
+            // The kernel dropped events, so anything may have changed, new directories too
+            if event.mask & libc::IN_Q_OVERFLOW != 0 {
+                changed = true;
+                new_dirs = true;
+                continue;
+            }
+            let Some(dir) = self.watches.get(&event.wd) else {
+                continue;
+            };
+            let path = dir.join(std::ffi::OsStr::from_bytes(name));
+            if event.mask & libc::IN_ISDIR != 0 {
+                new_dirs |= event.mask & (libc::IN_CREATE | libc::IN_MOVED_TO) != 0;
+            } else {
+                changed |= self.set.matches(&path);
+            }
+            if event.mask & libc::IN_IGNORED != 0 {
+                self.watches.remove(&event.wd);
+            }
+        }
+        if new_dirs {
+            self.add_watches()?;
+        }
+        Ok(changed)
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    async fn next_change(&mut self) -> Result<bool> {
+        anyhow::bail!(UNSUPPORTED)
+    }
+
+    /// Call `on_change` with the number of watched files each time they change, once
+    /// things have been quiet for the debounce time. Ends when `on_change` returns false
+    pub async fn run(mut self, mut on_change: impl FnMut(usize) -> bool) -> Result<()> {
+        let debounce = Duration::from_millis(WATCH_DEBOUNCE_MS);
+        loop {
+            while !self.next_change().await? {}
+            // Further changes push the run back until they stop
+            while let Ok(result) = tokio::time::timeout(debounce, self.next_change()).await {
+                result?;
+            }
+            if !on_change(self.set.files().len()) {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Number of watched files that exist now
+    pub fn file_count(&self) -> usize {
+        self.set.files().len()
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+    use tokio::sync::mpsc;
+
+    #[tokio::test]
+    async fn test_reports_changes_to_watched_files() {
+        let dir =
+            std::env::temp_dir().join(format!("mise-sequencer-watcher-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("main.rs"), "").unwrap();
+
+        let set = WatchSet::new(&dir, &["**/*.rs".to_string()]);
+        let watcher = FileWatcher::new(set).unwrap();
+        assert_eq!(watcher.file_count(), 1);
+        let (changed_tx, mut changed_rx) = mpsc::unbounded_channel();
+        let handle = tokio::spawn(watcher.run(move |files| changed_tx.send(files).is_ok()));
+
+        // Unwatched files and a new directory change nothing
+        std::fs::write(dir.join("notes.md"), "").unwrap();
+        std::fs::create_dir(dir.join("lib")).unwrap();
+        tokio::time::sleep(Duration::from_millis(WATCH_DEBOUNCE_MS * 2)).await;
+        assert!(changed_rx.try_recv().is_err());
+
+        // Files in the new directory are watched too, bursts are reported once
+        std::fs::write(dir.join("lib/a.rs"), "").unwrap();
+        std::fs::write(dir.join("main.rs"), "fn main() {}").unwrap();
+        let files = tokio::time::timeout(Duration::from_secs(5), changed_rx.recv())
+            .await
+            .unwrap();
+        assert_eq!(files, Some(2));
+        tokio::time::sleep(Duration::from_millis(WATCH_DEBOUNCE_MS * 2)).await;
+        assert!(changed_rx.try_recv().is_err());
+
+        handle.abort();
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
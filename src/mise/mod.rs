@@ -1,3 +1,13 @@
+pub mod backend;
 pub mod client;
+pub mod config_edit;
+pub mod fake;
+pub mod file_watcher;
+pub mod process;
+#[cfg(unix)]
+pub mod pty;
+pub mod task_cache;
+pub mod usage;
 
-pub use client::MiseClient;
+pub use backend::MiseClient;
+pub use fake::FakeMise;
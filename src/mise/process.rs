@@ -0,0 +1,156 @@
+use log::debug;
+use std::fmt;
+use std::time::Duration;
+use tokio::process::{Child, Command};
+use tokio::time::{sleep, timeout, Instant};
+
+/// How often a terminating process group is checked for remaining processes
+const GROUP_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Signals sent to a cancelled task, in order, each followed by the grace period
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Signal {
+    Interrupt,
+    Terminate,
+    Kill,
+}
+
+impl Signal {
+    const ESCALATION: [Signal; 3] = [Self::Interrupt, Self::Terminate, Self::Kill];
+
+    #[cfg(unix)]
+    fn number(self) -> libc::c_int {
+        match self {
+            Self::Interrupt => libc::SIGINT,
+            Self::Terminate => libc::SIGTERM,
+            Self::Kill => libc::SIGKILL,
+        }
+    }
+}
+
+impl fmt::Display for Signal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Interrupt => write!(f, "SIGINT"),
+            Self::Terminate => write!(f, "SIGTERM"),
+            Self::Kill => write!(f, "SIGKILL"),
+        }
+    }
+}
+
+/// How a cancelled task's process tree ended up
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TerminateOutcome {
+    /// Every process in the group exited after this signal
+    Exited(Signal),
+    /// Some processes survived SIGKILL, e.g. because they are stuck in the kernel
+    StillRunning,
+}
+
+/// Start the command as the leader of a new process group, so the group id is its pid
+pub fn spawn_in_new_group(cmd: &mut Command) {
+    #[cfg(unix)]
+    cmd.process_group(0);
+}
+
+/// Shut down `child` and everything it started, sending SIGINT, SIGTERM and finally
+/// SIGKILL to its process group and waiting `grace_period` after each for the group to exit
+pub async fn terminate(child: &mut Child, grace_period: Duration) -> TerminateOutcome {
+    let Some(pgid) = child.id() else {
+        // Already reaped, nothing left to signal
+        return TerminateOutcome::Exited(Signal::Interrupt);
+    };
+
+    for signal in Signal::ESCALATION {
+        debug!("Sending {signal} to process group {pgid}");
+        signal_group(child, pgid, signal);
+        if wait_for_group_exit(child, pgid, grace_period).await {
+            return TerminateOutcome::Exited(signal);
+        }
+    }
+    TerminateOutcome::StillRunning
+}
+
+/// Wait until the group leader has been reaped and no process is left in the group
+async fn wait_for_group_exit(child: &mut Child, pgid: u32, grace_period: Duration) -> bool {
+    let deadline = Instant::now() + grace_period;
+    if timeout(grace_period, child.wait()).await.is_err() {
+        return false;
+    }
+
+    while group_exists(pgid) {
+        if Instant::now() >= deadline {
+            return false;
+        }
+        sleep(GROUP_POLL_INTERVAL).await;
+    }
+    true
+}
+
+#[cfg(unix)]
+fn signal_group(_child: &mut Child, pgid: u32, signal: Signal) {
+    // SAFETY: killpg has no memory safety requirements, a stale group id only yields ESRCH
+    unsafe {
+        libc::killpg(pgid as libc::pid_t, signal.number());
+    }
+}
+
+#[cfg(not(unix))]
+fn signal_group(child: &mut Child, _pgid: u32, _signal: Signal) {
+    // Without process groups the best we can do is kill the direct child
+    let _ = child.start_kill();
+}
+
+#[cfg(unix)]
+fn group_exists(pgid: u32) -> bool {
+    // SAFETY: signal 0 only checks whether the group has any process we may signal
+    unsafe { libc::killpg(pgid as libc::pid_t, 0) == 0 }
+}
+
+#[cfg(not(unix))]
+fn group_exists(_pgid: u32) -> bool {
+    false
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    fn spawn_shell(script: &str) -> Child {
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c").arg(script);
+        spawn_in_new_group(&mut cmd);
+        cmd.spawn().unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_terminate_stops_grandchildren_with_sigint() {
+        // The shell waits on a grandchild that shares its process group
+        let mut child = spawn_shell("sleep 30; echo done");
+        let pgid = child.id().unwrap();
+
+        let outcome = terminate(&mut child, Duration::from_secs(5)).await;
+
+        assert_eq!(outcome, TerminateOutcome::Exited(Signal::Interrupt));
+        assert!(!group_exists(pgid));
+    }
+
+    #[tokio::test]
+    async fn test_terminate_escalates_when_signals_are_ignored() {
+        let mut child = spawn_shell("trap '' INT TERM; while :; do :; done");
+        let pgid = child.id().unwrap();
+        // Let the shell install its traps before signalling it
+        sleep(Duration::from_millis(200)).await;
+
+        let outcome = terminate(&mut child, Duration::from_millis(300)).await;
+
+        assert_eq!(outcome, TerminateOutcome::Exited(Signal::Kill));
+        assert!(!group_exists(pgid));
+    }
+
+    #[test]
+    fn test_signal_names() {
+        let names: Vec<String> = Signal::ESCALATION.iter().map(Signal::to_string).collect();
+        assert_eq!(names, vec!["SIGINT", "SIGTERM", "SIGKILL"]);
+    }
+}
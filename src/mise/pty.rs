@@ -0,0 +1,258 @@
+use anyhow::Result;
+use std::io;
+use std::os::fd::{AsRawFd, OwnedFd};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::task::{ready, Context, Poll};
+use tokio::io::unix::AsyncFd;
+use tokio::io::AsyncWrite;
+use tokio::process::Command;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+use crate::models::PtySize;
+
+/// The master end of a pseudo-terminal. It is read and written without blocking, so
+/// reading can be given up on while a background process still holds the slave end.
+/// Clones share the same descriptor
+#[derive(Debug, Clone)]
+pub struct Master(Arc<AsyncFd<OwnedFd>>);
+
+impl Master {
+    /// Must be called within the runtime, which is told when the terminal is ready
+    #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+    fn new(fd: OwnedFd) -> io::Result<Self> {
+        set_nonblocking(&fd)?;
+        Ok(Self(Arc::new(AsyncFd::new(fd)?)))
+    }
+
+    /// Tell the terminal, and through SIGWINCH the task, about a new size
+    #[cfg(target_os = "linux")]
+    pub fn resize(&self, size: PtySize) {
+        let winsize = winsize(size);
+        // SAFETY: TIOCSWINSZ only reads the winsize struct
+        unsafe {
+            libc::ioctl(self.0.as_raw_fd(), libc::TIOCSWINSZ as _, &winsize);
+        }
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn resize(&self, _size: PtySize) {}
+
+    /// Forward everything the task writes to the terminal until every process holding the
+    /// slave end has exited, counting the lines seen in `lines`. Aborting the reader is
+    /// how a run stops waiting for processes it left behind
+    pub fn spawn_reader(
+        &self,
+        output_tx: mpsc::UnboundedSender<Vec<u8>>,
+        lines: Arc<AtomicUsize>,
+    ) -> JoinHandle<()> {
+        let terminal = self.0.clone();
+        tokio::spawn(async move {
+            let mut buffer = [0; 4096];
+            loop {
+                let Ok(mut guard) = terminal.readable().await else {
+                    break;
+                };
+                let read = match guard.try_io(|fd| read(fd.as_raw_fd(), &mut buffer)) {
+                    Ok(read) => read,
+                    Err(_would_block) => continue,
+                };
+                // Reading fails with EIO once the slave end is closed
+                let Ok(read @ 1..) = read else {
+                    break;
+                };
+                let bytes = &buffer[..read];
+                let new_lines = bytes.iter().filter(|&&byte| byte == b'\n').count();
+                lines.fetch_add(new_lines, Ordering::Relaxed);
+                if output_tx.send(bytes.to_vec()).is_err() {
+                    break;
+                }
+            }
+        })
+    }
+}
+
+/// Input typed into the terminal
+impl AsyncWrite for Master {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bytes: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        loop {
+            let mut guard = ready!(self.0.poll_write_ready(cx))?;
+            if let Ok(result) = guard.try_io(|fd| write(fd.as_raw_fd(), bytes)) {
+                return Poll::Ready(result);
+            }
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+fn read(fd: i32, buffer: &mut [u8]) -> io::Result<usize> {
+    // SAFETY: the buffer is valid for writes of its length
+    let read = unsafe { libc::read(fd, buffer.as_mut_ptr().cast(), buffer.len()) };
+    if read < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(read as usize)
+}
+
+fn write(fd: i32, bytes: &[u8]) -> io::Result<usize> {
+    // SAFETY: the buffer is valid for reads of its length
+    let written = unsafe { libc::write(fd, bytes.as_ptr().cast(), bytes.len()) };
+    if written < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(written as usize)
+}
+
+fn set_nonblocking(fd: &OwnedFd) -> io::Result<()> {
+    // SAFETY: F_GETFL and F_SETFL on a descriptor we own have no memory safety requirements
+    unsafe {
+        let flags = libc::fcntl(fd.as_raw_fd(), libc::F_GETFL);
+        if flags == -1 || libc::fcntl(fd.as_raw_fd(), libc::F_SETFL, flags | libc::O_NONBLOCK) == -1
+        {
+            return Err(io::Error::last_os_error());
+        }
+    }
+    Ok(())
+}
+
+/// Open a pseudo-terminal of `size`, returning its master and slave ends
+#[cfg(target_os = "linux")]
+pub fn open(size: PtySize) -> Result<(Master, OwnedFd)> {
+    use anyhow::Context;
+    use std::os::fd::FromRawFd;
+
+    let mut master = -1;
+    let mut slave = -1;
+    let winsize = winsize(size);
+    // SAFETY: openpty only writes the two descriptors, name and termios may be null
+    let result = unsafe {
+        libc::openpty(
+            &mut master,
+            &mut slave,
+            std::ptr::null_mut(),
+            std::ptr::null(),
+            &winsize,
+        )
+    };
+    if result != 0 {
+        return Err(std::io::Error::last_os_error()).context("Failed to open a pseudo-terminal");
+    }
+
+    // SAFETY: openpty succeeded, so both descriptors are open and owned by nobody else
+    let (master, slave) = unsafe { (OwnedFd::from_raw_fd(master), OwnedFd::from_raw_fd(slave)) };
+    // The task gets the slave as its stdio, no other descriptor may leak into it
+    set_cloexec(&master);
+    set_cloexec(&slave);
+    let master = Master::new(master).context("Failed to watch the pseudo-terminal")?;
+    Ok((master, slave))
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn open(_size: PtySize) -> Result<(Master, OwnedFd)> {
+    anyhow::bail!("PTY mode is only supported on Linux")
+}
+
+/// Run `cmd` on the terminal's slave end as the leader of a new session, with the
+/// terminal as its controlling terminal. The session id doubles as the process group id
+#[cfg(target_os = "linux")]
+pub fn attach(cmd: &mut Command, slave: &OwnedFd) -> Result<()> {
+    use std::process::Stdio;
+
+    cmd.stdin(Stdio::from(slave.try_clone()?))
+        .stdout(Stdio::from(slave.try_clone()?))
+        .stderr(Stdio::from(slave.try_clone()?));
+
+    // SAFETY: only async-signal-safe calls run between fork and exec
+    unsafe {
+        cmd.pre_exec(|| {
+            if libc::setsid() == -1 || libc::ioctl(0, libc::TIOCSCTTY as _, 0) == -1 {
+                return Err(std::io::Error::last_os_error());
+            }
+            Ok(())
+        });
+    }
+    Ok(())
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn attach(_cmd: &mut Command, _slave: &OwnedFd) -> Result<()> {
+    anyhow::bail!("PTY mode is only supported on Linux")
+}
+
+#[cfg(target_os = "linux")]
+fn winsize(size: PtySize) -> libc::winsize {
+    libc::winsize {
+        ws_row: size.rows,
+        ws_col: size.cols,
+        ws_xpixel: 0,
+        ws_ypixel: 0,
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn set_cloexec(fd: &OwnedFd) {
+    // SAFETY: F_SETFD on a descriptor we own has no memory safety requirements
+    unsafe {
+        libc::fcntl(fd.as_raw_fd(), libc::F_SETFD, libc::FD_CLOEXEC);
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_task_sees_a_terminal_of_the_requested_size() {
+        let (master, slave) = open(PtySize {
+            rows: 30,
+            cols: 100,
+        })
+        .unwrap();
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c").arg("test -t 1 && echo tty; stty size");
+        attach(&mut cmd, &slave).unwrap();
+
+        let mut child = cmd.spawn().unwrap();
+        drop(cmd);
+        drop(slave);
+
+        let (output_tx, mut output_rx) = mpsc::unbounded_channel();
+        let lines = Arc::new(AtomicUsize::new(0));
+        let reader = master.spawn_reader(output_tx, lines.clone());
+        assert!(child.wait().await.unwrap().success());
+        reader.await.unwrap();
+
+        let mut output = Vec::new();
+        while let Ok(bytes) = output_rx.try_recv() {
+            output.extend(bytes);
+        }
+        assert_eq!(String::from_utf8_lossy(&output), "tty\r\n30 100\r\n");
+        assert_eq!(lines.load(Ordering::Relaxed), 2);
+    }
+
+    #[tokio::test]
+    async fn test_reader_can_be_stopped_while_the_terminal_is_held_open() {
+        let (master, slave) = open(PtySize { rows: 24, cols: 80 }).unwrap();
+        let (output_tx, _output_rx) = mpsc::unbounded_channel();
+        let reader = master.spawn_reader(output_tx, Arc::new(AtomicUsize::new(0)));
+
+        // The slave end stays open, as with a daemon left behind by a task
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        reader.abort();
+        assert!(reader.await.unwrap_err().is_cancelled());
+        drop(slave);
+    }
+}
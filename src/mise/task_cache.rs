@@ -0,0 +1,181 @@
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::models::config_layout::ConfigLayout;
+use crate::models::{MiseTask, MiseTaskInfo};
+
+/// How often lookups check the config for changes. Lookups in between, like those of every
+/// frame drawn, trust the cache
+pub const STAMP_CHECK_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Modification times of the config files and task directories, and of the files the
+/// listed tasks are defined in, when they were listed. Files that did not exist are stamped too, so creating one
+/// counts as a change
+#[derive(Debug)]
+struct ConfigStamp(Vec<(PathBuf, Option<SystemTime>)>);
+
+impl ConfigStamp {
+    fn of(tasks: &[MiseTask], layout: &ConfigLayout) -> Self {
+        let files: BTreeSet<PathBuf> = tasks
+            .iter()
+            .map(|task| PathBuf::from(&task.source))
+            .chain(layout.paths().cloned())
+            .collect();
+        Self(
+            files
+                .into_iter()
+                .map(|file| {
+                    let modified = modified(&file);
+                    (file, modified)
+                })
+                .collect(),
+        )
+    }
+
+    /// Whether none of the files changed, appeared or went away since
+    fn is_current(&self) -> bool {
+        self.0
+            .iter()
+            .all(|(file, modified_at)| modified(file) == *modified_at)
+    }
+}
+
+fn modified(file: &Path) -> Option<SystemTime> {
+    std::fs::metadata(file)
+        .and_then(|meta| meta.modified())
+        .ok()
+}
+
+#[derive(Debug, Default)]
+struct CacheState {
+    /// Set by the last listing, nothing is cached without it
+    stamp: Option<ConfigStamp>,
+    checked_at: Option<Instant>,
+    layout: ConfigLayout,
+    tasks: Vec<MiseTask>,
+    infos: HashMap<String, MiseTaskInfo>,
+}
+
+/// Task list and task details from mise, kept until a file defining the tasks changes.
+/// Clones share the same cache
+#[derive(Debug, Clone, Default)]
+pub struct TaskCache(Arc<Mutex<CacheState>>);
+
+impl TaskCache {
+    fn state(&self) -> MutexGuard<'_, CacheState> {
+        let mut state = self
+            .0
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let due = state
+            .checked_at
+            .is_none_or(|checked_at| checked_at.elapsed() >= STAMP_CHECK_INTERVAL);
+        if due && let Some(stamp) = &state.stamp {
+            if stamp.is_current() {
+                state.checked_at = Some(Instant::now());
+            } else {
+                *state = CacheState::default();
+            }
+        }
+        state
+    }
+
+    /// Start over from a fresh listing, dropping the details of the previous one.
+    /// `layout` holds the files that may change the tasks besides their sources
+    pub fn store_tasks(&self, tasks: &[MiseTask], layout: &ConfigLayout) {
+        *self.state() = CacheState {
+            stamp: Some(ConfigStamp::of(tasks, layout)),
+            checked_at: Some(Instant::now()),
+            layout: layout.clone(),
+            tasks: tasks.to_vec(),
+            infos: HashMap::new(),
+        };
+    }
+
+    /// The listed tasks, unless their config changed since
+    pub fn tasks(&self) -> Option<Vec<MiseTask>> {
+        let state = self.state();
+        state.stamp.as_ref().map(|_| state.tasks.clone())
+    }
+
+    /// The config layout of the listing, unless the config changed since
+    pub fn layout(&self) -> Option<ConfigLayout> {
+        let state = self.state();
+        state.stamp.as_ref().map(|_| state.layout.clone())
+    }
+
+    pub fn store_info(&self, info: &MiseTaskInfo) {
+        let mut state = self.state();
+        if state.stamp.is_some() {
+            state.infos.insert(info.name.clone(), info.clone());
+        }
+    }
+
+    /// Details of a task, unless they were not fetched since the config last changed
+    pub fn info(&self, task_name: &str) -> Option<MiseTaskInfo> {
+        self.state().infos.get(task_name).cloned()
+    }
+
+    /// Forget everything, after the app changed the config itself
+    pub fn invalidate(&self) {
+        *self.state() = CacheState::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cache_follows_config_changes() {
+        let dir = std::env::temp_dir().join(format!("mise-sequencer-cache-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("mise.toml");
+        std::fs::write(&path, "[tasks.build]").unwrap();
+        let layout = ConfigLayout {
+            project_root: dir.clone(),
+            config_files: vec![dir.join("mise.local.toml")],
+            task_dirs: [(dir.join("mise-tasks"), dir.clone())].into(),
+        };
+        let task = MiseTask::new("build".to_string(), path.display().to_string());
+        let cache = TaskCache::default();
+
+        // Details are only kept for a listing
+        cache.store_info(&MiseTaskInfo::from(task.clone()));
+        assert!(cache.info("build").is_none());
+
+        cache.store_tasks(std::slice::from_ref(&task), &layout);
+        cache.store_info(&MiseTaskInfo::from(task.clone()));
+        assert_eq!(cache.info("build").unwrap().name, "build");
+        assert_eq!(cache.tasks().unwrap().len(), 1);
+        assert_eq!(cache.layout().unwrap(), layout);
+
+        // Editing the config drops everything, once it is checked again
+        let file = std::fs::File::options().write(true).open(&path).unwrap();
+        file.set_modified(SystemTime::now() + Duration::from_secs(10))
+            .unwrap();
+        assert!(cache.info("build").is_some());
+        std::thread::sleep(STAMP_CHECK_INTERVAL);
+        assert!(cache.info("build").is_none());
+        assert!(cache.tasks().is_none());
+
+        // So does creating a config file that did not exist
+        cache.store_tasks(std::slice::from_ref(&task), &layout);
+        std::fs::write(&layout.config_files[0], "").unwrap();
+        std::thread::sleep(STAMP_CHECK_INTERVAL);
+        assert!(cache.tasks().is_none());
+
+        // And creating a task directory
+        cache.store_tasks(std::slice::from_ref(&task), &layout);
+        std::fs::create_dir(dir.join("mise-tasks")).unwrap();
+        std::thread::sleep(STAMP_CHECK_INTERVAL);
+        assert!(cache.layout().is_none());
+
+        cache.store_tasks(std::slice::from_ref(&task), &ConfigLayout::default());
+        cache.invalidate();
+        assert!(cache.tasks().is_none());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
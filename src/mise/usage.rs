@@ -0,0 +1,242 @@
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio::sync::{mpsc, watch};
+use tokio::task::JoinHandle;
+use tokio::time::{interval_at, Instant};
+
+use crate::models::resource_usage::{ResourceUsage, UsageSample};
+
+/// How often a running task's process tree is sampled
+pub const USAGE_SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// What `/proc/<pid>/stat` tells about one process
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ProcessStat {
+    ppid: u32,
+    /// User and system time, in clock ticks
+    cpu_ticks: u64,
+    rss_pages: u64,
+}
+
+/// Parse the fields after the command name, which is in parentheses and may hold spaces
+fn parse_stat(stat: &str) -> Option<ProcessStat> {
+    let fields: Vec<&str> = stat[stat.rfind(')')? + 1..].split_whitespace().collect();
+    // Counted from the state, the third field of the line
+    let field = |number: usize| fields.get(number - 3)?.parse::<u64>().ok();
+    Some(ProcessStat {
+        ppid: field(4)? as u32,
+        cpu_ticks: field(14)? + field(15)?,
+        rss_pages: field(24)?,
+    })
+}
+
+#[cfg(target_os = "linux")]
+fn read_processes() -> HashMap<u32, ProcessStat> {
+    let Ok(entries) = std::fs::read_dir("/proc") else {
+        return HashMap::new();
+    };
+    entries
+        .flatten()
+        .filter_map(|entry| entry.file_name().to_str()?.parse::<u32>().ok())
+        .filter_map(|pid| {
+            let stat = std::fs::read_to_string(format!("/proc/{pid}/stat")).ok()?;
+            Some((pid, parse_stat(&stat)?))
+        })
+        .collect()
+}
+
+#[cfg(not(target_os = "linux"))]
+fn read_processes() -> HashMap<u32, ProcessStat> {
+    HashMap::new()
+}
+
+/// `root` and every process below it
+fn process_tree(processes: &HashMap<u32, ProcessStat>, root: u32) -> Vec<u32> {
+    let mut tree = vec![root];
+    let mut next = 0;
+    while let Some(&parent) = tree.get(next) {
+        tree.extend(
+            processes
+                .iter()
+                .filter(|(_, stat)| stat.ppid == parent)
+                .map(|(&pid, _)| pid),
+        );
+        next += 1;
+    }
+    tree
+}
+
+/// Samples the CPU and memory use of a process and its descendants from `/proc`
+#[derive(Debug)]
+pub struct ProcessTreeSampler {
+    root: u32,
+    /// CPU ticks of each process at the previous sample
+    ticks: HashMap<u32, u64>,
+    sampled_at: Instant,
+    ticks_per_second: f64,
+    page_size: u64,
+}
+
+impl ProcessTreeSampler {
+    pub fn new(root: u32) -> Self {
+        // SAFETY: sysconf only reads configuration values
+        let (ticks_per_second, page_size) = unsafe {
+            (
+                libc::sysconf(libc::_SC_CLK_TCK),
+                libc::sysconf(libc::_SC_PAGESIZE),
+            )
+        };
+        Self {
+            root,
+            ticks: HashMap::new(),
+            sampled_at: Instant::now(),
+            ticks_per_second: ticks_per_second.max(1) as f64,
+            page_size: page_size.max(1) as u64,
+        }
+    }
+
+    /// Usage since the previous sample, or since the sampler was created.
+    /// `None` once the root process is gone
+    pub fn sample(&mut self) -> Option<ResourceUsage> {
+        let processes = read_processes();
+        if !processes.contains_key(&self.root) {
+            return None;
+        }
+        let tree = process_tree(&processes, self.root);
+
+        let now = Instant::now();
+        let wall_secs = now.duration_since(self.sampled_at).as_secs_f64();
+        let mut ticks = HashMap::new();
+        let mut busy_ticks = 0;
+        let mut rss_pages = 0;
+        for pid in &tree {
+            let stat = processes[pid];
+            // Processes started since the last sample count in full
+            busy_ticks += stat
+                .cpu_ticks
+                .saturating_sub(self.ticks.get(pid).copied().unwrap_or(0));
+            rss_pages += stat.rss_pages;
+            ticks.insert(*pid, stat.cpu_ticks);
+        }
+        self.ticks = ticks;
+        self.sampled_at = now;
+
+        let cpu_percent = if wall_secs > 0.0 {
+            busy_ticks as f64 / self.ticks_per_second / wall_secs * 100.0
+        } else {
+            0.0
+        };
+        Some(ResourceUsage {
+            cpu_percent,
+            rss_bytes: rss_pages * self.page_size,
+            children: tree.len() - 1,
+        })
+    }
+}
+
+/// Watches a task's process tree while it runs, reporting each sample and keeping the peaks
+pub struct UsageMonitor {
+    handle: JoinHandle<()>,
+    peak: watch::Receiver<Option<ResourceUsage>>,
+}
+
+impl UsageMonitor {
+    /// Start sampling the tree below `pid`, sending samples to `usage_tx` if given
+    pub fn spawn(
+        pid: u32,
+        task_name: &str,
+        usage_tx: Option<mpsc::UnboundedSender<UsageSample>>,
+    ) -> Self {
+        let (peak_tx, peak) = watch::channel(None::<ResourceUsage>);
+        let task_name = task_name.to_string();
+        let handle = tokio::spawn(async move {
+            let mut sampler = ProcessTreeSampler::new(pid);
+            let mut ticks = interval_at(
+                Instant::now() + USAGE_SAMPLE_INTERVAL,
+                USAGE_SAMPLE_INTERVAL,
+            );
+            loop {
+                ticks.tick().await;
+                // Reading /proc blocks, so it runs off the async workers
+                let sampled = tokio::task::spawn_blocking(move || {
+                    let usage = sampler.sample();
+                    (sampler, usage)
+                })
+                .await;
+                let Ok((returned, Some(usage))) = sampled else {
+                    break;
+                };
+                sampler = returned;
+                peak_tx
+                    .send_modify(|peak| *peak = Some(peak.map_or(usage, |peak| peak.max(usage))));
+                if let Some(usage_tx) = &usage_tx {
+                    let sample = UsageSample {
+                        task_name: task_name.clone(),
+                        usage,
+                    };
+                    if usage_tx.send(sample).is_err() {
+                        break;
+                    }
+                }
+            }
+        });
+        Self { handle, peak }
+    }
+
+    /// Stop sampling and return the peaks, if the task ran long enough to be sampled
+    pub fn finish(self) -> Option<ResourceUsage> {
+        self.handle.abort();
+        *self.peak.borrow()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_stat() {
+        let stat = "4242 (my (odd) task) S 4200 4242 4242 0 -1 4194560 1056 0 0 0 \
+                    120 30 0 0 20 0 1 0 35163 9326592 2560 18446744073709551615";
+        assert_eq!(
+            parse_stat(stat),
+            Some(ProcessStat {
+                ppid: 4200,
+                cpu_ticks: 150,
+                rss_pages: 2560,
+            })
+        );
+        assert_eq!(parse_stat("4242 (truncated) S 1"), None);
+    }
+
+    #[test]
+    fn test_process_tree() {
+        let stat = |ppid| ProcessStat {
+            ppid,
+            cpu_ticks: 0,
+            rss_pages: 0,
+        };
+        let processes =
+            HashMap::from([(10, stat(1)), (11, stat(10)), (12, stat(11)), (20, stat(1))]);
+        let mut tree = process_tree(&processes, 10);
+        tree.sort();
+        assert_eq!(tree, vec![10, 11, 12]);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_sample_counts_children() {
+        let mut child = std::process::Command::new("sleep")
+            .arg("5")
+            .spawn()
+            .unwrap();
+        let usage = ProcessTreeSampler::new(std::process::id()).sample();
+        child.kill().unwrap();
+        child.wait().unwrap();
+
+        let usage = usage.unwrap();
+        assert!(usage.children >= 1);
+        assert!(usage.rss_bytes > 0);
+        assert_eq!(ProcessTreeSampler::new(u32::MAX).sample(), None);
+    }
+}
@@ -1,6 +1,11 @@
+use super::config_layout::ConfigLayout;
+use super::freshness::Freshness;
 use super::mise_task::MiseTask;
+use super::output_line::OutputLine;
 use super::sequence::SequenceEvent;
+use super::task_run::TaskResult;
 use ratatui::crossterm::event::{KeyEvent, MouseButton};
+use std::collections::HashMap;
 
 #[cfg(test)]
 use ratatui::crossterm::event::KeyCode;
@@ -9,6 +14,8 @@ use ratatui::crossterm::event::KeyCode;
 pub enum AppEvent {
     Quit,
     KeyPress(KeyEvent),
+    /// Text pasted into the terminal, delivered at once rather than as key presses
+    Paste(String),
     MouseClick {
         button: MouseButton,
         row: u16,
@@ -24,18 +31,35 @@ pub enum AppEvent {
         col: u16,
     },
     TasksRefreshed(Vec<MiseTask>),
-    TaskOutput(String),
-    TaskCompleted,
-    TaskCancelled,
+    /// Where the config defining the tasks lives, after each refresh
+    ConfigLayoutLoaded(ConfigLayout),
+    /// A file defining the tasks changed
+    ConfigChanged,
+    /// Freshness of each task, checked in the background
+    FreshnessRefreshed(HashMap<String, Freshness>),
+    TaskOutput(OutputLine),
+    TaskCompleted(TaskResult),
     DeleteTask(String),
     Tick,
     Sequence(SequenceEvent),
+    /// Files a tab watches changed, with the number of files now watched
+    SourcesChanged(usize),
+    /// An event from a tab's runner, handled against that tab even when it is in the background
+    ForTab(usize, Box<AppEvent>),
+}
+
+impl AppEvent {
+    pub fn for_tab(tab_id: usize, event: AppEvent) -> Self {
+        Self::ForTab(tab_id, Box::new(event))
+    }
 }
 
 #[derive(Debug, Clone)]
 pub enum ScrollDirection {
     Up,
     Down,
+    Left,
+    Right,
 }
 
 #[cfg(test)]
@@ -51,11 +75,11 @@ mod tests {
             ratatui::crossterm::event::KeyModifiers::NONE,
         );
         let key_press = AppEvent::KeyPress(key_event);
-        let task_completed = AppEvent::TaskCompleted;
+        let task_completed = AppEvent::TaskCompleted(TaskResult::error("build", "no mise"));
         let tick = AppEvent::Tick;
 
         match quit {
-            AppEvent::Quit => assert!(true),
+            AppEvent::Quit => {}
             _ => panic!("Expected Quit variant"),
         }
 
@@ -65,12 +89,12 @@ mod tests {
         }
 
         match task_completed {
-            AppEvent::TaskCompleted => assert!(true),
+            AppEvent::TaskCompleted(result) => assert_eq!(result.task_name, "build"),
             _ => panic!("Expected TaskCompleted variant"),
         }
 
         match tick {
-            AppEvent::Tick => assert!(true),
+            AppEvent::Tick => {}
             _ => panic!("Expected Tick variant"),
         }
     }
@@ -95,11 +119,11 @@ mod tests {
 
     #[test]
     fn test_task_output_event() {
-        let output_event = AppEvent::TaskOutput("test output".to_string());
+        let output_event = AppEvent::TaskOutput(OutputLine::stdout("test output"));
 
         match output_event {
             AppEvent::TaskOutput(output) => {
-                assert_eq!(output, "test output");
+                assert_eq!(output.text, "test output");
             }
             _ => panic!("Expected TaskOutput variant"),
         }
@@ -124,7 +148,7 @@ mod tests {
         let seq_event = AppEvent::Sequence(SequenceEvent::RunSequence);
 
         match seq_event {
-            AppEvent::Sequence(SequenceEvent::RunSequence) => assert!(true),
+            AppEvent::Sequence(SequenceEvent::RunSequence) => {}
             _ => panic!("Expected Sequence variant with RunSequence"),
         }
     }
@@ -4,6 +4,10 @@ pub enum AppState {
     Running(String),
     SequenceBuilder,
     Renaming(String),
+    /// List of sequences saved in the project's sequences file
+    Sequences,
+    /// Past task and sequence runs of the project
+    History,
 }
 
 #[cfg(test)]
@@ -16,11 +20,15 @@ mod tests {
         let running_state = AppState::Running("task2".to_string());
         let builder_state = AppState::SequenceBuilder;
         let renaming_state = AppState::Renaming("task3".to_string());
+        let sequences_state = AppState::Sequences;
+        let history_state = AppState::History;
 
         assert_eq!(detail_state, AppState::Detail("task1".to_string()));
         assert_eq!(running_state, AppState::Running("task2".to_string()));
         assert_eq!(builder_state, AppState::SequenceBuilder);
         assert_eq!(renaming_state, AppState::Renaming("task3".to_string()));
+        assert_eq!(sequences_state, AppState::Sequences);
+        assert_eq!(history_state, AppState::History);
     }
 
     #[test]
@@ -30,14 +38,18 @@ mod tests {
             AppState::Running("build".to_string()),
             AppState::SequenceBuilder,
             AppState::Renaming("rename".to_string()),
+            AppState::Sequences,
+            AppState::History,
         ];
 
         for state in states {
             match state {
                 AppState::Detail(task) => assert!(!task.is_empty()),
                 AppState::Running(task) => assert!(!task.is_empty()),
-                AppState::SequenceBuilder => assert!(true),
+                AppState::SequenceBuilder => {}
                 AppState::Renaming(task) => assert!(!task.is_empty()),
+                AppState::Sequences => {}
+                AppState::History => {}
             }
         }
     }
@@ -0,0 +1,284 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Config files mise reads from each directory of a project, relative to the directory.
+/// Watched even before they exist, as creating one adds to the config
+pub const PROJECT_CONFIG_FILES: [&str; 8] = [
+    "mise.toml",
+    ".mise.toml",
+    "mise.local.toml",
+    ".mise.local.toml",
+    "mise/config.toml",
+    ".mise/config.toml",
+    ".config/mise.toml",
+    ".config/mise/config.toml",
+];
+
+/// Directories mise looks for file tasks in, relative to the directory of a config that
+/// does not set `task_config.includes`
+pub const PROJECT_TASK_DIRS: [&str; 5] = [
+    "mise-tasks",
+    ".mise-tasks",
+    "mise/tasks",
+    ".mise/tasks",
+    ".config/mise/tasks",
+];
+
+/// Directories below which mise keeps config files, instead of next to the project
+const CONFIG_SUBDIRS: [&str; 3] = ["mise", ".mise", ".config"];
+
+/// One entry of `mise config ls --json`
+#[derive(Debug, Deserialize)]
+struct ListedConfig {
+    path: String,
+}
+
+/// The config files in the output of `mise config ls --json`, with `~` expanded
+pub fn parse_config_ls(json: &[u8], home: Option<&Path>) -> Result<Vec<PathBuf>> {
+    let listed: Vec<ListedConfig> =
+        serde_json::from_slice(json).context("Failed to parse mise config ls JSON output")?;
+    Ok(listed
+        .into_iter()
+        .map(|config| match (config.path.strip_prefix("~/"), home) {
+            (Some(rest), Some(home)) => home.join(rest),
+            _ => PathBuf::from(config.path),
+        })
+        .collect())
+}
+
+/// Directory a config file applies to, e.g. `/repo` for `/repo/.config/mise/config.toml`
+pub fn config_root(config_file: &Path) -> PathBuf {
+    let mut root = config_file.parent().unwrap_or(config_file);
+    while let Some(parent) = root.parent()
+        && root
+            .file_name()
+            .is_some_and(|name| CONFIG_SUBDIRS.iter().any(|dir| name == *dir))
+    {
+        root = parent;
+    }
+    root.to_path_buf()
+}
+
+/// Where the config mise works from lives
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConfigLayout {
+    /// The innermost directory with a config file that applies to the working directory
+    pub project_root: PathBuf,
+    /// The config files mise reports, and those it would read if created in the working
+    /// directory or one between it and the project root
+    pub config_files: Vec<PathBuf>,
+    /// Where file tasks are picked up from: the `task_config.includes` of each config, or
+    /// the default task directories next to it. Each with the directory of its config
+    pub task_dirs: BTreeMap<PathBuf, PathBuf>,
+}
+
+/// The `task_config.includes` of a config file, relative to `root`. `None` when the file
+/// sets none, or cannot be read as TOML like a `.tool-versions`
+fn task_includes(config_file: &Path, root: &Path) -> Option<Vec<PathBuf>> {
+    let content = std::fs::read_to_string(config_file).ok()?;
+    let config: toml::Table = toml::from_str(&content).ok()?;
+    let includes = config.get("task_config")?.get("includes")?.as_array()?;
+    Some(
+        includes
+            .iter()
+            .filter_map(|include| include.as_str())
+            .map(|include| root.join(include))
+            .collect(),
+    )
+}
+
+impl ConfigLayout {
+    /// The layout for `cwd`, given the config files mise loaded for it. Reads the configs
+    /// for their task includes
+    pub fn new(cwd: &Path, loaded: Vec<PathBuf>) -> Self {
+        let project_root = loaded
+            .iter()
+            .map(|file| config_root(file))
+            .filter(|root| cwd.starts_with(root))
+            .max_by_key(|root| root.components().count())
+            .unwrap_or_else(|| cwd.to_path_buf());
+
+        // Includes set by any config of a directory replace its default task directories
+        let mut includes: BTreeMap<PathBuf, Option<Vec<PathBuf>>> = BTreeMap::new();
+        for file in &loaded {
+            let root = config_root(file);
+            let file_includes = task_includes(file, &root);
+            let entry = includes.entry(root).or_default();
+            if let Some(file_includes) = file_includes {
+                entry.get_or_insert_with(Vec::new).extend(file_includes);
+            }
+        }
+
+        let mut config_files = loaded;
+        for dir in cwd.ancestors() {
+            config_files.extend(PROJECT_CONFIG_FILES.iter().map(|file| dir.join(file)));
+            includes.entry(dir.to_path_buf()).or_default();
+            if dir == project_root {
+                break;
+            }
+        }
+        config_files.sort();
+        config_files.dedup();
+
+        let task_dirs = includes
+            .into_iter()
+            .flat_map(|(root, includes)| {
+                includes
+                    .unwrap_or_else(|| PROJECT_TASK_DIRS.iter().map(|dir| root.join(dir)).collect())
+                    .into_iter()
+                    .map(move |dir| (dir, root.clone()))
+            })
+            .collect();
+        Self {
+            project_root,
+            config_files,
+            task_dirs,
+        }
+    }
+
+    /// Every file and directory whose changes may change the tasks
+    pub fn paths(&self) -> impl Iterator<Item = &PathBuf> {
+        self.config_files.iter().chain(self.task_dirs.keys())
+    }
+
+    /// Directory of the config defining the task from `source`, which the relative
+    /// `sources` and `outputs` of the task and its `{{config_root}}` refer to. A file task
+    /// belongs to the config whose task directory it is in
+    pub fn task_config_root(&self, source: &Path) -> PathBuf {
+        self.task_dirs
+            .iter()
+            .filter(|(dir, _)| source.starts_with(dir))
+            .max_by_key(|(dir, _)| dir.components().count())
+            .map(|(_, root)| root.clone())
+            .unwrap_or_else(|| config_root(source))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_config_ls() {
+        let json = br#"[
+            {"path": "~/.config/mise/config.toml", "tools": ["node"]},
+            {"path": "/repo/mise.toml", "tools": []}
+        ]"#;
+        assert_eq!(
+            parse_config_ls(json, Some(Path::new("/home/me"))).unwrap(),
+            vec![
+                PathBuf::from("/home/me/.config/mise/config.toml"),
+                PathBuf::from("/repo/mise.toml"),
+            ]
+        );
+        assert!(parse_config_ls(b"oops", None).is_err());
+    }
+
+    #[test]
+    fn test_config_root() {
+        assert_eq!(
+            config_root(Path::new("/repo/mise.toml")),
+            Path::new("/repo")
+        );
+        assert_eq!(
+            config_root(Path::new("/repo/.config/mise/config.toml")),
+            Path::new("/repo")
+        );
+        assert_eq!(
+            config_root(Path::new("/repo/.mise/config.toml")),
+            Path::new("/repo")
+        );
+    }
+
+    #[test]
+    fn test_layout_covers_configs_that_do_not_exist_yet() {
+        let loaded = vec![
+            PathBuf::from("/home/me/.config/mise/config.toml"),
+            PathBuf::from("/home/me/repo/mise.toml"),
+        ];
+        let layout = ConfigLayout::new(Path::new("/home/me/repo/crates/app"), loaded);
+
+        assert_eq!(layout.project_root, Path::new("/home/me/repo"));
+        let files = &layout.config_files;
+        assert!(files.contains(&PathBuf::from("/home/me/.config/mise/config.toml")));
+        assert!(files.contains(&PathBuf::from("/home/me/repo/mise.local.toml")));
+        assert!(files.contains(&PathBuf::from("/home/me/repo/crates/mise.toml")));
+        assert!(files.contains(&PathBuf::from("/home/me/repo/crates/app/.mise.toml")));
+        assert!(!files.contains(&PathBuf::from("/home/me/mise.local.toml")));
+        assert_eq!(files.len(), 1 + 3 * PROJECT_CONFIG_FILES.len());
+        let task_dirs = &layout.task_dirs;
+        assert_eq!(
+            task_dirs[Path::new("/home/me/.config/mise/tasks")],
+            Path::new("/home/me")
+        );
+        assert_eq!(
+            task_dirs[Path::new("/home/me/repo/mise-tasks")],
+            Path::new("/home/me/repo")
+        );
+        assert_eq!(
+            task_dirs[Path::new("/home/me/repo/crates/app/.mise/tasks")],
+            Path::new("/home/me/repo/crates/app")
+        );
+        assert_eq!(task_dirs.len(), 4 * PROJECT_TASK_DIRS.len());
+
+        // Without a project config, the working directory is the project
+        let layout = ConfigLayout::new(Path::new("/tmp/scratch"), Vec::new());
+        assert_eq!(layout.project_root, Path::new("/tmp/scratch"));
+        assert_eq!(layout.config_files.len(), PROJECT_CONFIG_FILES.len());
+        assert_eq!(layout.task_dirs.len(), PROJECT_TASK_DIRS.len());
+    }
+
+    #[test]
+    fn test_task_config_root() {
+        let loaded = vec![
+            PathBuf::from("/home/me/.config/mise/config.toml"),
+            PathBuf::from("/home/me/repo/mise.toml"),
+        ];
+        let layout = ConfigLayout::new(Path::new("/home/me/repo/crates/app"), loaded);
+        let root = |source: &str| layout.task_config_root(Path::new(source));
+
+        assert_eq!(root("/home/me/repo/mise.toml"), Path::new("/home/me/repo"));
+        assert_eq!(
+            root("/home/me/.config/mise/config.toml"),
+            Path::new("/home/me")
+        );
+        assert_eq!(
+            root("/home/me/repo/.mise/tasks/ci/test"),
+            Path::new("/home/me/repo")
+        );
+        assert_eq!(
+            root("/home/me/repo/crates/app/mise-tasks/build"),
+            Path::new("/home/me/repo/crates/app")
+        );
+    }
+
+    #[test]
+    fn test_task_includes_replace_the_default_task_dirs() {
+        let dir =
+            std::env::temp_dir().join(format!("mise-sequencer-layout-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join(".config")).unwrap();
+        let config = dir.join(".config/mise.toml");
+        std::fs::write(
+            &config,
+            "[task_config]\nincludes = [\"scripts\", \"ci/tasks.toml\"]\n",
+        )
+        .unwrap();
+        let tool_versions = dir.join(".tool-versions");
+        std::fs::write(&tool_versions, "node 22\n").unwrap();
+
+        let layout = ConfigLayout::new(&dir, vec![config, tool_versions]);
+        assert_eq!(layout.project_root, dir);
+        let task_dirs: Vec<&PathBuf> = layout.task_dirs.keys().collect();
+        assert_eq!(
+            task_dirs,
+            vec![&dir.join("ci/tasks.toml"), &dir.join("scripts")]
+        );
+        assert_eq!(
+            layout.paths().count(),
+            layout.config_files.len() + layout.task_dirs.len()
+        );
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,102 @@
+use std::path::Path;
+
+/// Environment variable with the command files are opened with, e.g. `code` or `subl -n`
+pub const EDITOR_ENV: &str = "MISE_SEQUENCER_EDITOR";
+
+/// Editor used unless `EDITOR_ENV` is set
+pub const DEFAULT_EDITOR: &str = "code";
+
+/// The editor command from `EDITOR_ENV`. It is started next to the app rather than in its
+/// terminal, so it should be a graphical editor or a client of one
+pub fn editor_from_env() -> String {
+    std::env::var(EDITOR_ENV)
+        .ok()
+        .filter(|editor| !editor.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_EDITOR.to_string())
+}
+
+/// Program and arguments opening `path` in `editor`, at `line` and `column` for the
+/// editors known to take a position. Others just open the file
+pub fn editor_command(
+    editor: &str,
+    path: &str,
+    line: Option<usize>,
+    column: Option<usize>,
+) -> (String, Vec<String>) {
+    let mut words = editor.split_whitespace().map(str::to_string);
+    let program = words.next().unwrap_or_else(|| DEFAULT_EDITOR.to_string());
+    let mut args: Vec<String> = words.collect();
+
+    let name = Path::new(&program)
+        .file_stem()
+        .and_then(|name| name.to_str())
+        .unwrap_or_default();
+    let colon_position = || match column {
+        Some(column) => format!("{path}:{}:{column}", line.unwrap_or(1)),
+        None => format!("{path}:{}", line.unwrap_or(1)),
+    };
+
+    match (name, line) {
+        (_, None) => args.push(path.to_string()),
+        ("code" | "code-insiders" | "codium" | "cursor" | "windsurf", Some(_)) => {
+            args.extend(["--goto".to_string(), colon_position()]);
+        }
+        ("subl" | "zed", Some(_)) => args.push(colon_position()),
+        (
+            "idea" | "clion" | "goland" | "pycharm" | "rustrover" | "webstorm" | "phpstorm",
+            Some(line),
+        ) => {
+            args.extend(["--line".to_string(), line.to_string()]);
+            if let Some(column) = column {
+                args.extend(["--column".to_string(), column.to_string()]);
+            }
+            args.push(path.to_string());
+        }
+        ("emacsclient" | "gvim" | "mvim", Some(line)) => {
+            args.extend([format!("+{line}"), path.to_string()]);
+        }
+        (_, Some(_)) => args.push(path.to_string()),
+    }
+    (program, args)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_editor_command_with_position() {
+        assert_eq!(
+            editor_command("code", "src/main.rs", Some(4), Some(5)),
+            (
+                "code".to_string(),
+                vec!["--goto".to_string(), "src/main.rs:4:5".to_string()]
+            )
+        );
+        assert_eq!(
+            editor_command("/usr/bin/subl -n", "a.py", Some(7), None),
+            (
+                "/usr/bin/subl".to_string(),
+                vec!["-n".to_string(), "a.py:7".to_string()]
+            )
+        );
+        assert_eq!(
+            editor_command("idea", "A.java", Some(3), Some(2)).1,
+            vec!["--line", "3", "--column", "2", "A.java"]
+        );
+        assert_eq!(
+            editor_command("emacsclient -n", "x.c", Some(9), Some(1)).1,
+            vec!["-n", "+9", "x.c"]
+        );
+    }
+
+    #[test]
+    fn test_editor_command_without_position() {
+        assert_eq!(
+            editor_command("code", "mise.toml", None, None),
+            ("code".to_string(), vec!["mise.toml".to_string()])
+        );
+        // Editors whose position syntax is unknown still open the file
+        assert_eq!(editor_command("kate", "x.c", Some(9), None).1, vec!["x.c"]);
+    }
+}
@@ -0,0 +1,274 @@
+use regex::Regex;
+use std::collections::HashSet;
+use std::sync::LazyLock;
+
+use super::output_buffer::OutputBuffer;
+use super::output_search::strip_ansi;
+
+/// `path:line` or `path:line:col`, as printed by rustc (`--> src/main.rs:4:5`), gcc and
+/// clang, go, pytest and eslint's unix format. The path needs an extension, so times and
+/// ports are not taken for locations
+static PATH_LINE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?P<path>[\w./\\~@+-]*\w\.[A-Za-z][\w]*):(?P<line>\d+)(?::(?P<col>\d+))?").unwrap()
+});
+
+/// `path(line,col)`, as printed by tsc
+static PATH_PAREN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?P<path>[\w./\\~@+-]*\w\.[A-Za-z][\w]*)\((?P<line>\d+),(?P<col>\d+)\)").unwrap()
+});
+
+/// `File "path", line 42`, in Python tracebacks
+static PYTHON_FRAME: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"File "(?P<path>[^"]+)", line (?P<line>\d+)"#).unwrap());
+
+/// A line holding nothing but a path, which eslint's default format prints above the
+/// problems found in that file
+static PATH_ONLY: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\s*(?P<path>[\w./\\~@+-]*\w\.[A-Za-z][\w]*)\s*$").unwrap());
+
+/// `  42:7  error  message  rule`, a problem below a path line in eslint's default format
+static ESLINT_PROBLEM: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\s+(?P<line>\d+):(?P<col>\d+)\s+(error|warning)\s").unwrap());
+
+/// A place in a source file
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ErrorLocation {
+    pub path: String,
+    pub line: usize,
+    pub column: Option<usize>,
+}
+
+impl ErrorLocation {
+    fn from_captures(captures: &regex::Captures, path: &str) -> Option<Self> {
+        Some(Self {
+            path: path.to_string(),
+            line: captures["line"].parse().ok().filter(|&line| line > 0)?,
+            column: captures
+                .name("col")
+                .and_then(|col| col.as_str().parse().ok()),
+        })
+    }
+
+    /// `path:line:col`, or `path:line` without a column
+    pub fn label(&self) -> String {
+        match self.column {
+            Some(column) => format!("{}:{}:{column}", self.path, self.line),
+            None => format!("{}:{}", self.path, self.line),
+        }
+    }
+}
+
+/// Finds locations line by line, remembering the file of eslint's grouped problems
+#[derive(Debug, Default)]
+pub struct LocationScanner {
+    current_file: Option<String>,
+}
+
+impl LocationScanner {
+    /// The first location in `text`, which is free of escape codes
+    pub fn scan(&mut self, text: &str) -> Option<ErrorLocation> {
+        if text.trim().is_empty() {
+            self.current_file = None;
+            return None;
+        }
+        if let Some(captures) = PATH_ONLY.captures(text) {
+            self.current_file = Some(captures["path"].to_string());
+            return None;
+        }
+        if let Some(file) = &self.current_file
+            && let Some(captures) = ESLINT_PROBLEM.captures(text)
+        {
+            return ErrorLocation::from_captures(&captures, file);
+        }
+
+        if let Some(captures) = PYTHON_FRAME.captures(text) {
+            return ErrorLocation::from_captures(&captures, &captures["path"]);
+        }
+        [&*PATH_PAREN, &*PATH_LINE]
+            .into_iter()
+            .flat_map(|regex| regex.captures_iter(text))
+            .filter(|captures| !is_in_url(text, captures))
+            .min_by_key(|captures| captures.get(0).map_or(0, |found| found.start()))
+            .and_then(|captures| ErrorLocation::from_captures(&captures, &captures["path"]))
+    }
+}
+
+/// Whether the match is the host and port of a URL such as `https://example.com:8080`
+fn is_in_url(text: &str, captures: &regex::Captures) -> bool {
+    let path = &captures["path"];
+    let start = captures.get(0).map_or(0, |found| found.start());
+    path.starts_with("//") || text[..start].ends_with(':')
+}
+
+/// A location found in the output, with the output line it was found on
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FoundLocation {
+    pub location: ErrorLocation,
+    /// Index of the output line
+    pub index: usize,
+    /// The output line, without escape codes
+    pub text: String,
+}
+
+/// Every location in `output`, oldest first. A location found again on a later line,
+/// like rustc's summary of the same error, is listed once
+pub fn find_locations(output: &OutputBuffer) -> Vec<FoundLocation> {
+    let mut scanner = LocationScanner::default();
+    let mut found: Vec<FoundLocation> = Vec::new();
+    let mut seen: HashSet<ErrorLocation> = HashSet::new();
+    for (index, line) in output.iter().enumerate() {
+        let text = strip_ansi(&line.text);
+        let Some(location) = scanner.scan(&text) else {
+            continue;
+        };
+        if seen.insert(location.clone()) {
+            found.push(FoundLocation {
+                location,
+                index,
+                text: text.trim().to_string(),
+            });
+        }
+    }
+    found
+}
+
+/// The list of locations found in the output pane, opened with `L`
+#[derive(Debug, Clone, Default)]
+pub struct ErrorNavigator {
+    pub locations: Vec<FoundLocation>,
+    pub selected: usize,
+}
+
+impl ErrorNavigator {
+    pub fn new(locations: Vec<FoundLocation>) -> Self {
+        Self {
+            locations,
+            selected: 0,
+        }
+    }
+
+    pub fn selected(&self) -> Option<&FoundLocation> {
+        self.locations.get(self.selected)
+    }
+
+    pub fn select_next(&mut self) {
+        if !self.locations.is_empty() {
+            self.selected = (self.selected + 1) % self.locations.len();
+        }
+    }
+
+    pub fn select_previous(&mut self) {
+        if !self.locations.is_empty() {
+            self.selected = self
+                .selected
+                .checked_sub(1)
+                .unwrap_or(self.locations.len() - 1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::output_line::OutputLine;
+
+    fn scan(text: &str) -> Option<String> {
+        LocationScanner::default()
+            .scan(text)
+            .map(|location| location.label())
+    }
+
+    #[test]
+    fn test_compiler_formats() {
+        // rustc
+        assert_eq!(
+            scan("  --> src/main.rs:42:7").as_deref(),
+            Some("src/main.rs:42:7")
+        );
+        assert_eq!(
+            scan("thread 'main' panicked at src/lib.rs:10:5:").as_deref(),
+            Some("src/lib.rs:10:5")
+        );
+        // gcc and clang
+        assert_eq!(
+            scan("src/foo.c:3:14: error: expected ';'").as_deref(),
+            Some("src/foo.c:3:14")
+        );
+        // tsc, plain and pretty
+        assert_eq!(
+            scan("src/app.ts(12,5): error TS2322: Type 'string'").as_deref(),
+            Some("src/app.ts:12:5")
+        );
+        assert_eq!(
+            scan("src/app.ts:12:5 - error TS2322").as_deref(),
+            Some("src/app.ts:12:5")
+        );
+        // go
+        assert_eq!(
+            scan("./cmd/main.go:8:2: undefined: foo").as_deref(),
+            Some("./cmd/main.go:8:2")
+        );
+        assert_eq!(
+            scan("    parse_test.go:31: got 2, want 3").as_deref(),
+            Some("parse_test.go:31")
+        );
+    }
+
+    #[test]
+    fn test_pytest_formats() {
+        assert_eq!(
+            scan("tests/test_api.py:42: AssertionError").as_deref(),
+            Some("tests/test_api.py:42")
+        );
+        assert_eq!(
+            scan(r#"  File "/srv/app/main.py", line 7, in handler"#).as_deref(),
+            Some("/srv/app/main.py:7")
+        );
+    }
+
+    #[test]
+    fn test_eslint_stylish_format() {
+        let mut scanner = LocationScanner::default();
+        assert_eq!(scanner.scan("/repo/src/index.js"), None);
+        let location = scanner
+            .scan("  3:10  error  'x' is not defined  no-undef")
+            .unwrap();
+        assert_eq!(location.label(), "/repo/src/index.js:3:10");
+        assert_eq!(scanner.scan(""), None);
+        assert_eq!(scanner.scan("  3:10  error  'x' is not defined"), None);
+    }
+
+    #[test]
+    fn test_no_false_locations() {
+        assert_eq!(scan("Listening on http://localhost.dev:8080/"), None);
+        assert_eq!(scan("Finished at 12:30:45"), None);
+        assert_eq!(scan("compiling 3 crates"), None);
+    }
+
+    #[test]
+    fn test_find_locations_in_output() {
+        let mut output = OutputBuffer::default();
+        for line in [
+            "error[E0308]: mismatched types",
+            "  \x1b[1;34m-->\x1b[0m src/main.rs:4:5",
+            "warning: unused variable",
+            "  --> src/lib.rs:9:1",
+            "  --> src/main.rs:4:5",
+        ] {
+            output.push_back(OutputLine::stderr(line));
+        }
+
+        let found = find_locations(&output);
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[0].location.label(), "src/main.rs:4:5");
+        assert_eq!(found[0].index, 1);
+        assert_eq!(found[0].text, "--> src/main.rs:4:5");
+        assert_eq!(found[1].index, 3);
+
+        let mut navigator = ErrorNavigator::new(found);
+        navigator.select_previous();
+        assert_eq!(navigator.selected().unwrap().index, 3);
+        navigator.select_next();
+        assert_eq!(navigator.selected, 0);
+    }
+}
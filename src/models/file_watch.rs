@@ -0,0 +1,241 @@
+use regex::Regex;
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+/// Directories never looked into below a watched directory, they hold nothing a task's
+/// `sources` are about and can be huge
+const SKIPPED_DIRS: [&str; 3] = [".git", "node_modules", "target"];
+
+/// How long files have to be quiet after a change before the watched run starts again,
+/// so saving many files at once restarts it once
+pub const WATCH_DEBOUNCE_MS: u64 = 300;
+
+/// Anchored regex matching the paths `glob` matches. `*` and `?` stay within a path
+/// segment, `**` spans segments, and `{a,b}` and `[abc]` work as in shells
+pub fn glob_regex(glob: &str) -> Option<Regex> {
+    let mut regex = String::from("^");
+    let mut chars = glob.chars().peekable();
+    let mut braces = 0;
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                if chars.peek() == Some(&'/') {
+                    chars.next();
+                    regex.push_str("(?:.*/)?");
+                } else {
+                    regex.push_str(".*");
+                }
+            }
+            '*' => regex.push_str("[^/]*"),
+            '?' => regex.push_str("[^/]"),
+            '[' => {
+                regex.push('[');
+                if chars.peek() == Some(&'!') {
+                    chars.next();
+                    regex.push('^');
+                }
+                for c in chars.by_ref() {
+                    if c == ']' {
+                        break;
+                    }
+                    if c == '\\' || c == '[' {
+                        regex.push('\\');
+                    }
+                    regex.push(c);
+                }
+                regex.push(']');
+            }
+            '{' => {
+                braces += 1;
+                regex.push_str("(?:");
+            }
+            '}' if braces > 0 => {
+                braces -= 1;
+                regex.push(')');
+            }
+            ',' if braces > 0 => regex.push('|'),
+            c => regex.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    regex.push('$');
+    Regex::new(&regex).ok()
+}
+
+/// The directory part of `glob` before its first wildcard, e.g. `src` for `src/**/*.rs`
+fn literal_dir(glob: &str) -> &str {
+    let wildcard = glob.find(['*', '?', '[', '{']).unwrap_or(glob.len());
+    glob[..wildcard]
+        .rfind('/')
+        .map_or("", |slash| &glob[..slash])
+}
+
+/// The files a task's `sources` globs stand for. Relative globs and `{{config_root}}` are
+/// taken from the directory of the config defining the task, as mise does
+#[derive(Debug, Clone, Default)]
+pub struct WatchSet {
+    patterns: Vec<Regex>,
+    /// Directories the globs can match files in, and whether in directories below them
+    roots: Vec<(PathBuf, bool)>,
+}
+
+impl WatchSet {
+    pub fn new(base: &Path, globs: &[String]) -> Self {
+        let mut set = Self::default();
+        set.add(base, globs);
+        set
+    }
+
+    /// Watch the files of `globs` too, relative ones taken from `base`
+    pub fn add(&mut self, base: &Path, globs: &[String]) {
+        let base_dir = base.to_string_lossy();
+        for glob in globs {
+            let glob = glob
+                .replace("{{config_root}}", &base_dir)
+                .replace("{{ config_root }}", &base_dir);
+            let glob = if Path::new(&glob).is_absolute() {
+                glob
+            } else {
+                format!(
+                    "{}/{}",
+                    base_dir.trim_end_matches('/'),
+                    glob.trim_start_matches("./")
+                )
+            };
+            let Some(pattern) = glob_regex(&glob) else {
+                continue;
+            };
+            self.patterns.push(pattern);
+            let root = literal_dir(&glob);
+            let rest = &glob[root.len()..].trim_start_matches('/');
+            let recursive = rest.contains('/') || rest.contains("**");
+            self.roots.push((PathBuf::from(root), recursive));
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
+
+    /// Whether `path`, which is absolute, is one of the watched files
+    pub fn matches(&self, path: &Path) -> bool {
+        let path = path.to_string_lossy();
+        self.patterns.iter().any(|pattern| pattern.is_match(&path))
+    }
+
+    /// Directories to watch for changes: each root, and every directory below the roots
+    /// of globs reaching into subdirectories
+    pub fn directories(&self) -> Vec<PathBuf> {
+        let mut directories = BTreeSet::new();
+        for (root, recursive) in &self.roots {
+            if !recursive {
+                if root.is_dir() {
+                    directories.insert(root.clone());
+                }
+                continue;
+            }
+            let mut pending = vec![root.clone()];
+            while let Some(dir) = pending.pop() {
+                let Ok(entries) = std::fs::read_dir(&dir) else {
+                    continue;
+                };
+                pending.extend(entries.flatten().filter_map(|entry| {
+                    let is_dir = entry.file_type().is_ok_and(|kind| kind.is_dir());
+                    let skipped = SKIPPED_DIRS.iter().any(|name| entry.file_name() == *name);
+                    (is_dir && !skipped).then(|| entry.path())
+                }));
+                directories.insert(dir);
+            }
+        }
+        directories.into_iter().collect()
+    }
+
+    /// The watched files that exist now
+    pub fn files(&self) -> Vec<PathBuf> {
+        self.directories()
+            .iter()
+            .filter_map(|dir| std::fs::read_dir(dir).ok())
+            .flat_map(|entries| entries.flatten())
+            .filter(|entry| entry.file_type().is_ok_and(|kind| kind.is_file()))
+            .map(|entry| entry.path())
+            .filter(|path| self.matches(path))
+            .collect()
+    }
+}
+
+/// `watching 3 files`
+pub fn watching_label(files: usize) -> String {
+    match files {
+        1 => "watching 1 file".to_string(),
+        n => format!("watching {n} files"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn is_match(glob: &str, path: &str) -> bool {
+        glob_regex(glob).unwrap().is_match(path)
+    }
+
+    #[test]
+    fn test_glob_regex() {
+        assert!(is_match("src/*.rs", "src/main.rs"));
+        assert!(!is_match("src/*.rs", "src/app/mod.rs"));
+        assert!(is_match("src/**/*.rs", "src/main.rs"));
+        assert!(is_match("src/**/*.rs", "src/app/mod.rs"));
+        assert!(is_match("**/*.{ts,tsx}", "web/app.tsx"));
+        assert!(!is_match("**/*.{ts,tsx}", "web/app.js"));
+        assert!(is_match("file?.[ch]", "file1.c"));
+        assert!(!is_match("file?.[!ch]", "file1.c"));
+        assert!(is_match("Cargo.toml", "Cargo.toml"));
+        assert!(!is_match("Cargo.toml", "Cargo_toml"));
+    }
+
+    #[test]
+    fn test_literal_dir() {
+        assert_eq!(literal_dir("/repo/src/**/*.rs"), "/repo/src");
+        assert_eq!(literal_dir("/repo/Cargo.toml"), "/repo");
+        assert_eq!(literal_dir("*.rs"), "");
+    }
+
+    #[test]
+    fn test_watch_set_finds_files() {
+        let dir = std::env::temp_dir().join(format!("mise-sequencer-watch-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("src/app")).unwrap();
+        std::fs::create_dir_all(dir.join("src/target")).unwrap();
+        for file in [
+            "Cargo.toml",
+            "src/main.rs",
+            "src/app/mod.rs",
+            "src/notes.md",
+            "src/target/x.rs",
+        ] {
+            std::fs::write(dir.join(file), "").unwrap();
+        }
+
+        let set = WatchSet::new(
+            &dir,
+            &[
+                "src/**/*.rs".to_string(),
+                "{{config_root}}/Cargo.toml".to_string(),
+            ],
+        );
+        let mut files = set.files();
+        files.sort();
+        assert_eq!(
+            files,
+            vec![
+                dir.join("Cargo.toml"),
+                dir.join("src/app/mod.rs"),
+                dir.join("src/main.rs")
+            ]
+        );
+        assert!(set.matches(&dir.join("src/new.rs")));
+        assert!(!set.matches(&dir.join("src/notes.md")));
+        assert!(WatchSet::new(&dir, &[]).is_empty());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,111 @@
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+use super::file_watch::WatchSet;
+use super::mise_task::MiseTask;
+
+/// How often the freshness of every task is checked again in the background
+pub const FRESHNESS_REFRESH_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Whether a task's `outputs` are up to date with its `sources`, as mise decides
+/// whether to skip it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Freshness {
+    /// Every output exists and is newer than every source
+    Fresh,
+    /// An output is missing or older than one of the sources
+    Stale,
+    /// The task declares no sources or no outputs to compare
+    Untracked,
+}
+
+impl Freshness {
+    /// Text of the freshness column
+    pub fn label(self) -> &'static str {
+        match self {
+            Freshness::Fresh => "fresh",
+            Freshness::Stale => "stale",
+            Freshness::Untracked => "-",
+        }
+    }
+}
+
+fn modified(path: impl AsRef<Path>) -> Option<SystemTime> {
+    std::fs::metadata(path)
+        .and_then(|meta| meta.modified())
+        .ok()
+}
+
+/// Compare the modification times of the files `sources` and `outputs` match, with
+/// relative globs taken from `base`. Each output glob has to match at least one file
+pub fn freshness(base: &Path, sources: &[String], outputs: &[String]) -> Freshness {
+    if sources.is_empty() || outputs.is_empty() {
+        return Freshness::Untracked;
+    }
+
+    let mut oldest_output: Option<SystemTime> = None;
+    for glob in outputs {
+        let files = WatchSet::new(base, std::slice::from_ref(glob)).files();
+        let Some(oldest) = files.iter().filter_map(modified).min() else {
+            return Freshness::Stale;
+        };
+        oldest_output = Some(oldest_output.map_or(oldest, |current| current.min(oldest)));
+    }
+
+    let newest_source = WatchSet::new(base, sources)
+        .files()
+        .iter()
+        .filter_map(modified)
+        .max();
+    match (newest_source, oldest_output) {
+        (Some(source), Some(output)) if source > output => Freshness::Stale,
+        _ => Freshness::Fresh,
+    }
+}
+
+/// Freshness of `task`, see `freshness`
+pub fn task_freshness(base: &Path, task: &MiseTask) -> Freshness {
+    freshness(base, &task.sources, &task.outputs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set_modified(path: &Path, secs_ago: u64) {
+        let file = std::fs::File::options().write(true).open(path).unwrap();
+        file.set_modified(SystemTime::now() - Duration::from_secs(secs_ago))
+            .unwrap();
+    }
+
+    #[test]
+    fn test_freshness() {
+        let dir =
+            std::env::temp_dir().join(format!("mise-sequencer-freshness-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("src")).unwrap();
+        std::fs::create_dir_all(dir.join("dist")).unwrap();
+        for file in ["src/main.rs", "src/lib.rs", "dist/app"] {
+            std::fs::write(dir.join(file), "").unwrap();
+        }
+        set_modified(&dir.join("src/main.rs"), 60);
+        set_modified(&dir.join("src/lib.rs"), 30);
+        set_modified(&dir.join("dist/app"), 10);
+
+        let sources = vec!["src/**/*.rs".to_string()];
+        let outputs = vec!["dist/app".to_string()];
+        assert_eq!(freshness(&dir, &sources, &outputs), Freshness::Fresh);
+        assert_eq!(freshness(&dir, &sources, &[]), Freshness::Untracked);
+        assert_eq!(freshness(&dir, &[], &outputs), Freshness::Untracked);
+
+        // A source edited after the output was built
+        set_modified(&dir.join("src/lib.rs"), 5);
+        assert_eq!(freshness(&dir, &sources, &outputs), Freshness::Stale);
+
+        // An output that was never built
+        set_modified(&dir.join("src/lib.rs"), 30);
+        let outputs = vec!["dist/app".to_string(), "dist/app.map".to_string()];
+        assert_eq!(freshness(&dir, &sources, &outputs), Freshness::Stale);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -1,5 +1,57 @@
+use anyhow::{bail, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::fmt;
+
+/// One entry of a task's `run` list: a shell script, or a reference to other tasks
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum RunEntry {
+    Script(String),
+    /// `{ task = "build" }` runs another task
+    Task {
+        task: String,
+    },
+    /// `{ tasks = ["build", "lint"] }` runs several tasks in parallel
+    Tasks {
+        tasks: Vec<String>,
+    },
+}
+
+impl RunEntry {
+    /// Tasks run by each step of this entry, or `None` for scripts that do more than
+    /// call `mise run`. Scripts like `mise run a ::: b && mise run c` are understood too.
+    fn sequence_steps(&self) -> Option<Vec<Vec<String>>> {
+        match self {
+            Self::Task { task } => Some(vec![vec![task.clone()]]),
+            Self::Tasks { tasks } => Some(vec![tasks.clone()]),
+            Self::Script(script) => script
+                .split("&&")
+                .map(|command| {
+                    let tasks: Vec<String> = command
+                        .trim()
+                        .strip_prefix("mise run ")?
+                        .split(":::")
+                        .map(|task| task.trim().to_string())
+                        .collect();
+                    let is_task_name =
+                        |task: &String| !task.is_empty() && !task.contains(char::is_whitespace);
+                    tasks.iter().all(is_task_name).then_some(tasks)
+                })
+                .collect(),
+        }
+    }
+}
+
+impl fmt::Display for RunEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Script(script) => write!(f, "{script}"),
+            Self::Task { task } => write!(f, "mise run {task}"),
+            Self::Tasks { tasks } => write!(f, "mise run {}", tasks.join(" ::: ")),
+        }
+    }
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MiseTask {
@@ -20,7 +72,7 @@ pub struct MiseTask {
     pub quiet: bool,
     pub silent: bool,
     pub tools: HashMap<String, serde_json::Value>,
-    pub run: Vec<String>,
+    pub run: Vec<RunEntry>,
     pub file: Option<String>,
 }
 
@@ -73,15 +125,100 @@ pub struct MiseTaskInfo {
     pub quiet: bool,
     pub silent: bool,
     pub tools: HashMap<String, serde_json::Value>,
-    pub run: Vec<String>,
+    pub run: Vec<RunEntry>,
     pub file: Option<String>,
     pub usage_spec: serde_json::Value,
 }
 
+impl From<MiseTask> for MiseTaskInfo {
+    /// The details of a listed task, without the usage spec only `mise tasks info` has
+    fn from(task: MiseTask) -> Self {
+        Self {
+            name: task.name,
+            aliases: task.aliases,
+            description: task.description,
+            source: task.source,
+            depends: task.depends,
+            depends_post: task.depends_post,
+            wait_for: task.wait_for,
+            env: task.env,
+            dir: task.dir,
+            hide: task.hide,
+            raw: task.raw,
+            sources: task.sources,
+            outputs: task.outputs,
+            shell: task.shell,
+            quiet: task.quiet,
+            silent: task.silent,
+            tools: task.tools,
+            run: task.run,
+            file: task.file,
+            usage_spec: serde_json::Value::Null,
+        }
+    }
+}
+
+impl MiseTaskInfo {
+    /// Rebuild the steps of a sequence from this task: its `depends` run in parallel as
+    /// the first step, followed by one step per task group in `run`.
+    /// Fails for tasks whose `run` does anything besides running other tasks.
+    pub fn sequence_steps(&self) -> Result<Vec<Vec<String>>> {
+        let mut steps = Vec::new();
+        if !self.depends.is_empty() {
+            steps.push(self.depends.clone());
+        }
+
+        for entry in &self.run {
+            match entry.sequence_steps() {
+                Some(entry_steps) => steps.extend(entry_steps),
+                None => bail!(
+                    "Task '{}' runs a script, not just other tasks: {entry}",
+                    self.name
+                ),
+            }
+        }
+
+        if steps.is_empty() {
+            bail!("Task '{}' does not run any other tasks", self.name);
+        }
+        Ok(steps)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn task_info(depends: &[&str], run: Vec<RunEntry>) -> MiseTaskInfo {
+        let json = serde_json::json!({
+            "name": "release",
+            "aliases": [],
+            "description": "",
+            "source": "mise.toml",
+            "depends": depends,
+            "depends_post": [],
+            "wait_for": [],
+            "env": [],
+            "dir": null,
+            "hide": false,
+            "raw": false,
+            "sources": [],
+            "outputs": [],
+            "shell": null,
+            "quiet": false,
+            "silent": false,
+            "tools": {},
+            "run": run,
+            "file": null,
+            "usage_spec": null,
+        });
+        serde_json::from_value(json).unwrap()
+    }
+
+    fn script(script: &str) -> RunEntry {
+        RunEntry::Script(script.to_string())
+    }
+
     #[test]
     fn test_mise_task_creation() {
         let task = MiseTask::new("test".to_string(), "test-source".to_string());
@@ -103,4 +240,69 @@ mod tests {
         task.hide = false;
         assert!(!task.is_hidden());
     }
+
+    #[test]
+    fn test_run_entries_deserialize() {
+        let run: Vec<RunEntry> =
+            serde_json::from_str(r#"["echo hi", {"task": "build"}, {"tasks": ["a", "b"]}]"#)
+                .unwrap();
+        assert_eq!(
+            run,
+            vec![
+                script("echo hi"),
+                RunEntry::Task {
+                    task: "build".to_string()
+                },
+                RunEntry::Tasks {
+                    tasks: vec!["a".to_string(), "b".to_string()]
+                },
+            ]
+        );
+        assert_eq!(run[2].to_string(), "mise run a ::: b");
+    }
+
+    #[test]
+    fn test_sequence_steps_from_task_references() {
+        let info = task_info(
+            &["lint"],
+            vec![
+                RunEntry::Tasks {
+                    tasks: vec!["build".to_string(), "docs".to_string()],
+                },
+                RunEntry::Task {
+                    task: "deploy".to_string(),
+                },
+            ],
+        );
+
+        assert_eq!(
+            info.sequence_steps().unwrap(),
+            vec![vec!["lint"], vec!["build", "docs"], vec!["deploy"]]
+        );
+    }
+
+    #[test]
+    fn test_sequence_steps_from_mise_run_script() {
+        let info = task_info(
+            &[],
+            vec![script("mise run build ::: lint && mise run test")],
+        );
+
+        assert_eq!(
+            info.sequence_steps().unwrap(),
+            vec![vec!["build", "lint"], vec!["test"]]
+        );
+    }
+
+    #[test]
+    fn test_sequence_steps_rejects_other_scripts() {
+        let info = task_info(&[], vec![script("cargo build && mise run test")]);
+        assert!(info.sequence_steps().is_err());
+
+        let info = task_info(&[], vec![script("mise run build --release")]);
+        assert!(info.sequence_steps().is_err());
+
+        let info = task_info(&[], vec![]);
+        assert!(info.sequence_steps().is_err());
+    }
 }
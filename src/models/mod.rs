@@ -1,9 +1,39 @@
 pub mod app_event;
 pub mod app_state;
+pub mod config_layout;
+pub mod editor;
+pub mod error_location;
+pub mod file_watch;
+pub mod freshness;
 pub mod mise_task;
+pub mod notification;
+pub mod output_buffer;
+pub mod output_line;
+pub mod output_search;
+pub mod prompt;
+pub mod resource_usage;
+pub mod run_history;
+pub mod saved_sequence;
 pub mod sequence;
+pub mod task_args;
+pub mod task_input;
+pub mod task_run;
+pub mod terminal;
 
 pub use app_event::AppEvent;
 pub use app_state::AppState;
+pub use error_location::ErrorNavigator;
 pub use mise_task::{MiseTask, MiseTaskInfo};
-pub use sequence::{SequenceEvent, SequenceState};
+pub use output_buffer::{OutputBuffer, ScrollbackConfig};
+pub use output_line::{LineSource, OutputLine};
+pub use output_search::OutputSearch;
+pub use prompt::{Prompt, PromptKind};
+pub use resource_usage::{ResourceUsage, UsageSample};
+pub use run_history::{RunHistory, RunRecord, RunRecording};
+pub use saved_sequence::{SavedSequence, SequenceStore, SEQUENCES_FILE};
+pub use sequence::{
+    SequenceEvent, SequenceState, StepFailureAction, StepFailurePolicy, DEFAULT_NUM_STEPS,
+};
+pub use task_input::TaskInput;
+pub use task_run::{TaskExit, TaskResult};
+pub use terminal::{PtySize, TerminalSession};
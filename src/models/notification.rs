@@ -0,0 +1,251 @@
+use log::warn;
+use std::io::Write;
+use std::process::Stdio;
+use std::time::Duration;
+
+/// Environment variable with the ways to notify, comma separated: `bell`, `osc9`, `osc777`,
+/// or `off` for none
+pub const NOTIFY_ENV: &str = "MISE_SEQUENCER_NOTIFY";
+
+/// Environment variable with a shell command to run on notifications. It gets the title,
+/// message and status (`success` or `failure`) in `NOTIFY_TITLE_VAR`, `NOTIFY_MESSAGE_VAR`
+/// and `NOTIFY_STATUS_VAR`
+pub const NOTIFY_COMMAND_ENV: &str = "MISE_SEQUENCER_NOTIFY_COMMAND";
+
+/// Environment variable with the number of seconds a run must take to be notified about
+pub const NOTIFY_AFTER_ENV: &str = "MISE_SEQUENCER_NOTIFY_AFTER";
+
+pub const NOTIFY_TITLE_VAR: &str = "MISE_SEQUENCER_TITLE";
+pub const NOTIFY_MESSAGE_VAR: &str = "MISE_SEQUENCER_MESSAGE";
+pub const NOTIFY_STATUS_VAR: &str = "MISE_SEQUENCER_STATUS";
+
+/// Runs quicker than this finish quietly unless configured otherwise
+pub const DEFAULT_NOTIFY_AFTER: Duration = Duration::from_secs(10);
+
+/// A way to tell that a run finished
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NotifyMethod {
+    /// The terminal bell, which most terminals turn into an urgency hint
+    Bell,
+    /// OSC 9 desktop notification, supported by iTerm2, WezTerm, Windows Terminal and others
+    Osc9,
+    /// OSC 777 desktop notification, supported by VTE based terminals, foot and others
+    Osc777,
+    /// A shell command
+    Command(String),
+}
+
+/// What a notification says
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Notification {
+    pub title: String,
+    pub message: String,
+    pub success: bool,
+}
+
+/// How and when finished runs are notified about
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NotifyConfig {
+    pub methods: Vec<NotifyMethod>,
+    pub min_duration: Duration,
+}
+
+impl Default for NotifyConfig {
+    fn default() -> Self {
+        Self {
+            methods: vec![NotifyMethod::Bell],
+            min_duration: DEFAULT_NOTIFY_AFTER,
+        }
+    }
+}
+
+impl NotifyConfig {
+    /// The configuration from `NOTIFY_ENV`, `NOTIFY_COMMAND_ENV` and `NOTIFY_AFTER_ENV`
+    pub fn from_env() -> Self {
+        let mut config = Self::default();
+        if let Ok(value) = std::env::var(NOTIFY_ENV) {
+            config.methods = parse_methods(&value);
+        }
+        if let Ok(command) = std::env::var(NOTIFY_COMMAND_ENV)
+            && !command.trim().is_empty()
+        {
+            config.methods.push(NotifyMethod::Command(command));
+        }
+        if let Ok(value) = std::env::var(NOTIFY_AFTER_ENV) {
+            match value.parse::<f64>() {
+                Ok(secs) if secs >= 0.0 => config.min_duration = Duration::from_secs_f64(secs),
+                _ => warn!("Ignoring invalid {NOTIFY_AFTER_ENV} '{value}'"),
+            }
+        }
+        config
+    }
+
+    /// Whether a run that took `elapsed` is notified about
+    pub fn should_notify(&self, elapsed: Duration) -> bool {
+        !self.methods.is_empty() && elapsed >= self.min_duration
+    }
+
+    /// Send `notification` every configured way. Escape sequences go to the terminal
+    /// between frames, they do not show on screen
+    pub fn send(&self, notification: &Notification) {
+        let escapes = self.escape_sequences(notification);
+        if !escapes.is_empty() {
+            let mut stdout = std::io::stdout();
+            if let Err(e) = stdout
+                .write_all(escapes.as_bytes())
+                .and_then(|()| stdout.flush())
+            {
+                warn!("Failed to write notification to the terminal: {e}");
+            }
+        }
+
+        for method in &self.methods {
+            if let NotifyMethod::Command(command) = method
+                && let Err(e) = spawn_command(command, notification)
+            {
+                warn!("Failed to run notify command '{command}': {e}");
+            }
+        }
+    }
+
+    /// What is written to the terminal for `notification`
+    pub fn escape_sequences(&self, notification: &Notification) -> String {
+        let title = sanitize(&notification.title);
+        let message = sanitize(&notification.message);
+        self.methods
+            .iter()
+            .map(|method| match method {
+                NotifyMethod::Bell => "\x07".to_string(),
+                NotifyMethod::Osc9 => format!("\x1b]9;{title}: {message}\x1b\\"),
+                // Fields are separated by `;`, which cannot be escaped
+                NotifyMethod::Osc777 => format!(
+                    "\x1b]777;notify;{};{message}\x1b\\",
+                    title.replace(';', ",")
+                ),
+                NotifyMethod::Command(_) => String::new(),
+            })
+            .collect()
+    }
+}
+
+/// Methods named in `value`, unknown names are skipped with a warning
+fn parse_methods(value: &str) -> Vec<NotifyMethod> {
+    value
+        .split(',')
+        .map(|name| name.trim().to_lowercase())
+        .filter(|name| !name.is_empty())
+        .filter_map(|name| match name.as_str() {
+            "bell" => Some(NotifyMethod::Bell),
+            "osc9" => Some(NotifyMethod::Osc9),
+            "osc777" => Some(NotifyMethod::Osc777),
+            "off" | "none" => None,
+            _ => {
+                warn!("Ignoring unknown notification method '{name}' in {NOTIFY_ENV}");
+                None
+            }
+        })
+        .collect()
+}
+
+/// `text` without control characters, which would end the escape sequence early
+fn sanitize(text: &str) -> String {
+    text.chars().filter(|c| !c.is_control()).collect()
+}
+
+fn spawn_command(command: &str, notification: &Notification) -> std::io::Result<()> {
+    let status = if notification.success {
+        "success"
+    } else {
+        "failure"
+    };
+    tokio::process::Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .env(NOTIFY_TITLE_VAR, &notification.title)
+        .env(NOTIFY_MESSAGE_VAR, &notification.message)
+        .env(NOTIFY_STATUS_VAR, status)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map(drop)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn notification() -> Notification {
+        Notification {
+            title: "build; lint failed".to_string(),
+            message: "step 2: exit code 1\n".to_string(),
+            success: false,
+        }
+    }
+
+    #[test]
+    fn test_parse_methods() {
+        assert_eq!(
+            parse_methods("bell, OSC777,unknown"),
+            vec![NotifyMethod::Bell, NotifyMethod::Osc777]
+        );
+        assert!(parse_methods("off").is_empty());
+    }
+
+    #[test]
+    fn test_escape_sequences() {
+        let config = NotifyConfig {
+            methods: vec![
+                NotifyMethod::Bell,
+                NotifyMethod::Osc9,
+                NotifyMethod::Osc777,
+                NotifyMethod::Command("true".to_string()),
+            ],
+            min_duration: Duration::ZERO,
+        };
+        assert_eq!(
+            config.escape_sequences(&notification()),
+            "\x07\x1b]9;build; lint failed: step 2: exit code 1\x1b\\\
+             \x1b]777;notify;build, lint failed;step 2: exit code 1\x1b\\"
+        );
+    }
+
+    #[test]
+    fn test_quick_runs_are_quiet() {
+        let config = NotifyConfig::default();
+        assert!(!config.should_notify(Duration::from_secs(2)));
+        assert!(config.should_notify(DEFAULT_NOTIFY_AFTER));
+
+        let off = NotifyConfig {
+            methods: Vec::new(),
+            ..NotifyConfig::default()
+        };
+        assert!(!off.should_notify(Duration::from_secs(3600)));
+    }
+
+    #[tokio::test]
+    async fn test_command_gets_notification_in_environment() {
+        let path =
+            std::env::temp_dir().join(format!("mise-sequencer-notify-{}.txt", std::process::id()));
+        let config = NotifyConfig {
+            methods: vec![NotifyMethod::Command(format!(
+                "echo \"$MISE_SEQUENCER_STATUS $MISE_SEQUENCER_TITLE\" > {}",
+                path.display()
+            ))],
+            min_duration: Duration::ZERO,
+        };
+        config.send(&notification());
+
+        for _ in 0..100 {
+            if std::fs::read_to_string(&path).is_ok_and(|content| content.ends_with('\n')) {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "failure build; lint failed\n"
+        );
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,649 @@
+use log::warn;
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::ops::Range;
+#[cfg(unix)]
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::SystemTime;
+
+use super::output_line::{LineSource, OutputLine};
+
+/// Lines of task output kept per tab unless configured otherwise
+pub const DEFAULT_SCROLLBACK_LINES: usize = 100_000;
+
+/// Environment variable overriding the number of output lines kept per tab
+pub const SCROLLBACK_LINES_ENV: &str = "MISE_SEQUENCER_SCROLLBACK_LINES";
+
+/// Environment variable that keeps all output in memory when set to `0` or `false`
+pub const SPILL_TO_DISK_ENV: &str = "MISE_SEQUENCER_SPILL_TO_DISK";
+
+/// Names tried for the spill file before giving up, as other files may take them
+const SPILL_FILE_ATTEMPTS: usize = 16;
+
+/// Lines per chunk, the unit in which output is spilled to disk
+const CHUNK_LINES: usize = 1024;
+
+/// Full chunks kept in memory before the oldest ones are moved to the spill file
+const MEMORY_CHUNKS: usize = 8;
+
+/// The spill file is compacted once at least this many bytes at its start, and no less
+/// than the bytes still in use, belong to lines that left the scrollback
+const COMPACT_THRESHOLD: u64 = 1 << 20;
+
+/// How much task output is kept and where
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScrollbackConfig {
+    pub max_lines: usize,
+    /// Move older output to a temporary file instead of keeping it all in memory
+    pub spill_to_disk: bool,
+}
+
+impl Default for ScrollbackConfig {
+    fn default() -> Self {
+        Self {
+            max_lines: DEFAULT_SCROLLBACK_LINES,
+            spill_to_disk: true,
+        }
+    }
+}
+
+impl ScrollbackConfig {
+    /// The configuration from `SCROLLBACK_LINES_ENV` and `SPILL_TO_DISK_ENV`
+    pub fn from_env() -> Self {
+        let mut config = Self::default();
+        if let Ok(value) = std::env::var(SCROLLBACK_LINES_ENV) {
+            match value.parse() {
+                Ok(lines) if lines > 0 => config.max_lines = lines,
+                _ => warn!("Ignoring invalid {SCROLLBACK_LINES_ENV} '{value}'"),
+            }
+        }
+        if let Ok(value) = std::env::var(SPILL_TO_DISK_ENV) {
+            config.spill_to_disk =
+                !matches!(value.to_lowercase().as_str(), "0" | "false" | "no" | "off");
+        }
+        config
+    }
+}
+
+#[derive(Debug)]
+enum ChunkLines {
+    Memory(Vec<OutputLine>),
+    /// Written to the spill file at `offset` as JSON, one entry per line in `lengths`
+    Spilled {
+        offset: u64,
+        lengths: Vec<u32>,
+    },
+}
+
+#[derive(Debug)]
+struct Chunk {
+    lines: ChunkLines,
+    /// Leading lines that already left the scrollback
+    skipped: usize,
+}
+
+impl Chunk {
+    fn total(&self) -> usize {
+        match &self.lines {
+            ChunkLines::Memory(lines) => lines.len(),
+            ChunkLines::Spilled { lengths, .. } => lengths.len(),
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.total() - self.skipped
+    }
+}
+
+/// Temporary file holding spilled chunks back to back, removed when dropped
+#[derive(Debug)]
+struct SpillFile {
+    path: PathBuf,
+    file: File,
+    end: u64,
+}
+
+impl SpillFile {
+    /// Create a file in the temp directory that only the user can read. Task output may
+    /// hold secrets, and as the directory is shared, a name that is already taken, even
+    /// by a symlink, is never opened but skipped
+    fn create() -> io::Result<Self> {
+        static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+        let mut attempts = 1;
+        loop {
+            let path = std::env::temp_dir().join(format!(
+                "mise-sequencer-output-{}-{}.log",
+                std::process::id(),
+                NEXT_ID.fetch_add(1, Ordering::Relaxed)
+            ));
+            let mut options = OpenOptions::new();
+            options.read(true).write(true).create_new(true);
+            #[cfg(unix)]
+            options.mode(0o600);
+            match options.open(&path) {
+                Ok(file) => return Ok(Self { path, file, end: 0 }),
+                Err(e)
+                    if e.kind() == io::ErrorKind::AlreadyExists
+                        && attempts < SPILL_FILE_ATTEMPTS =>
+                {
+                    attempts += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    fn append(&mut self, lines: &[OutputLine]) -> io::Result<(u64, Vec<u32>)> {
+        let offset = self.end;
+        let lines = lines
+            .iter()
+            .map(serde_json::to_string)
+            .collect::<Result<Vec<_>, _>>()?;
+        let bytes = lines.concat();
+        self.file.seek(SeekFrom::Start(offset))?;
+        self.file.write_all(bytes.as_bytes())?;
+        self.end += bytes.len() as u64;
+        let lengths = lines.iter().map(|line| line.len() as u32).collect();
+        Ok((offset, lengths))
+    }
+
+    fn read(&self, offset: u64, lengths: &[u32]) -> io::Result<Vec<OutputLine>> {
+        let mut bytes = vec![0; lengths.iter().map(|&length| length as usize).sum()];
+        let mut file = &self.file;
+        file.seek(SeekFrom::Start(offset))?;
+        file.read_exact(&mut bytes)?;
+
+        let mut start = 0;
+        Ok(lengths
+            .iter()
+            .map(|&length| {
+                let end = start + length as usize;
+                let line = serde_json::from_slice(&bytes[start..end]);
+                start = end;
+                line
+            })
+            .collect::<Result<_, _>>()?)
+    }
+
+    /// Move the bytes from `offset` on to the start of the file
+    fn discard_before(&mut self, offset: u64) -> io::Result<()> {
+        let mut live = Vec::new();
+        self.file.seek(SeekFrom::Start(offset))?;
+        (&self.file)
+            .take(self.end - offset)
+            .read_to_end(&mut live)?;
+        self.file.seek(SeekFrom::Start(0))?;
+        self.file.write_all(&live)?;
+        self.end = live.len() as u64;
+        self.file.set_len(self.end)
+    }
+}
+
+impl Drop for SpillFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// Scrollback of a tab's output pane. Lines are stored in fixed-size chunks; beyond a few
+/// chunks the oldest ones go to a temporary file, so long outputs cost little memory, and
+/// beyond `max_lines` the oldest lines are dropped
+#[derive(Debug)]
+pub struct OutputBuffer {
+    config: ScrollbackConfig,
+    chunks: VecDeque<Chunk>,
+    len: usize,
+    /// Number of the first line kept, counting every line pushed since the last clear
+    first_line_number: usize,
+    /// Bumped on every clear, so line numbers from before it can be told apart
+    generation: u64,
+    /// Numbers of the stderr lines kept, in order
+    stderr_lines: VecDeque<usize>,
+    /// Time of the earliest line since the last clear
+    started_at: Option<SystemTime>,
+    spill: Option<SpillFile>,
+    /// Lines of the spilled chunk read last, by its offset, so scrolling stays cheap
+    cache: RefCell<Option<(u64, Vec<OutputLine>)>>,
+}
+
+impl Default for OutputBuffer {
+    fn default() -> Self {
+        Self::new(ScrollbackConfig::default())
+    }
+}
+
+impl OutputBuffer {
+    pub fn new(config: ScrollbackConfig) -> Self {
+        Self {
+            config,
+            chunks: VecDeque::new(),
+            len: 0,
+            first_line_number: 0,
+            generation: 0,
+            stderr_lines: VecDeque::new(),
+            started_at: None,
+            spill: None,
+            cache: RefCell::new(None),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Number of the line at index 0. Line numbers stay the same as older lines are
+    /// dropped, unlike indexes
+    pub fn first_line_number(&self) -> usize {
+        self.first_line_number
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Time of the earliest line since the output was last cleared, which timestamps in
+    /// the output pane are relative to
+    pub fn started_at(&self) -> Option<SystemTime> {
+        self.started_at
+    }
+
+    /// Number of stderr lines kept
+    pub fn stderr_len(&self) -> usize {
+        self.stderr_lines.len()
+    }
+
+    /// Index of the stderr line at `position` among the stderr lines
+    pub fn stderr_index(&self, position: usize) -> Option<usize> {
+        let line = self.stderr_lines.get(position)?;
+        Some(line - self.first_line_number)
+    }
+
+    /// Position among the stderr lines of the first one at or after `index`
+    pub fn stderr_position(&self, index: usize) -> usize {
+        let line = self.first_line_number + index;
+        self.stderr_lines.partition_point(|&stderr| stderr < line)
+    }
+
+    /// Append a message of the app, see `push_back`
+    pub fn push_notice(&mut self, text: impl Into<String>) -> usize {
+        self.push_back(OutputLine::notice(text))
+    }
+
+    /// Append a line, returning how many of the oldest lines were dropped to make room
+    pub fn push_back(&mut self, line: OutputLine) -> usize {
+        if line.source == LineSource::Stderr {
+            self.stderr_lines
+                .push_back(self.first_line_number + self.len);
+        }
+        self.started_at = Some(
+            self.started_at
+                .map_or(line.time, |time| time.min(line.time)),
+        );
+        match self.chunks.back_mut() {
+            Some(Chunk {
+                lines: ChunkLines::Memory(lines),
+                ..
+            }) if lines.len() < CHUNK_LINES => lines.push(line),
+            _ => {
+                let mut lines = Vec::with_capacity(CHUNK_LINES);
+                lines.push(line);
+                self.chunks.push_back(Chunk {
+                    lines: ChunkLines::Memory(lines),
+                    skipped: 0,
+                });
+            }
+        }
+        self.len += 1;
+
+        self.spill_old_chunks();
+        self.drop_oldest_lines()
+    }
+
+    /// The latest line, which is always in memory
+    pub fn back(&self) -> Option<&OutputLine> {
+        match &self.chunks.back()?.lines {
+            ChunkLines::Memory(lines) if self.len > 0 => lines.last(),
+            _ => None,
+        }
+    }
+
+    pub fn get(&self, index: usize) -> Option<Cow<'_, OutputLine>> {
+        self.lines(index..index + 1).pop()
+    }
+
+    /// The lines in `range`, which is clamped to the lines kept
+    pub fn lines(&self, range: Range<usize>) -> Vec<Cow<'_, OutputLine>> {
+        let end = range.end.min(self.len);
+        let mut start = range.start;
+        let mut lines = Vec::with_capacity(end.saturating_sub(start));
+
+        let mut chunk_start = 0;
+        for chunk in &self.chunks {
+            if start >= end {
+                break;
+            }
+            let chunk_end = chunk_start + chunk.len();
+            if start < chunk_end {
+                let from = start - chunk_start + chunk.skipped;
+                let to = end.min(chunk_end) - chunk_start + chunk.skipped;
+                lines.extend(self.chunk_lines(chunk, from..to));
+                start = end.min(chunk_end);
+            }
+            chunk_start = chunk_end;
+        }
+        lines
+    }
+
+    /// Every line kept, oldest first. Spilled lines are read back one chunk at a time
+    pub fn iter(&self) -> impl Iterator<Item = Cow<'_, OutputLine>> {
+        self.chunks
+            .iter()
+            .flat_map(|chunk| self.chunk_lines(chunk, chunk.skipped..chunk.total()))
+    }
+
+    pub fn clear(&mut self) {
+        self.chunks.clear();
+        self.len = 0;
+        self.first_line_number = 0;
+        self.generation += 1;
+        self.stderr_lines.clear();
+        self.started_at = None;
+        self.spill = None;
+        self.cache.take();
+    }
+
+    fn chunk_lines<'a>(
+        &'a self,
+        chunk: &'a Chunk,
+        range: Range<usize>,
+    ) -> Vec<Cow<'a, OutputLine>> {
+        match &chunk.lines {
+            ChunkLines::Memory(lines) => lines[range].iter().map(Cow::Borrowed).collect(),
+            ChunkLines::Spilled { offset, lengths } => {
+                let mut cache = self.cache.borrow_mut();
+                if cache.as_ref().is_none_or(|(cached, _)| cached != offset) {
+                    let lines = self
+                        .spill
+                        .as_ref()
+                        .ok_or_else(|| io::Error::other("spill file is gone"))
+                        .and_then(|spill| spill.read(*offset, lengths))
+                        .unwrap_or_else(|e| {
+                            warn!("Failed to read spilled output: {e}");
+                            vec![OutputLine::notice(""); lengths.len()]
+                        });
+                    *cache = Some((*offset, lines));
+                }
+                let (_, lines) = cache.as_ref().expect("cache was just filled");
+                lines[range].iter().cloned().map(Cow::Owned).collect()
+            }
+        }
+    }
+
+    /// Write the oldest full chunks in memory to the spill file, keeping `MEMORY_CHUNKS`
+    /// and the chunk being filled. Output stays in memory if the file cannot be written
+    fn spill_old_chunks(&mut self) {
+        if !self.config.spill_to_disk {
+            return;
+        }
+        let spilled = self
+            .chunks
+            .iter()
+            .take_while(|chunk| matches!(chunk.lines, ChunkLines::Spilled { .. }))
+            .count();
+        if self.chunks.len() - spilled <= MEMORY_CHUNKS + 1 {
+            return;
+        }
+
+        let spill = match &mut self.spill {
+            Some(spill) => spill,
+            None => match SpillFile::create() {
+                Ok(spill) => self.spill.insert(spill),
+                Err(e) => {
+                    warn!("Keeping task output in memory, cannot create spill file: {e}");
+                    self.config.spill_to_disk = false;
+                    return;
+                }
+            },
+        };
+
+        let chunk = &mut self.chunks[spilled];
+        let ChunkLines::Memory(lines) = &chunk.lines else {
+            return;
+        };
+        match spill.append(lines) {
+            Ok((offset, lengths)) => chunk.lines = ChunkLines::Spilled { offset, lengths },
+            Err(e) => {
+                warn!("Keeping task output in memory, cannot write spill file: {e}");
+                self.config.spill_to_disk = false;
+            }
+        }
+    }
+
+    fn drop_oldest_lines(&mut self) -> usize {
+        let mut dropped = 0;
+        let mut dropped_spilled_chunk = false;
+
+        while self.len > self.config.max_lines {
+            let excess = self.len - self.config.max_lines;
+            let Some(first) = self.chunks.front_mut() else {
+                break;
+            };
+            let available = first.len();
+            if available <= excess {
+                dropped_spilled_chunk |= matches!(first.lines, ChunkLines::Spilled { .. });
+                self.chunks.pop_front();
+                self.len -= available;
+                dropped += available;
+            } else {
+                if let ChunkLines::Memory(lines) = &mut first.lines {
+                    lines[first.skipped..first.skipped + excess]
+                        .iter_mut()
+                        .for_each(|line| *line = OutputLine::notice(""));
+                }
+                first.skipped += excess;
+                self.len -= excess;
+                dropped += excess;
+            }
+        }
+
+        if dropped_spilled_chunk {
+            self.compact_spill_file();
+        }
+        self.first_line_number += dropped;
+        while self
+            .stderr_lines
+            .front()
+            .is_some_and(|&line| line < self.first_line_number)
+        {
+            self.stderr_lines.pop_front();
+        }
+        dropped
+    }
+
+    /// Reclaim the space of spilled chunks that left the scrollback
+    fn compact_spill_file(&mut self) {
+        let Some(spill) = &mut self.spill else {
+            return;
+        };
+        let first_offset = self.chunks.iter().find_map(|chunk| match chunk.lines {
+            ChunkLines::Spilled { offset, .. } => Some(offset),
+            ChunkLines::Memory(_) => None,
+        });
+
+        let Some(first_offset) = first_offset else {
+            self.spill = None;
+            self.cache.take();
+            return;
+        };
+        if first_offset < COMPACT_THRESHOLD || first_offset < spill.end - first_offset {
+            return;
+        }
+
+        match spill.discard_before(first_offset) {
+            Ok(()) => {
+                for chunk in &mut self.chunks {
+                    if let ChunkLines::Spilled { offset, .. } = &mut chunk.lines {
+                        *offset -= first_offset;
+                    }
+                }
+            }
+            Err(e) => warn!("Failed to compact spilled output: {e}"),
+        }
+        self.cache.take();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn buffer(max_lines: usize, spill_to_disk: bool) -> OutputBuffer {
+        OutputBuffer::new(ScrollbackConfig {
+            max_lines,
+            spill_to_disk,
+        })
+    }
+
+    fn texts<'a>(lines: impl IntoIterator<Item = Cow<'a, OutputLine>>) -> Vec<String> {
+        lines.into_iter().map(|line| line.text.clone()).collect()
+    }
+
+    fn spilled_chunks(buffer: &OutputBuffer) -> usize {
+        buffer
+            .chunks
+            .iter()
+            .filter(|chunk| matches!(chunk.lines, ChunkLines::Spilled { .. }))
+            .count()
+    }
+
+    #[test]
+    fn test_oldest_lines_are_dropped_beyond_max_lines() {
+        let mut output = buffer(3, false);
+        for line in ["a", "b", "c"] {
+            assert_eq!(output.push_back(OutputLine::stdout(line)), 0);
+        }
+        assert_eq!(output.push_notice("d"), 1);
+
+        assert_eq!(output.len(), 3);
+        assert_eq!(output.back().unwrap().text, "d");
+        assert_eq!(output.get(0).unwrap().text, "b");
+        assert_eq!(output.get(3), None);
+        assert_eq!(texts(output.iter()), vec!["b", "c", "d"]);
+        assert_eq!(texts(output.lines(1..10)), vec!["c", "d"]);
+        assert_eq!(output.first_line_number(), 1);
+
+        output.clear();
+        assert!(output.is_empty());
+        assert_eq!(output.first_line_number(), 0);
+        assert_eq!(output.generation(), 1);
+        assert_eq!(output.back(), None);
+    }
+
+    #[test]
+    fn test_lines_across_chunks() {
+        let mut output = buffer(CHUNK_LINES * 3, false);
+        for i in 0..CHUNK_LINES * 4 {
+            output.push_notice(i.to_string());
+        }
+
+        assert_eq!(output.len(), CHUNK_LINES * 3);
+        assert_eq!(output.get(0).unwrap().text, CHUNK_LINES.to_string());
+        let around_boundary = output.lines(CHUNK_LINES - 1..CHUNK_LINES + 1);
+        assert_eq!(
+            texts(around_boundary),
+            vec![
+                (2 * CHUNK_LINES - 1).to_string(),
+                (2 * CHUNK_LINES).to_string()
+            ]
+        );
+        assert_eq!(output.iter().count(), CHUNK_LINES * 3);
+    }
+
+    #[test]
+    fn test_old_chunks_spill_to_disk() {
+        let chunks = MEMORY_CHUNKS + 4;
+        let mut output = buffer(CHUNK_LINES * (chunks - 1), true);
+        for i in 0..CHUNK_LINES * chunks {
+            output.push_back(OutputLine::stdout(format!("line {i}")).with_task("build"));
+        }
+
+        // The oldest chunk left the scrollback, the next ones are on disk
+        assert_eq!(output.len(), CHUNK_LINES * (chunks - 1));
+        assert_eq!(spilled_chunks(&output), 2);
+        let path = output.spill.as_ref().unwrap().path.clone();
+        assert!(path.exists());
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600, "output is private to the user");
+        }
+
+        let first = output.get(0).unwrap();
+        assert_eq!(first.text, format!("line {CHUNK_LINES}"));
+        assert_eq!(first.task.as_deref(), Some("build"));
+        let expected: Vec<String> = (CHUNK_LINES..CHUNK_LINES * chunks)
+            .map(|i| format!("line {i}"))
+            .collect();
+        assert_eq!(texts(output.iter()), expected);
+
+        output.clear();
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_spill_file_is_compacted() {
+        let mut output = buffer(CHUNK_LINES * (MEMORY_CHUNKS + 2), true);
+        let line = "x".repeat(512);
+        for _ in 0..CHUNK_LINES * (MEMORY_CHUNKS + 8) {
+            output.push_notice(line.clone());
+        }
+
+        let spill = output.spill.as_ref().unwrap();
+        let live: u64 = output
+            .chunks
+            .iter()
+            .filter_map(|chunk| match &chunk.lines {
+                ChunkLines::Spilled { lengths, .. } => {
+                    Some(lengths.iter().map(|&length| length as u64).sum::<u64>())
+                }
+                ChunkLines::Memory(_) => None,
+            })
+            .sum();
+        assert!(spill.end < 2 * live + COMPACT_THRESHOLD);
+        assert!(output.iter().all(|kept| kept.text == line));
+    }
+
+    #[test]
+    fn test_stderr_lines_are_indexed() {
+        let mut output = buffer(4, false);
+        for (i, line) in ["a", "b", "c", "d", "e"].into_iter().enumerate() {
+            if i % 2 == 0 {
+                output.push_back(OutputLine::stderr(line));
+            } else {
+                output.push_back(OutputLine::stdout(line));
+            }
+        }
+
+        // "a" left the scrollback, "c" and "e" are at indexes 1 and 3
+        assert_eq!(output.stderr_len(), 2);
+        assert_eq!(output.stderr_index(0), Some(1));
+        assert_eq!(output.stderr_index(1), Some(3));
+        assert_eq!(output.stderr_index(2), None);
+        assert_eq!(output.stderr_position(2), 1);
+        assert_eq!(output.stderr_position(4), 2);
+
+        output.clear();
+        assert_eq!(output.stderr_len(), 0);
+        assert_eq!(output.started_at(), None);
+    }
+}
@@ -0,0 +1,83 @@
+use serde::{Deserialize, Serialize};
+use std::time::SystemTime;
+
+/// Where a line of the output pane comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LineSource {
+    /// Printed by a task on its standard output, or on its terminal in PTY mode
+    Stdout,
+    /// Printed by a task on its standard error
+    Stderr,
+    /// Typed into a running task
+    Stdin,
+    /// A message of the app itself, such as a run summary
+    Notice,
+}
+
+/// A line of the output pane, with what printed it and when
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OutputLine {
+    pub text: String,
+    pub source: LineSource,
+    /// When the line arrived
+    pub time: SystemTime,
+    /// Task that printed the line, or was typed into
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub task: Option<String>,
+    /// Index of the sequence step that was running
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub step: Option<usize>,
+    /// Shown with the task name, for steps running several tasks at once
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub tagged: bool,
+}
+
+impl OutputLine {
+    pub fn new(source: LineSource, text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            source,
+            time: SystemTime::now(),
+            task: None,
+            step: None,
+            tagged: false,
+        }
+    }
+
+    pub fn stdout(text: impl Into<String>) -> Self {
+        Self::new(LineSource::Stdout, text)
+    }
+
+    pub fn stderr(text: impl Into<String>) -> Self {
+        Self::new(LineSource::Stderr, text)
+    }
+
+    pub fn stdin(text: impl Into<String>) -> Self {
+        Self::new(LineSource::Stdin, text)
+    }
+
+    pub fn notice(text: impl Into<String>) -> Self {
+        Self::new(LineSource::Notice, text)
+    }
+
+    pub fn with_task(mut self, task_name: &str) -> Self {
+        self.task = Some(task_name.to_string());
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serialization_skips_unset_fields() {
+        let line = OutputLine::stdout("hello").with_task("build");
+        let json = serde_json::to_string(&line).unwrap();
+        assert!(json.contains(r#""source":"stdout""#));
+        assert!(!json.contains("step"));
+        assert!(!json.contains("tagged"));
+        assert_eq!(serde_json::from_str::<OutputLine>(&json).unwrap(), line);
+    }
+}
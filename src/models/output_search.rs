@@ -0,0 +1,244 @@
+use regex::{Regex, RegexBuilder};
+
+use super::output_buffer::OutputBuffer;
+
+/// `line` without ANSI escape sequences, i.e. the text as it shows in the output pane
+pub fn strip_ansi(line: &str) -> String {
+    let mut text = String::with_capacity(line.len());
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            text.push(c);
+            continue;
+        }
+        match chars.next() {
+            // CSI, e.g. colors: parameters up to a final byte in @..~
+            Some('[') => {
+                for c in chars.by_ref() {
+                    if ('@'..='~').contains(&c) {
+                        break;
+                    }
+                }
+            }
+            // OSC, e.g. hyperlinks: up to BEL or ESC \
+            Some(']') => {
+                while let Some(c) = chars.next() {
+                    if c == '\x07' || (c == '\x1b' && chars.next_if_eq(&'\\').is_some()) {
+                        break;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    text
+}
+
+/// Compile a search pattern. It is case-insensitive unless it contains an uppercase letter
+pub fn search_regex(pattern: &str) -> Result<Regex, regex::Error> {
+    RegexBuilder::new(pattern)
+        .case_insensitive(!pattern.chars().any(char::is_uppercase))
+        .build()
+}
+
+/// Search through the output pane. Matches are kept by line number, see
+/// `OutputBuffer::first_line_number`, and brought up to date as output arrives
+#[derive(Debug, Clone)]
+pub struct OutputSearch {
+    pub pattern: String,
+    regex: Regex,
+    /// Numbers of the lines that match, in order
+    matches: Vec<usize>,
+    /// Number of the line last jumped to
+    current: Option<usize>,
+    generation: u64,
+    /// Lines before this number have been searched
+    searched_to: usize,
+}
+
+impl OutputSearch {
+    pub fn new(pattern: &str) -> Result<Self, regex::Error> {
+        Ok(Self {
+            pattern: pattern.to_string(),
+            regex: search_regex(pattern)?,
+            matches: Vec::new(),
+            current: None,
+            generation: 0,
+            searched_to: 0,
+        })
+    }
+
+    pub fn regex(&self) -> &Regex {
+        &self.regex
+    }
+
+    /// Search the lines added since the last update, forgetting matches that left the
+    /// scrollback. A cleared output is searched from scratch
+    pub fn update(&mut self, output: &OutputBuffer) {
+        let first = output.first_line_number();
+        if self.generation != output.generation() {
+            self.generation = output.generation();
+            self.matches.clear();
+            self.current = None;
+            self.searched_to = first;
+        }
+        self.matches.retain(|&line| line >= first);
+        if self.current.is_some_and(|line| line < first) {
+            self.current = None;
+        }
+
+        let from = self.searched_to.max(first);
+        let new_lines = output.lines(from - first..output.len());
+        self.matches.extend(
+            new_lines
+                .iter()
+                .enumerate()
+                .filter(|(_, line)| self.regex.is_match(&strip_ansi(&line.text)))
+                .map(|(offset, _)| from + offset),
+        );
+        self.searched_to = first + output.len();
+    }
+
+    pub fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    /// Index into the output of the line last jumped to
+    pub fn current_index(&self, output: &OutputBuffer) -> Option<usize> {
+        self.current
+            .and_then(|line| line.checked_sub(output.first_line_number()))
+    }
+
+    /// Jump to the first match at or after the line at `index`, wrapping around to the
+    /// first match. Returns the index of the match
+    pub fn jump_from(&mut self, output: &OutputBuffer, index: usize) -> Option<usize> {
+        let line = output.first_line_number() + index;
+        let target = self
+            .matches
+            .iter()
+            .find(|&&matched| matched >= line)
+            .or(self.matches.first())
+            .copied();
+        self.jump_to(output, target)
+    }
+
+    /// Jump to the match after the current one, or before it with `forward` unset,
+    /// wrapping around at either end. Returns the index of the match
+    pub fn jump_next(&mut self, output: &OutputBuffer, forward: bool) -> Option<usize> {
+        let target = match (self.current, forward) {
+            (None, true) => self.matches.first(),
+            (None, false) => self.matches.last(),
+            (Some(current), true) => self
+                .matches
+                .iter()
+                .find(|&&line| line > current)
+                .or(self.matches.first()),
+            (Some(current), false) => self
+                .matches
+                .iter()
+                .rev()
+                .find(|&&line| line < current)
+                .or(self.matches.last()),
+        }
+        .copied();
+        self.jump_to(output, target)
+    }
+
+    fn jump_to(&mut self, output: &OutputBuffer, target: Option<usize>) -> Option<usize> {
+        self.current = target;
+        self.current_index(output)
+    }
+
+    /// Position of the current match among all matches, e.g. `3/15`
+    pub fn status(&self) -> String {
+        let position = self
+            .current
+            .and_then(|current| self.matches.iter().position(|&line| line == current));
+        match position {
+            Some(position) => format!("{}/{}", position + 1, self.matches.len()),
+            None if self.matches.len() == 1 => "1 match".to_string(),
+            None => format!("{} matches", self.matches.len()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::output_buffer::ScrollbackConfig;
+    use crate::models::output_line::OutputLine;
+
+    fn output(lines: &[&str], max_lines: usize) -> OutputBuffer {
+        let mut output = OutputBuffer::new(ScrollbackConfig {
+            max_lines,
+            spill_to_disk: false,
+        });
+        for line in lines {
+            output.push_back(OutputLine::stdout(*line));
+        }
+        output
+    }
+
+    #[test]
+    fn test_strip_ansi() {
+        assert_eq!(strip_ansi("\x1b[1;31merror\x1b[0m: x"), "error: x");
+        assert_eq!(
+            strip_ansi("\x1b]8;;https://x.dev\x1b\\link\x1b]8;;\x07!"),
+            "link!"
+        );
+        assert_eq!(strip_ansi("plain"), "plain");
+    }
+
+    #[test]
+    fn test_search_regex_is_smart_case() {
+        assert!(search_regex("error").unwrap().is_match("ERROR: x"));
+        assert!(!search_regex("Error").unwrap().is_match("ERROR: x"));
+        assert!(search_regex(r"E\d{4}").unwrap().is_match("error[E0308]"));
+        assert!(search_regex("(").is_err());
+    }
+
+    #[test]
+    fn test_matches_ignore_escape_codes() {
+        // "31m" is part of the color code, not of the text
+        let output = output(&["\x1b[31mred\x1b[0m", "plain 31m"], 10);
+        let mut search = OutputSearch::new("31m").unwrap();
+        search.update(&output);
+        assert_eq!(search.match_count(), 1);
+        assert_eq!(search.jump_next(&output, true), Some(1));
+    }
+
+    #[test]
+    fn test_jumps_wrap_around() {
+        let output = output(&["a", "hit 1", "b", "hit 2", "c"], 10);
+        let mut search = OutputSearch::new("hit").unwrap();
+        search.update(&output);
+
+        assert_eq!(search.jump_from(&output, 2), Some(3));
+        assert_eq!(search.status(), "2/2");
+        assert_eq!(search.jump_next(&output, true), Some(1));
+        assert_eq!(search.jump_next(&output, false), Some(3));
+        assert_eq!(search.jump_from(&output, 4), Some(1));
+    }
+
+    #[test]
+    fn test_update_follows_new_and_dropped_lines() {
+        let mut output = output(&["hit 1", "a"], 3);
+        let mut search = OutputSearch::new("hit").unwrap();
+        search.update(&output);
+        assert_eq!(search.jump_next(&output, true), Some(0));
+
+        output.push_back(OutputLine::stdout("hit 2"));
+        output.push_back(OutputLine::stdout("b"));
+        search.update(&output);
+        // "hit 1" left the scrollback
+        assert_eq!(search.match_count(), 1);
+        assert_eq!(search.current_index(&output), None);
+        assert_eq!(search.jump_next(&output, true), Some(1));
+
+        output.clear();
+        output.push_back(OutputLine::stdout("hit 3"));
+        search.update(&output);
+        assert_eq!(search.match_count(), 1);
+        assert_eq!(search.status(), "1 match");
+    }
+}
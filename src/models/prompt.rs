@@ -0,0 +1,72 @@
+use tui_input::Input;
+
+/// What a text prompt's answer is used for
+#[derive(Debug, Clone, PartialEq)]
+pub enum PromptKind {
+    /// Save the current sequence under the entered name
+    SaveSequence,
+    /// Rename the saved sequence with this name
+    RenameSequence(String),
+    /// Write the current sequence to mise.toml as a task with the entered name
+    AddAsTask,
+    /// Show only the past runs matching the entered text, an empty answer shows all
+    FilterHistory,
+    /// Search the output pane for the entered pattern as it is typed
+    SearchOutput,
+}
+
+impl PromptKind {
+    pub fn title(&self) -> String {
+        match self {
+            Self::SaveSequence => " Save sequence as ".to_string(),
+            Self::RenameSequence(name) => format!(" Rename sequence '{name}' "),
+            Self::AddAsTask => " Add sequence as mise task ".to_string(),
+            Self::FilterHistory => " Filter run history ".to_string(),
+            Self::SearchOutput => " Search output ".to_string(),
+        }
+    }
+}
+
+/// Single-line text prompt shown as a dialog over the current view
+#[derive(Debug, Clone)]
+pub struct Prompt {
+    pub kind: PromptKind,
+    pub input: Input,
+}
+
+impl Prompt {
+    pub fn new(kind: PromptKind, initial_value: &str) -> Self {
+        Self {
+            kind,
+            input: Input::new(initial_value.to_string()),
+        }
+    }
+
+    /// Entered text with surrounding whitespace removed
+    pub fn value(&self) -> &str {
+        self.input.value().trim()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prompt_value_is_trimmed() {
+        let prompt = Prompt::new(PromptKind::SaveSequence, "  release  ");
+        assert_eq!(prompt.value(), "release");
+    }
+
+    #[test]
+    fn test_prompt_titles() {
+        assert_eq!(PromptKind::SaveSequence.title(), " Save sequence as ");
+        assert_eq!(
+            PromptKind::RenameSequence("ci".to_string()).title(),
+            " Rename sequence 'ci' "
+        );
+        assert_eq!(PromptKind::AddAsTask.title(), " Add sequence as mise task ");
+        assert_eq!(PromptKind::FilterHistory.title(), " Filter run history ");
+        assert_eq!(PromptKind::SearchOutput.title(), " Search output ");
+    }
+}
@@ -0,0 +1,101 @@
+use serde::{Deserialize, Serialize};
+
+/// CPU and memory use of a task's process tree at one moment, or the peaks over a run
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct ResourceUsage {
+    /// Share of one CPU core, above 100 when several cores are busy
+    pub cpu_percent: f64,
+    /// Resident memory of every process in the tree
+    pub rss_bytes: u64,
+    /// Processes started below the task's own process
+    pub children: usize,
+}
+
+impl ResourceUsage {
+    /// The higher of each value, for peaks
+    pub fn max(self, other: Self) -> Self {
+        Self {
+            cpu_percent: self.cpu_percent.max(other.cpu_percent),
+            rss_bytes: self.rss_bytes.max(other.rss_bytes),
+            children: self.children.max(other.children),
+        }
+    }
+
+    /// Use of several process trees together, e.g. the tasks of a step
+    pub fn sum(usages: impl IntoIterator<Item = Self>) -> Self {
+        usages
+            .into_iter()
+            .fold(Self::default(), |total, usage| Self {
+                cpu_percent: total.cpu_percent + usage.cpu_percent,
+                rss_bytes: total.rss_bytes + usage.rss_bytes,
+                children: total.children + usage.children,
+            })
+    }
+
+    /// Compact description, e.g. `CPU 95% · 120.4 MiB · 3 children`
+    pub fn label(&self) -> String {
+        let children = match self.children {
+            1 => "1 child".to_string(),
+            n => format!("{n} children"),
+        };
+        format!(
+            "CPU {:.0}% · {} · {children}",
+            self.cpu_percent,
+            format_bytes(self.rss_bytes)
+        )
+    }
+}
+
+/// The latest usage of a running task, as sent while it runs
+#[derive(Debug, Clone, PartialEq)]
+pub struct UsageSample {
+    pub task_name: String,
+    pub usage: ResourceUsage,
+}
+
+/// Byte count in binary units, e.g. `512 B` or `120.4 MiB`
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{value:.1} {}", UNITS[unit])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usage(cpu_percent: f64, mib: u64, children: usize) -> ResourceUsage {
+        ResourceUsage {
+            cpu_percent,
+            rss_bytes: mib * 1024 * 1024,
+            children,
+        }
+    }
+
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(1536), "1.5 KiB");
+        assert_eq!(format_bytes(120 * 1024 * 1024), "120.0 MiB");
+        assert_eq!(format_bytes(3 * 1024 * 1024 * 1024), "3.0 GiB");
+    }
+
+    #[test]
+    fn test_peak_and_total() {
+        let peak = usage(150.0, 40, 1).max(usage(20.0, 90, 4));
+        assert_eq!(peak, usage(150.0, 90, 4));
+
+        let total = ResourceUsage::sum([usage(50.0, 10, 1), usage(25.0, 20, 2)]);
+        assert_eq!(total, usage(75.0, 30, 3));
+        assert_eq!(total.label(), "CPU 75% · 30.0 MiB · 3 children");
+        assert_eq!(usage(0.4, 0, 1).label(), "CPU 0% · 0 B · 1 child");
+    }
+}
@@ -0,0 +1,615 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, DirBuilder, File, OpenOptions};
+use std::io::{self, BufWriter, Write};
+#[cfg(unix)]
+use std::os::unix::fs::{DirBuilderExt, OpenOptionsExt};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime};
+
+use super::output_line::OutputLine;
+use super::resource_usage::format_bytes;
+use super::saved_sequence::{SavedSequence, SavedStep};
+use super::task_args::task_command_line;
+use super::task_run::TaskResult;
+
+/// Index of recorded runs inside the history directory, one JSON record per line
+const INDEX_FILE: &str = "runs.jsonl";
+
+/// Oldest runs beyond this many are dropped, together with their logs
+pub const MAX_HISTORY_RUNS: usize = 500;
+
+/// Output of a run beyond this many bytes of log is not kept
+pub const MAX_RUN_LOG_BYTES: u64 = 16 * 1024 * 1024;
+
+/// Runs started by this process so far, telling apart runs started in the same millisecond
+static RUNS_STARTED: AtomicU64 = AtomicU64::new(0);
+
+/// Where run history is kept: `$XDG_STATE_HOME/mise-command-sequencer/history`, falling
+/// back to `~/.local/state`. `None` without either, rather than a shared directory where
+/// other users could read the output of runs
+pub fn default_history_dir() -> Option<PathBuf> {
+    let state_home = std::env::var_os("XDG_STATE_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| {
+            std::env::var_os("HOME")
+                .filter(|home| !home.is_empty())
+                .map(|home| PathBuf::from(home).join(".local/state"))
+        })?;
+    Some(state_home.join("mise-command-sequencer").join("history"))
+}
+
+/// Create `dir` and its parents, readable by the user only
+fn create_private_dir(dir: &Path) -> io::Result<()> {
+    let mut builder = DirBuilder::new();
+    builder.recursive(true);
+    #[cfg(unix)]
+    builder.mode(0o700);
+    builder.create(dir)
+}
+
+/// Options for files of the history, which hold task output and are readable by the
+/// user only
+fn private_file_options() -> OpenOptions {
+    let mut options = OpenOptions::new();
+    #[cfg(unix)]
+    options.mode(0o600);
+    options
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RunKind {
+    Task,
+    Sequence,
+}
+
+/// One finished task or sequence run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunRecord {
+    pub id: String,
+    pub kind: RunKind,
+    /// Task name, or the name of the sequence if it had one
+    pub name: String,
+    /// What was run, e.g. `mise run build` or `build + lint → test`
+    pub command: String,
+    /// Directory the run was started in, history is shown per project
+    pub project_dir: PathBuf,
+    /// Step layout at the time of the run, a task run is a single step
+    pub steps: Vec<SavedStep>,
+    pub started_at: SystemTime,
+    pub finished_at: SystemTime,
+    /// Every task result of the run in completion order, retries included
+    pub results: Vec<TaskResult>,
+}
+
+impl RunRecord {
+    /// A run of a single task with `args`
+    pub fn task(task_name: &str, args: &[String], project_dir: &Path) -> Self {
+        let mut step = SavedStep {
+            tasks: vec![task_name.to_string()],
+            on_failure: Default::default(),
+            args: Default::default(),
+        };
+        if !args.is_empty() {
+            step.args.insert(task_name.to_string(), args.to_vec());
+        }
+        Self::new(
+            RunKind::Task,
+            task_name,
+            &format!("mise run {}", task_command_line(task_name, args)),
+            project_dir,
+            vec![step],
+        )
+    }
+
+    /// A run of `sequence`, whose name may be empty for an unsaved sequence
+    pub fn sequence(sequence: SavedSequence, command: &str, project_dir: &Path) -> Self {
+        let name = if sequence.name.is_empty() {
+            "sequence".to_string()
+        } else {
+            sequence.name
+        };
+        Self::new(
+            RunKind::Sequence,
+            &name,
+            command,
+            project_dir,
+            sequence.steps,
+        )
+    }
+
+    fn new(
+        kind: RunKind,
+        name: &str,
+        command: &str,
+        project_dir: &Path,
+        steps: Vec<SavedStep>,
+    ) -> Self {
+        let started_at = SystemTime::now();
+        let millis = started_at
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis();
+        let run = RUNS_STARTED.fetch_add(1, Ordering::Relaxed);
+        Self {
+            id: format!("{millis}-{}-{run}", std::process::id()),
+            kind,
+            name: name.to_string(),
+            command: command.to_string(),
+            project_dir: project_dir.to_path_buf(),
+            steps,
+            started_at,
+            finished_at: started_at,
+            results: Vec::new(),
+        }
+    }
+
+    /// Whether every task that ran succeeded
+    pub fn success(&self) -> bool {
+        self.results.iter().all(TaskResult::success)
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.finished_at
+            .duration_since(self.started_at)
+            .unwrap_or_default()
+    }
+
+    /// The run as a sequence, for loading it back into the builder
+    pub fn to_sequence(&self) -> SavedSequence {
+        SavedSequence {
+            name: self.name.clone(),
+            steps: self.steps.clone(),
+        }
+    }
+
+    /// Case-insensitive match of `filter` against the name, the command and the status
+    pub fn matches(&self, filter: &str) -> bool {
+        let filter = filter.to_lowercase();
+        let status = if self.success() { "ok" } else { "failed" };
+        [self.name.as_str(), self.command.as_str(), status]
+            .iter()
+            .any(|field| field.to_lowercase().contains(&filter))
+    }
+}
+
+/// A run in progress: its record so far, with every output line it produces appended to
+/// the run's log file as it arrives instead of being kept in memory
+#[derive(Debug)]
+pub struct RunRecording {
+    pub record: RunRecord,
+    /// `None` while history is not kept on disk, or the log could not be created
+    log_path: Option<PathBuf>,
+    /// `None` once the log could not be opened or written, or is full
+    log: Option<BufWriter<File>>,
+    log_bytes: u64,
+    max_log_bytes: u64,
+    finished: bool,
+}
+
+impl RunRecording {
+    /// Append a line to the run's log, until it reaches `MAX_RUN_LOG_BYTES`
+    pub fn push(&mut self, line: &OutputLine) {
+        let Some(log) = &mut self.log else {
+            return;
+        };
+        let mut entry = match serde_json::to_vec(line) {
+            Ok(entry) => entry,
+            Err(e) => {
+                log::warn!("Failed to serialize a line of run {}: {e}", self.record.id);
+                return;
+            }
+        };
+        entry.push(b'\n');
+
+        let full = self.log_bytes + entry.len() as u64 > self.max_log_bytes;
+        if full {
+            let notice = OutputLine::notice(format!(
+                "Output beyond {} not kept in history",
+                format_bytes(self.max_log_bytes)
+            ));
+            entry = serde_json::to_vec(&notice).unwrap_or_default();
+            entry.push(b'\n');
+        }
+        let written = log.write_all(&entry).and_then(|()| {
+            if full {
+                log.flush()?;
+            }
+            Ok(())
+        });
+        self.log_bytes += entry.len() as u64;
+        if let Err(e) = written {
+            log::warn!("Stopped writing the log of run {}: {e}", self.record.id);
+            self.log = None;
+        } else if full {
+            self.log = None;
+        }
+    }
+
+    /// Complete the record once the run is over, with its log written out
+    pub fn finish(mut self) -> RunRecord {
+        if let Some(mut log) = self.log.take()
+            && let Err(e) = log.flush()
+        {
+            log::warn!("Failed to write the log of run {}: {e}", self.record.id);
+        }
+        self.finished = true;
+        self.record.finished_at = SystemTime::now();
+        self.record.clone()
+    }
+}
+
+impl Drop for RunRecording {
+    fn drop(&mut self) {
+        // A run that never finished is not in the index, so its log would never be pruned
+        if !self.finished
+            && let Some(log_path) = &self.log_path
+        {
+            self.log = None;
+            let _ = fs::remove_file(log_path);
+        }
+    }
+}
+
+/// Runs recorded for one project, backed by an index file and one log file per run
+/// holding its output lines as JSON
+#[derive(Debug, Clone)]
+pub struct RunHistory {
+    /// `None` when there is nowhere private to keep history, runs are then only kept in
+    /// memory and without their output
+    dir: Option<PathBuf>,
+    project_dir: PathBuf,
+    /// Oldest first
+    pub runs: Vec<RunRecord>,
+}
+
+impl RunHistory {
+    /// An empty history for `project_dir` kept in `dir`, without touching the disk
+    pub fn new(dir: impl Into<PathBuf>, project_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: Some(dir.into()),
+            project_dir: project_dir.into(),
+            runs: Vec::new(),
+        }
+    }
+
+    /// The history of `project_dir` in `default_history_dir`, or in memory without one
+    pub fn in_default_dir(project_dir: impl Into<PathBuf>) -> Self {
+        match default_history_dir() {
+            Some(dir) => Self::new(dir, project_dir),
+            None => Self::disabled(project_dir),
+        }
+    }
+
+    /// A history for `project_dir` that is not kept on disk
+    pub fn disabled(project_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: None,
+            project_dir: project_dir.into(),
+            runs: Vec::new(),
+        }
+    }
+
+    /// Read the runs of `project_dir` from `dir`. A missing index is an empty history,
+    /// lines that fail to parse are skipped
+    pub fn load(dir: impl Into<PathBuf>, project_dir: impl Into<PathBuf>) -> Result<Self> {
+        let mut history = Self::new(dir, project_dir);
+        history.runs = history
+            .read_index()?
+            .into_iter()
+            .filter(|run| run.project_dir == history.project_dir)
+            .collect();
+        Ok(history)
+    }
+
+    pub fn dir(&self) -> Option<&Path> {
+        self.dir.as_deref()
+    }
+
+    /// The history as it is on disk now, runs of other instances included
+    pub fn reload(&self) -> Result<Self> {
+        match &self.dir {
+            Some(dir) => Self::load(dir.clone(), self.project_dir.clone()),
+            None => Ok(self.clone()),
+        }
+    }
+
+    pub fn project_dir(&self) -> &Path {
+        &self.project_dir
+    }
+
+    /// Runs matching `filter`, newest first
+    pub fn filtered(&self, filter: &str) -> Vec<&RunRecord> {
+        self.runs
+            .iter()
+            .rev()
+            .filter(|run| run.matches(filter))
+            .collect()
+    }
+
+    /// Start recording a run, creating its log file. A log that cannot be created is
+    /// reported and the run is still recorded, without output
+    pub fn start_recording(&self, record: RunRecord) -> RunRecording {
+        let log_path = self.log_path(&record.id);
+        let log = log_path.as_ref().and_then(|log_path| {
+            let log = create_private_dir(log_path.parent().unwrap_or(log_path)).and_then(|()| {
+                private_file_options()
+                    .write(true)
+                    .create_new(true)
+                    .open(log_path)
+            });
+            log.inspect_err(|e| log::warn!("Failed to create {}: {e}", log_path.display()))
+                .ok()
+        });
+        RunRecording {
+            record,
+            // Only a log this recording created is its to remove
+            log_path: log_path.filter(|_| log.is_some()),
+            log: log.map(BufWriter::new),
+            log_bytes: 0,
+            max_log_bytes: MAX_RUN_LOG_BYTES,
+            finished: false,
+        }
+    }
+
+    /// Store a finished run, whose log its recording already wrote, dropping the oldest
+    /// runs beyond `MAX_HISTORY_RUNS`
+    pub fn record(&mut self, record: RunRecord) -> Result<()> {
+        let Some(dir) = &self.dir else {
+            self.runs.push(record);
+            let excess = self.runs.len().saturating_sub(MAX_HISTORY_RUNS);
+            self.runs.drain(..excess);
+            return Ok(());
+        };
+        create_private_dir(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+
+        let mut line = serde_json::to_string(&record).context("Failed to serialize run")?;
+        line.push('\n');
+        let index_path = dir.join(INDEX_FILE);
+        private_file_options()
+            .create(true)
+            .append(true)
+            .open(&index_path)
+            .and_then(|mut index| index.write_all(line.as_bytes()))
+            .with_context(|| format!("Failed to write {}", index_path.display()))?;
+
+        self.runs.push(record);
+        self.prune()
+    }
+
+    /// Output lines captured during the run, lines that fail to parse are skipped
+    pub fn read_log(&self, record: &RunRecord) -> Result<Vec<OutputLine>> {
+        let path = self
+            .log_path(&record.id)
+            .context("Run output is not kept without a state directory")?;
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        Ok(content
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect())
+    }
+
+    fn index_path(&self) -> Option<PathBuf> {
+        Some(self.dir.as_ref()?.join(INDEX_FILE))
+    }
+
+    fn log_path(&self, id: &str) -> Option<PathBuf> {
+        Some(self.dir.as_ref()?.join(format!("{id}.log")))
+    }
+
+    /// Every run in the index, of all projects
+    fn read_index(&self) -> Result<Vec<RunRecord>> {
+        let Some(path) = self.index_path().filter(|path| path.exists()) else {
+            return Ok(Vec::new());
+        };
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        Ok(content
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect())
+    }
+
+    /// Rewrite the index without the oldest runs once it grows past `MAX_HISTORY_RUNS`
+    fn prune(&mut self) -> Result<()> {
+        let all_runs = self.read_index()?;
+        if all_runs.len() <= MAX_HISTORY_RUNS {
+            return Ok(());
+        }
+
+        let (dropped, kept) = all_runs.split_at(all_runs.len() - MAX_HISTORY_RUNS);
+        for log_path in dropped.iter().filter_map(|run| self.log_path(&run.id)) {
+            let _ = fs::remove_file(log_path);
+        }
+        let mut content = String::new();
+        for run in kept {
+            content.push_str(&serde_json::to_string(run).context("Failed to serialize run")?);
+            content.push('\n');
+        }
+        if let Some(index_path) = self.index_path() {
+            fs::write(&index_path, content)
+                .with_context(|| format!("Failed to write {}", index_path.display()))?;
+        }
+
+        self.runs
+            .retain(|run| kept.iter().any(|kept_run| kept_run.id == run.id));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::task_run::TaskExit;
+
+    fn temp_history_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "mise-sequencer-history-{}-{name}",
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn finished_task_run(task_name: &str, code: i32) -> RunRecord {
+        let mut record = RunRecord::task(task_name, &[], Path::new("/project"));
+        record.results.push(TaskResult {
+            task_name: task_name.to_string(),
+            exit: TaskExit::Code(code),
+            started_at: record.started_at,
+            finished_at: record.started_at,
+            stdout_lines: 1,
+            stderr_lines: 0,
+            peak_usage: None,
+        });
+        record
+    }
+
+    #[test]
+    fn test_record_and_reload_with_log() {
+        let dir = temp_history_dir("reload");
+        let mut history = RunHistory::new(&dir, "/project");
+
+        let log = vec![OutputLine::stderr("compiling").with_task("build")];
+        let mut recording = history.start_recording(finished_task_run("build", 0));
+        recording.push(&log[0]);
+        let record = recording.finish();
+        history.record(record.clone()).unwrap();
+
+        let reloaded = RunHistory::load(&dir, "/project").unwrap();
+        assert_eq!(reloaded.runs, vec![record.clone()]);
+        assert_eq!(reloaded.read_log(&record).unwrap(), log);
+
+        // Runs of other projects are not shown
+        let other = RunHistory::load(&dir, "/elsewhere").unwrap();
+        assert!(other.runs.is_empty());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_runs_get_distinct_ids() {
+        let first = RunRecord::task("build", &[], Path::new("/project"));
+        let second = RunRecord::task("lint", &[], Path::new("/project"));
+        assert_ne!(first.id, second.id);
+    }
+
+    #[test]
+    fn test_log_stops_at_its_limit() {
+        let dir = temp_history_dir("limit");
+        let history = RunHistory::new(&dir, "/project");
+
+        let mut recording = history.start_recording(finished_task_run("build", 0));
+        recording.max_log_bytes = 200;
+        for i in 0..10 {
+            recording.push(&OutputLine::stdout(format!("line {i}")));
+        }
+        let record = recording.finish();
+
+        let log = history.read_log(&record).unwrap();
+        assert!(log.len() < 10);
+        assert_eq!(log[0].text, "line 0");
+        assert_eq!(
+            log.last().unwrap().text,
+            "Output beyond 200 B not kept in history"
+        );
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_unfinished_recording_leaves_no_log() {
+        let dir = temp_history_dir("unfinished");
+        let history = RunHistory::new(&dir, "/project");
+
+        let mut recording = history.start_recording(finished_task_run("build", 0));
+        recording.push(&OutputLine::stdout("compiling"));
+        let log_path = history.log_path(&recording.record.id).unwrap();
+        assert!(log_path.exists());
+        drop(recording);
+
+        assert!(!log_path.exists());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_history_files_are_private() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = temp_history_dir("private");
+        let mut history = RunHistory::new(&dir, "/project");
+        let recording = history.start_recording(finished_task_run("build", 0));
+        let log_path = history.log_path(&recording.record.id).unwrap();
+        history.record(recording.finish()).unwrap();
+
+        let mode = |path: &Path| fs::metadata(path).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode(&dir), 0o700);
+        assert_eq!(mode(&log_path), 0o600);
+        assert_eq!(mode(&history.index_path().unwrap()), 0o600);
+
+        // A file already at the path of a new log is left alone
+        let mut record = finished_task_run("build", 0);
+        record.id = "taken".to_string();
+        let taken = history.log_path("taken").unwrap();
+        fs::write(&taken, "someone else's").unwrap();
+        drop(history.start_recording(record));
+        assert_eq!(fs::read_to_string(&taken).unwrap(), "someone else's");
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_disabled_history_keeps_runs_in_memory() {
+        let mut history = RunHistory::disabled("/project");
+        let mut recording = history.start_recording(finished_task_run("build", 0));
+        recording.push(&OutputLine::stdout("compiling"));
+        let record = recording.finish();
+        history.record(record.clone()).unwrap();
+
+        assert_eq!(history.dir(), None);
+        assert_eq!(history.reload().unwrap().runs, vec![record.clone()]);
+        assert!(history.read_log(&record).is_err());
+    }
+
+    #[test]
+    fn test_load_missing_history_is_empty() {
+        let history = RunHistory::load(temp_history_dir("missing"), "/project").unwrap();
+        assert!(history.runs.is_empty());
+    }
+
+    #[test]
+    fn test_filtered_is_newest_first_and_matches_status() {
+        let mut history = RunHistory::new("/unused", "/project");
+        history.runs = vec![finished_task_run("build", 0), finished_task_run("test", 1)];
+
+        let names = |runs: Vec<&RunRecord>| -> Vec<String> {
+            runs.iter().map(|run| run.name.clone()).collect()
+        };
+        assert_eq!(names(history.filtered("")), vec!["test", "build"]);
+        assert_eq!(names(history.filtered("FAILED")), vec!["test"]);
+        assert_eq!(names(history.filtered("run bui")), vec!["build"]);
+    }
+
+    #[test]
+    fn test_sequence_run_keeps_step_layout() {
+        let sequence = SavedSequence {
+            name: String::new(),
+            steps: vec![SavedStep {
+                tasks: vec!["build".to_string(), "lint".to_string()],
+                on_failure: Default::default(),
+                args: Default::default(),
+            }],
+        };
+        let record = RunRecord::sequence(sequence, "build + lint", Path::new("/project"));
+
+        assert_eq!(record.kind, RunKind::Sequence);
+        assert_eq!(record.name, "sequence");
+        assert_eq!(record.to_sequence().steps[0].tasks, vec!["build", "lint"]);
+        // Nothing failed in a run without results
+        assert!(record.success());
+    }
+}
@@ -0,0 +1,357 @@
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::sequence::{SequenceState, StepFailurePolicy};
+
+/// Project-local file that named sequences are saved to, meant to be committed
+pub const SEQUENCES_FILE: &str = ".mise-sequences.toml";
+
+/// One step of a saved sequence: the tasks it runs in parallel and its failure policy
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedStep {
+    #[serde(default)]
+    pub tasks: Vec<String>,
+    /// `JumpTo` targets are zero-based step indexes
+    #[serde(default)]
+    pub on_failure: StepFailurePolicy,
+    /// Arguments of the step's tasks that take any, by task name
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub args: BTreeMap<String, Vec<String>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedSequence {
+    pub name: String,
+    #[serde(default)]
+    pub steps: Vec<SavedStep>,
+}
+
+impl SavedSequence {
+    /// Snapshot the step assignments and policies of `state` under `name`
+    pub fn from_state(name: &str, state: &SequenceState) -> Self {
+        let steps = (0..state.num_steps)
+            .map(|step| SavedStep {
+                tasks: state.get_tasks_for_step(step),
+                on_failure: state.step_policy(step),
+                args: state.step_args[step]
+                    .iter()
+                    .map(|(task_name, args)| (task_name.clone(), args.clone()))
+                    .collect(),
+            })
+            .collect();
+
+        Self {
+            name: name.to_string(),
+            steps,
+        }
+    }
+
+    /// Rebuild a sequence from the saved steps, dropping cleanup jumps that point nowhere
+    pub fn to_state(&self) -> SequenceState {
+        let mut state = SequenceState::new(self.steps.len().max(1));
+
+        for (step, saved_step) in self.steps.iter().enumerate() {
+            for task_name in &saved_step.tasks {
+                state.set_task_step(task_name, step, true);
+                if let Some(args) = saved_step.args.get(task_name) {
+                    state.set_task_args(task_name, step, args.clone());
+                }
+            }
+
+            state.step_policies[step] = match saved_step.on_failure {
+                StepFailurePolicy::JumpTo(target)
+                    if target <= step || target >= state.num_steps =>
+                {
+                    StepFailurePolicy::Stop
+                }
+                policy => policy,
+            };
+        }
+
+        state
+    }
+
+    /// Every task the sequence refers to, in step order
+    pub fn task_names(&self) -> Vec<&str> {
+        self.steps
+            .iter()
+            .flat_map(|step| step.tasks.iter().map(String::as_str))
+            .collect()
+    }
+}
+
+/// Layout of the sequences file: one `[[sequence]]` table per saved sequence
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SequencesFile {
+    #[serde(default, rename = "sequence")]
+    sequences: Vec<SavedSequence>,
+}
+
+/// Named sequences backed by a TOML file. Every change is written straight back to disk
+#[derive(Debug, Clone)]
+pub struct SequenceStore {
+    path: PathBuf,
+    pub sequences: Vec<SavedSequence>,
+}
+
+impl SequenceStore {
+    /// An empty store for `path`, without touching the file
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            sequences: Vec::new(),
+        }
+    }
+
+    /// Read the store from `path`. A missing file is an empty store
+    pub fn load(path: impl Into<PathBuf>) -> Result<Self> {
+        let mut store = Self::new(path);
+        if !store.path.exists() {
+            return Ok(store);
+        }
+
+        let content = fs::read_to_string(&store.path)
+            .with_context(|| format!("Failed to read {}", store.path.display()))?;
+        let file: SequencesFile = toml::from_str(&content)
+            .with_context(|| format!("Failed to parse {}", store.path.display()))?;
+        store.sequences = file.sequences;
+        Ok(store)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn get(&self, name: &str) -> Option<&SavedSequence> {
+        self.sequences.iter().find(|sequence| sequence.name == name)
+    }
+
+    /// Save a sequence, replacing any existing one with the same name
+    pub fn upsert(&mut self, sequence: SavedSequence) -> Result<()> {
+        match self
+            .sequences
+            .iter_mut()
+            .find(|existing| existing.name == sequence.name)
+        {
+            Some(existing) => *existing = sequence,
+            None => self.sequences.push(sequence),
+        }
+        self.save()
+    }
+
+    pub fn rename(&mut self, old_name: &str, new_name: &str) -> Result<()> {
+        if old_name == new_name {
+            return Ok(());
+        }
+        if self.get(new_name).is_some() {
+            bail!("A sequence named '{new_name}' already exists");
+        }
+
+        let Some(sequence) = self
+            .sequences
+            .iter_mut()
+            .find(|sequence| sequence.name == old_name)
+        else {
+            bail!("No sequence named '{old_name}'");
+        };
+        sequence.name = new_name.to_string();
+        self.save()
+    }
+
+    pub fn delete(&mut self, name: &str) -> Result<()> {
+        let count = self.sequences.len();
+        self.sequences.retain(|sequence| sequence.name != name);
+        if self.sequences.len() == count {
+            bail!("No sequence named '{name}'");
+        }
+        self.save()
+    }
+
+    fn save(&self) -> Result<()> {
+        let file = SequencesFile {
+            sequences: self.sequences.clone(),
+        };
+        let content = toml::to_string(&file).context("Failed to serialize sequences")?;
+        fs::write(&self.path, content)
+            .with_context(|| format!("Failed to write {}", self.path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_store_path(name: &str) -> PathBuf {
+        let path =
+            std::env::temp_dir().join(format!("mise-sequences-{}-{name}.toml", std::process::id()));
+        let _ = fs::remove_file(&path);
+        path
+    }
+
+    fn sample_state() -> SequenceState {
+        let mut state = SequenceState::new(3);
+        state.set_task_step("build", 0, true);
+        state.set_task_step("lint", 0, true);
+        state.set_task_step("deploy", 1, true);
+        state.set_task_step("cleanup", 2, true);
+        state.step_policies[0] = StepFailurePolicy::Retry(2);
+        state.step_policies[1] = StepFailurePolicy::JumpTo(2);
+        state.set_task_args("deploy", 1, vec!["--env".to_string(), "prod".to_string()]);
+        state
+    }
+
+    #[test]
+    fn test_from_state_captures_steps_and_policies() {
+        let saved = SavedSequence::from_state("release", &sample_state());
+
+        assert_eq!(saved.name, "release");
+        assert_eq!(saved.steps.len(), 3);
+        assert_eq!(saved.steps[0].tasks, vec!["build", "lint"]);
+        assert_eq!(saved.steps[0].on_failure, StepFailurePolicy::Retry(2));
+        assert_eq!(saved.steps[1].on_failure, StepFailurePolicy::JumpTo(2));
+        assert_eq!(saved.steps[2].on_failure, StepFailurePolicy::Stop);
+        assert_eq!(
+            saved.task_names(),
+            vec!["build", "lint", "deploy", "cleanup"]
+        );
+    }
+
+    #[test]
+    fn test_state_round_trip() {
+        let state = sample_state();
+        let restored = SavedSequence::from_state("release", &state).to_state();
+
+        assert_eq!(restored.num_steps, 3);
+        for step in 0..3 {
+            assert_eq!(
+                restored.get_tasks_for_step(step),
+                state.get_tasks_for_step(step)
+            );
+            assert_eq!(restored.step_policy(step), state.step_policy(step));
+        }
+        assert_eq!(restored.task_args("deploy", 1), ["--env", "prod"]);
+        assert!(!restored.is_running);
+    }
+
+    #[test]
+    fn test_to_state_drops_invalid_jump_targets() {
+        let saved = SavedSequence {
+            name: "broken".to_string(),
+            steps: vec![
+                SavedStep {
+                    tasks: vec![],
+                    on_failure: StepFailurePolicy::JumpTo(5),
+                    args: BTreeMap::new(),
+                },
+                SavedStep {
+                    tasks: vec![],
+                    on_failure: StepFailurePolicy::JumpTo(0),
+                    args: BTreeMap::new(),
+                },
+            ],
+        };
+
+        let state = saved.to_state();
+        assert_eq!(state.step_policy(0), StepFailurePolicy::Stop);
+        assert_eq!(state.step_policy(1), StepFailurePolicy::Stop);
+    }
+
+    #[test]
+    fn test_to_state_without_steps_keeps_one_step() {
+        let saved = SavedSequence {
+            name: "empty".to_string(),
+            steps: vec![],
+        };
+
+        assert_eq!(saved.to_state().num_steps, 1);
+    }
+
+    #[test]
+    fn test_parse_sequences_file() {
+        let content = r#"
+[[sequence]]
+name = "ci"
+
+[[sequence.steps]]
+tasks = ["build", "lint"]
+on_failure = "continue"
+
+[[sequence.steps]]
+tasks = ["test"]
+on_failure = { retry = 2 }
+args = { test = ["--watch=false"] }
+
+[[sequence.steps]]
+"#;
+        let file: SequencesFile = toml::from_str(content).unwrap();
+
+        assert_eq!(file.sequences.len(), 1);
+        let steps = &file.sequences[0].steps;
+        assert_eq!(steps.len(), 3);
+        assert_eq!(steps[0].on_failure, StepFailurePolicy::Continue);
+        assert_eq!(steps[1].on_failure, StepFailurePolicy::Retry(2));
+        assert_eq!(steps[1].args["test"], vec!["--watch=false"]);
+        assert!(steps[0].args.is_empty());
+        assert!(steps[2].tasks.is_empty());
+        assert_eq!(steps[2].on_failure, StepFailurePolicy::Stop);
+    }
+
+    #[test]
+    fn test_load_missing_file_is_empty() {
+        let store = SequenceStore::load(temp_store_path("missing")).unwrap();
+        assert!(store.sequences.is_empty());
+    }
+
+    #[test]
+    fn test_load_invalid_file_fails() {
+        let path = temp_store_path("invalid");
+        fs::write(&path, "[[sequence]]\nsteps = 3\n").unwrap();
+
+        assert!(SequenceStore::load(&path).is_err());
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_upsert_persists_and_replaces() {
+        let path = temp_store_path("upsert");
+        let mut store = SequenceStore::new(&path);
+
+        store
+            .upsert(SavedSequence::from_state("release", &sample_state()))
+            .unwrap();
+        store
+            .upsert(SavedSequence::from_state("release", &SequenceState::new(1)))
+            .unwrap();
+
+        let reloaded = SequenceStore::load(&path).unwrap();
+        assert_eq!(reloaded.sequences.len(), 1);
+        assert_eq!(reloaded.get("release").unwrap().steps.len(), 1);
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_rename_and_delete() {
+        let path = temp_store_path("rename");
+        let mut store = SequenceStore::new(&path);
+        store
+            .upsert(SavedSequence::from_state("a", &sample_state()))
+            .unwrap();
+        store
+            .upsert(SavedSequence::from_state("b", &sample_state()))
+            .unwrap();
+
+        assert!(store.rename("a", "b").is_err());
+        assert!(store.rename("missing", "c").is_err());
+        store.rename("a", "c").unwrap();
+        store.delete("b").unwrap();
+        assert!(store.delete("b").is_err());
+
+        let reloaded = SequenceStore::load(&path).unwrap();
+        let names: Vec<&str> = reloaded.sequences.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["c"]);
+        let _ = fs::remove_file(&path);
+    }
+}
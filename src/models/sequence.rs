@@ -1,5 +1,64 @@
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+use super::task_args::task_command_line;
+use super::task_run::TaskResult;
+
+/// Number of step columns a fresh sequence starts with
+pub const DEFAULT_NUM_STEPS: usize = 3;
+
+/// What to do when a task in a step exits with a failure
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StepFailurePolicy {
+    /// Stop the whole sequence
+    #[default]
+    Stop,
+    /// Mark the step as failed and carry on with the next step
+    Continue,
+    /// Re-run the step up to this many extra times before stopping
+    Retry(u32),
+    /// Jump to a cleanup step, run it, then stop the sequence as failed
+    JumpTo(usize),
+}
+
+/// Most retries offered when cycling through policies
+const MAX_STEP_RETRIES: u32 = 3;
+
+impl StepFailurePolicy {
+    /// Compact label shown in the step column header
+    pub fn short_label(&self) -> String {
+        match self {
+            Self::Stop => "stop".to_string(),
+            Self::Continue => "cont".to_string(),
+            Self::Retry(times) => format!("↻{times}"),
+            Self::JumpTo(step) => format!("→{}", step + 1),
+        }
+    }
+
+    /// Next policy in the cycle Stop → Continue → Retry 1..3 → JumpTo later steps → Stop
+    pub fn next(&self, step: usize, num_steps: usize) -> Self {
+        let first_jump_target = step + 1;
+        match *self {
+            Self::Stop => Self::Continue,
+            Self::Continue => Self::Retry(1),
+            Self::Retry(times) if times < MAX_STEP_RETRIES => Self::Retry(times + 1),
+            Self::Retry(_) if first_jump_target < num_steps => Self::JumpTo(first_jump_target),
+            Self::JumpTo(target) if target + 1 < num_steps => Self::JumpTo(target + 1),
+            _ => Self::Stop,
+        }
+    }
+}
+
+/// How a running sequence reacts to a failed step, decided by the step's policy
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StepFailureAction {
+    Retry { attempt: u32, max_attempts: u32 },
+    Continue,
+    Cleanup(usize),
+    Abort,
+}
+
 #[derive(Debug, Clone)]
 pub struct SequenceState {
     pub task_steps: HashMap<String, Vec<bool>>,
@@ -7,6 +66,23 @@ pub struct SequenceState {
     pub current_step: Option<usize>,
     pub is_running: bool,
     pub completed_steps: Vec<bool>,
+    pub step_policies: Vec<StepFailurePolicy>,
+    /// Arguments saved for tasks of each step, passed whenever the sequence runs them
+    pub step_args: Vec<HashMap<String, Vec<String>>>,
+    pub failed_steps: Vec<bool>,
+    pub step_attempts: Vec<u32>,
+    /// Failure that sent the sequence to a cleanup step, reported once cleanup finishes
+    pub pending_failure: Option<String>,
+    /// Task results of each step's latest attempt, kept after the run for the matrix badges
+    pub step_results: Vec<Vec<TaskResult>>,
+    /// Skip steps whose tasks all have their outputs up to date with their sources
+    pub only_stale: bool,
+}
+
+impl Default for SequenceState {
+    fn default() -> Self {
+        Self::new(DEFAULT_NUM_STEPS)
+    }
 }
 
 impl SequenceState {
@@ -17,20 +93,30 @@ impl SequenceState {
             current_step: None,
             is_running: false,
             completed_steps: vec![false; num_steps],
+            step_policies: vec![StepFailurePolicy::default(); num_steps],
+            step_args: vec![HashMap::new(); num_steps],
+            failed_steps: vec![false; num_steps],
+            step_attempts: vec![0; num_steps],
+            pending_failure: None,
+            step_results: vec![Vec::new(); num_steps],
+            only_stale: false,
         }
     }
 
-    pub fn set_task_step(&mut self, task_name: &str, step: usize, enabled: bool) {
-        if step < self.num_steps {
-            if enabled {
-                // If enabling this task for this step, clear any existing task for this step
-                for (_, other_steps) in self.task_steps.iter_mut() {
-                    if step < other_steps.len() {
-                        other_steps[step] = false;
-                    }
-                }
+    /// A sequence that runs `steps` in order, with the tasks of each step in parallel
+    pub fn from_steps(steps: &[Vec<String>]) -> Self {
+        let mut state = Self::new(steps.len().max(1));
+        for (step, tasks) in steps.iter().enumerate() {
+            for task_name in tasks {
+                state.set_task_step(task_name, step, true);
             }
+        }
+        state
+    }
 
+    /// Enable or disable a task for a step. A step can hold several tasks, which run in parallel
+    pub fn set_task_step(&mut self, task_name: &str, step: usize, enabled: bool) {
+        if step < self.num_steps {
             let steps = self
                 .task_steps
                 .entry(task_name.to_string())
@@ -38,6 +124,142 @@ impl SequenceState {
             if step < steps.len() {
                 steps[step] = enabled;
             }
+            if !enabled && let Some(step_args) = self.step_args.get_mut(step) {
+                step_args.remove(task_name);
+            }
+        }
+    }
+
+    /// Save the arguments a task gets when it runs in `step`, none clears them
+    pub fn set_task_args(&mut self, task_name: &str, step: usize, args: Vec<String>) {
+        let Some(step_args) = self.step_args.get_mut(step) else {
+            return;
+        };
+        if args.is_empty() {
+            step_args.remove(task_name);
+        } else {
+            step_args.insert(task_name.to_string(), args);
+        }
+    }
+
+    /// Arguments the task gets when it runs in `step`
+    pub fn task_args(&self, task_name: &str, step: usize) -> &[String] {
+        self.step_args
+            .get(step)
+            .and_then(|step_args| step_args.get(task_name))
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// Append an empty step column at the end of the sequence
+    pub fn add_step(&mut self) {
+        self.insert_step(self.num_steps);
+    }
+
+    /// Insert an empty step column before `index` (or at the end if `index == num_steps`)
+    pub fn insert_step(&mut self, index: usize) {
+        if self.is_running || index > self.num_steps {
+            return;
+        }
+
+        for steps in self.task_steps.values_mut() {
+            steps.resize(self.num_steps, false);
+            steps.insert(index, false);
+        }
+
+        // Cleanup targets at or after the new step move one step to the right
+        for policy in self.step_policies.iter_mut() {
+            if let StepFailurePolicy::JumpTo(target) = policy
+                && *target >= index
+            {
+                *target += 1;
+            }
+        }
+        self.step_policies
+            .insert(index, StepFailurePolicy::default());
+        self.step_args.insert(index, HashMap::new());
+
+        self.num_steps += 1;
+        self.reset_step_progress();
+    }
+
+    /// Remove the step column at `index`, keeping at least one step
+    pub fn remove_step(&mut self, index: usize) -> bool {
+        if self.is_running || index >= self.num_steps || self.num_steps <= 1 {
+            return false;
+        }
+
+        for steps in self.task_steps.values_mut() {
+            steps.resize(self.num_steps, false);
+            steps.remove(index);
+        }
+
+        self.step_policies.remove(index);
+        self.step_args.remove(index);
+        for policy in self.step_policies.iter_mut() {
+            if let StepFailurePolicy::JumpTo(target) = *policy {
+                if target == index {
+                    // The cleanup step is gone, fall back to stopping
+                    *policy = StepFailurePolicy::Stop;
+                } else if target > index {
+                    *policy = StepFailurePolicy::JumpTo(target - 1);
+                }
+            }
+        }
+
+        self.num_steps -= 1;
+        self.reset_step_progress();
+        true
+    }
+
+    pub fn step_policy(&self, step: usize) -> StepFailurePolicy {
+        self.step_policies.get(step).copied().unwrap_or_default()
+    }
+
+    /// Move the step's failure policy to the next option in the cycle
+    pub fn cycle_step_policy(&mut self, step: usize) {
+        if step < self.num_steps && !self.is_running {
+            self.step_policies[step] = self.step_policies[step].next(step, self.num_steps);
+        }
+    }
+
+    /// Apply the current step's failure policy and report what the sequence should do next
+    pub fn record_step_failure(&mut self, error: &str) -> StepFailureAction {
+        let Some(current) = self.current_step else {
+            return StepFailureAction::Abort;
+        };
+        self.failed_steps[current] = true;
+
+        match self.step_policies[current] {
+            StepFailurePolicy::Stop => StepFailureAction::Abort,
+            StepFailurePolicy::Continue => StepFailureAction::Continue,
+            StepFailurePolicy::Retry(max_attempts)
+                if self.step_attempts[current] < max_attempts =>
+            {
+                self.step_attempts[current] += 1;
+                StepFailureAction::Retry {
+                    attempt: self.step_attempts[current],
+                    max_attempts,
+                }
+            }
+            StepFailurePolicy::Retry(_) => StepFailureAction::Abort,
+            // Only jump once, a failing cleanup step must not loop
+            StepFailurePolicy::JumpTo(target)
+                if target < self.num_steps
+                    && target != current
+                    && self.pending_failure.is_none() =>
+            {
+                self.pending_failure = Some(error.to_string());
+                self.current_step = Some(target);
+                StepFailureAction::Cleanup(target)
+            }
+            StepFailurePolicy::JumpTo(_) => StepFailureAction::Abort,
+        }
+    }
+
+    /// Clear the current step's failure once an attempt succeeds, as after a retry
+    pub fn record_step_success(&mut self) {
+        if let Some(current) = self.current_step {
+            self.failed_steps[current] = false;
         }
     }
 
@@ -48,8 +270,10 @@ impl SequenceState {
             .unwrap_or(false)
     }
 
+    /// Tasks enabled for a step, sorted by name so the order is stable
     pub fn get_tasks_for_step(&self, step: usize) -> Vec<String> {
-        self.task_steps
+        let mut tasks: Vec<String> = self
+            .task_steps
             .iter()
             .filter_map(|(task_name, steps)| {
                 if step < steps.len() && steps[step] {
@@ -58,30 +282,69 @@ impl SequenceState {
                     None
                 }
             })
-            .collect()
+            .collect();
+        tasks.sort();
+        tasks
     }
 
     pub fn clear_all(&mut self) {
         for steps in self.task_steps.values_mut() {
             steps.fill(false);
         }
+        self.step_policies.fill(StepFailurePolicy::default());
+        self.step_args.iter_mut().for_each(HashMap::clear);
         self.reset_execution();
     }
 
     pub fn remove_task(&mut self, task_name: &str) {
         self.task_steps.remove(task_name);
+        for step_args in &mut self.step_args {
+            step_args.remove(task_name);
+        }
     }
 
     pub fn reset_execution(&mut self) {
         self.current_step = None;
         self.is_running = false;
         self.completed_steps.fill(false);
+        self.pending_failure = None;
     }
 
     pub fn start_execution(&mut self) {
         self.current_step = Some(0);
         self.is_running = true;
-        self.completed_steps.fill(false);
+        self.reset_step_progress();
+    }
+
+    fn reset_step_progress(&mut self) {
+        self.completed_steps = vec![false; self.num_steps];
+        self.failed_steps = vec![false; self.num_steps];
+        self.step_attempts = vec![0; self.num_steps];
+        self.pending_failure = None;
+        self.step_results = vec![Vec::new(); self.num_steps];
+    }
+
+    /// Store the results of the current step, replacing those of an earlier attempt
+    pub fn record_step_results(&mut self, results: Vec<TaskResult>) {
+        if let Some(current) = self.current_step {
+            self.step_results[current] = results;
+        }
+    }
+
+    /// Whether every task of the step succeeded in its latest run, `None` if it has not run
+    pub fn step_outcome(&self, step: usize) -> Option<bool> {
+        match self.step_results.get(step) {
+            Some(results) if !results.is_empty() => Some(results.iter().all(TaskResult::success)),
+            _ => None,
+        }
+    }
+
+    /// The task's result in the latest run of the step
+    pub fn task_result(&self, task_name: &str, step: usize) -> Option<&TaskResult> {
+        self.step_results
+            .get(step)?
+            .iter()
+            .find(|result| result.task_name == task_name)
     }
 
     pub fn advance_step(&mut self) -> bool {
@@ -100,24 +363,72 @@ impl SequenceState {
         }
     }
 
-    pub fn generate_mise_task_command(&self) -> Option<String> {
-        let mut commands = Vec::new();
+    /// Steps that have at least one task, as the sorted task names of each step
+    fn non_empty_steps(&self) -> Vec<Vec<String>> {
+        (0..self.num_steps)
+            .map(|step| self.get_tasks_for_step(step))
+            .filter(|tasks| !tasks.is_empty())
+            .collect()
+    }
 
-        for step in 0..self.num_steps {
-            let tasks_for_step = self.get_tasks_for_step(step);
-            if !tasks_for_step.is_empty() {
-                // Should only be one task per step based on current logic
-                if let Some(task_name) = tasks_for_step.first() {
-                    commands.push(format!("mise run {task_name}"));
-                }
-            }
+    /// Like `non_empty_steps`, with each task followed by its saved arguments
+    fn non_empty_step_invocations(&self) -> Vec<Vec<String>> {
+        (0..self.num_steps)
+            .map(|step| {
+                self.get_tasks_for_step(step)
+                    .iter()
+                    .map(|task_name| task_command_line(task_name, self.task_args(task_name, step)))
+                    .collect::<Vec<_>>()
+            })
+            .filter(|tasks| !tasks.is_empty())
+            .collect()
+    }
+
+    /// One-line summary of the steps, e.g. `build + lint → test`
+    pub fn summary(&self) -> String {
+        self.non_empty_steps()
+            .iter()
+            .map(|tasks| tasks.join(" + "))
+            .collect::<Vec<_>>()
+            .join(" → ")
+    }
+
+    /// Build the body of a `[tasks.<name>]` table that runs this sequence through mise.
+    ///
+    /// A single step becomes a task that only `depends` on the step's tasks, which mise
+    /// runs in parallel. Longer sequences get one `run` entry per step: `{ task = "..." }`
+    /// for a lone task and `{ tasks = [...] }` for tasks that run in parallel. Saved
+    /// arguments follow the task name, e.g. `{ task = "deploy --env prod" }`.
+    pub fn to_mise_task(&self, description: &str) -> Option<toml::Table> {
+        let steps = self.non_empty_step_invocations();
+        if steps.is_empty() {
+            return None;
         }
 
-        if commands.is_empty() {
-            None
+        let task_list =
+            |tasks: &[String]| toml::Value::Array(tasks.iter().cloned().map(Into::into).collect());
+
+        let mut task = toml::Table::new();
+        task.insert("description".to_string(), description.into());
+
+        if let [tasks] = steps.as_slice() {
+            task.insert("depends".to_string(), task_list(tasks));
         } else {
-            Some(commands.join(" && "))
+            let run = steps
+                .iter()
+                .map(|tasks| {
+                    let mut entry = toml::Table::new();
+                    match tasks.as_slice() {
+                        [single] => entry.insert("task".to_string(), single.as_str().into()),
+                        _ => entry.insert("tasks".to_string(), task_list(tasks)),
+                    };
+                    toml::Value::Table(entry)
+                })
+                .collect();
+            task.insert("run".to_string(), toml::Value::Array(run));
         }
+
+        Some(task)
     }
 }
 
@@ -127,7 +438,18 @@ pub enum SequenceEvent {
     RunSequence,
     AddAsTask,
     ClearSequence,
-    StepCompleted,
+    AddStep,
+    InsertStep(usize),
+    RemoveStep(usize),
+    CycleStepPolicy(usize),
+    ToggleOnlyStale,
+    /// Whether the tasks of `step` were all fresh when it was about to run
+    StepFreshnessChecked {
+        step: usize,
+        fresh: bool,
+    },
+    StepCompleted(Vec<TaskResult>),
+    StepFailed(Vec<TaskResult>),
     SequenceCompleted,
     SequenceFailed(String),
 }
@@ -146,6 +468,20 @@ mod tests {
         assert_eq!(seq.completed_steps, vec![false, false, false]);
     }
 
+    #[test]
+    fn test_from_steps() {
+        let steps = vec![
+            vec!["build".to_string(), "lint".to_string()],
+            vec!["test".to_string()],
+        ];
+        let seq = SequenceState::from_steps(&steps);
+
+        assert_eq!(seq.num_steps, 2);
+        assert_eq!(seq.get_tasks_for_step(0), steps[0]);
+        assert_eq!(seq.get_tasks_for_step(1), steps[1]);
+        assert_eq!(SequenceState::from_steps(&[]).num_steps, 1);
+    }
+
     #[test]
     fn test_set_and_get_task_step() {
         let mut seq = SequenceState::new(3);
@@ -180,13 +516,12 @@ mod tests {
     fn test_get_tasks_for_step() {
         let mut seq = SequenceState::new(3);
 
-        seq.set_task_step("build", 0, true);
-        seq.set_task_step("test", 0, true); // This should replace "build" for step 0
+        seq.set_task_step("test", 0, true);
+        seq.set_task_step("build", 0, true); // Both tasks share step 0
         seq.set_task_step("deploy", 1, true);
 
         let step0_tasks = seq.get_tasks_for_step(0);
-        assert_eq!(step0_tasks.len(), 1); // Only one task allowed per step
-        assert!(step0_tasks.contains(&"test".to_string())); // "test" should replace "build"
+        assert_eq!(step0_tasks, vec!["build".to_string(), "test".to_string()]); // Sorted by name
 
         let step1_tasks = seq.get_tasks_for_step(1);
         assert_eq!(step1_tasks.len(), 1);
@@ -197,26 +532,27 @@ mod tests {
     }
 
     #[test]
-    fn test_one_task_per_step() {
+    fn test_multiple_tasks_per_step() {
         let mut seq = SequenceState::new(3);
 
         // Enable first task for step 0
         seq.set_task_step("build", 0, true);
         assert!(seq.is_task_enabled_for_step("build", 0));
 
-        // Enable second task for step 0 - should replace first task
+        // Enable second task for step 0 - both run in parallel
         seq.set_task_step("test", 0, true);
-        assert!(!seq.is_task_enabled_for_step("build", 0)); // build should be disabled
-        assert!(seq.is_task_enabled_for_step("test", 0)); // test should be enabled
+        assert!(seq.is_task_enabled_for_step("build", 0));
+        assert!(seq.is_task_enabled_for_step("test", 0));
 
-        // Enable same task again - should toggle it off
+        // Disabling one task leaves the other in place
         seq.set_task_step("test", 0, false);
         assert!(!seq.is_task_enabled_for_step("test", 0));
+        assert!(seq.is_task_enabled_for_step("build", 0));
 
         // Enable different task in different step - should not affect other steps
         seq.set_task_step("deploy", 1, true);
         assert!(seq.is_task_enabled_for_step("deploy", 1));
-        assert!(!seq.is_task_enabled_for_step("test", 0));
+        assert!(seq.is_task_enabled_for_step("build", 0));
     }
 
     #[test]
@@ -261,6 +597,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::bool_assert_comparison)]
     fn test_advance_step() {
         let mut seq = SequenceState::new(3);
         seq.start_execution();
@@ -298,52 +635,432 @@ mod tests {
         assert!(!seq.is_running);
     }
 
+    fn run_entries(task: &toml::Table) -> Vec<String> {
+        task["run"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|entry| entry.to_string())
+            .collect()
+    }
+
     #[test]
-    fn test_generate_mise_task_command_with_tasks() {
+    fn test_to_mise_task_runs_steps_in_order() {
         let mut seq = SequenceState::new(3);
 
         seq.set_task_step("build", 0, true);
         seq.set_task_step("test", 1, true);
         seq.set_task_step("deploy", 2, true);
 
-        let command = seq.generate_mise_task_command();
+        let task = seq.to_mise_task("Release").unwrap();
+        assert_eq!(task["description"].as_str(), Some("Release"));
+        assert!(task.get("depends").is_none());
         assert_eq!(
-            command,
-            Some("mise run build && mise run test && mise run deploy".to_string())
+            run_entries(&task),
+            vec![
+                r#"{ task = "build" }"#,
+                r#"{ task = "test" }"#,
+                r#"{ task = "deploy" }"#
+            ]
         );
     }
 
     #[test]
-    fn test_generate_mise_task_command_with_gaps() {
+    fn test_to_mise_task_skips_empty_steps() {
         let mut seq = SequenceState::new(3);
 
         seq.set_task_step("build", 0, true);
         // Skip step 1
         seq.set_task_step("deploy", 2, true);
 
-        let command = seq.generate_mise_task_command();
+        let task = seq.to_mise_task("").unwrap();
         assert_eq!(
-            command,
-            Some("mise run build && mise run deploy".to_string())
+            run_entries(&task),
+            vec![r#"{ task = "build" }"#, r#"{ task = "deploy" }"#]
         );
     }
 
     #[test]
-    fn test_generate_mise_task_command_empty() {
-        let seq = SequenceState::new(3);
+    fn test_to_mise_task_parallel_step() {
+        let mut seq = SequenceState::new(3);
+
+        seq.set_task_step("lint", 0, true);
+        seq.set_task_step("build", 0, true);
+        seq.set_task_step("deploy", 1, true);
 
-        let command = seq.generate_mise_task_command();
-        assert_eq!(command, None);
+        let task = seq.to_mise_task("").unwrap();
+        assert_eq!(
+            run_entries(&task),
+            vec![r#"{ tasks = ["build", "lint"] }"#, r#"{ task = "deploy" }"#]
+        );
     }
 
     #[test]
-    fn test_generate_mise_task_command_single_task() {
+    fn test_to_mise_task_single_step_uses_depends() {
         let mut seq = SequenceState::new(3);
 
+        seq.set_task_step("lint", 1, true);
         seq.set_task_step("build", 1, true);
 
-        let command = seq.generate_mise_task_command();
-        assert_eq!(command, Some("mise run build".to_string()));
+        let task = seq.to_mise_task("").unwrap();
+        assert!(task.get("run").is_none());
+        assert_eq!(task["depends"].to_string(), r#"["build", "lint"]"#);
+    }
+
+    #[test]
+    fn test_to_mise_task_empty() {
+        let seq = SequenceState::new(3);
+
+        assert_eq!(seq.to_mise_task(""), None);
+    }
+
+    #[test]
+    fn test_to_mise_task_passes_saved_args() {
+        let mut seq = SequenceState::new(2);
+
+        seq.set_task_step("build", 0, true);
+        seq.set_task_step("deploy", 1, true);
+        seq.set_task_args("deploy", 1, vec!["--env".to_string(), "prod".to_string()]);
+
+        let task = seq.to_mise_task("").unwrap();
+        assert_eq!(
+            run_entries(&task),
+            vec![r#"{ task = "build" }"#, r#"{ task = "deploy --env prod" }"#]
+        );
+    }
+
+    #[test]
+    fn test_task_args_follow_their_step() {
+        let mut seq = SequenceState::new(3);
+        seq.set_task_step("deploy", 1, true);
+        seq.set_task_args("deploy", 1, vec!["prod".to_string()]);
+
+        seq.insert_step(0);
+        assert!(seq.task_args("deploy", 1).is_empty());
+        assert_eq!(seq.task_args("deploy", 2), ["prod"]);
+
+        seq.remove_step(1);
+        assert_eq!(seq.task_args("deploy", 1), ["prod"]);
+
+        // Taking the task out of the step forgets its arguments
+        seq.set_task_step("deploy", 1, false);
+        seq.set_task_step("deploy", 1, true);
+        assert!(seq.task_args("deploy", 1).is_empty());
+    }
+
+    #[test]
+    fn test_summary() {
+        let mut seq = SequenceState::new(3);
+        seq.set_task_step("lint", 0, true);
+        seq.set_task_step("build", 0, true);
+        seq.set_task_step("test", 2, true);
+
+        assert_eq!(seq.summary(), "build + lint → test");
+        assert_eq!(SequenceState::new(2).summary(), "");
+    }
+
+    #[test]
+    fn test_add_step() {
+        let mut seq = SequenceState::new(3);
+        seq.set_task_step("build", 2, true);
+
+        seq.add_step();
+
+        assert_eq!(seq.num_steps, 4);
+        assert_eq!(seq.completed_steps.len(), 4);
+        assert!(seq.is_task_enabled_for_step("build", 2));
+        assert!(!seq.is_task_enabled_for_step("build", 3));
+
+        // The new step is usable straight away
+        seq.set_task_step("deploy", 3, true);
+        assert!(seq.is_task_enabled_for_step("deploy", 3));
+    }
+
+    #[test]
+    fn test_insert_step_shifts_later_steps() {
+        let mut seq = SequenceState::new(3);
+        seq.set_task_step("build", 0, true);
+        seq.set_task_step("test", 1, true);
+        seq.set_task_step("deploy", 2, true);
+
+        seq.insert_step(1);
+
+        assert_eq!(seq.num_steps, 4);
+        assert!(seq.is_task_enabled_for_step("build", 0));
+        assert!(seq.get_tasks_for_step(1).is_empty());
+        assert!(seq.is_task_enabled_for_step("test", 2));
+        assert!(seq.is_task_enabled_for_step("deploy", 3));
+    }
+
+    #[test]
+    fn test_insert_step_out_of_bounds() {
+        let mut seq = SequenceState::new(3);
+
+        seq.insert_step(5);
+
+        assert_eq!(seq.num_steps, 3);
+    }
+
+    #[test]
+    fn test_remove_step_shifts_later_steps() {
+        let mut seq = SequenceState::new(4);
+        seq.set_task_step("build", 0, true);
+        seq.set_task_step("test", 1, true);
+        seq.set_task_step("deploy", 3, true);
+
+        assert!(seq.remove_step(1));
+
+        assert_eq!(seq.num_steps, 3);
+        assert_eq!(seq.completed_steps.len(), 3);
+        assert!(seq.is_task_enabled_for_step("build", 0));
+        assert!(!seq.is_task_enabled_for_step("test", 1));
+        assert!(seq.is_task_enabled_for_step("deploy", 2));
+        assert_eq!(seq.summary(), "build → deploy");
+    }
+
+    #[test]
+    fn test_remove_step_keeps_at_least_one() {
+        let mut seq = SequenceState::new(1);
+
+        assert!(!seq.remove_step(0));
+        assert_eq!(seq.num_steps, 1);
+    }
+
+    #[test]
+    fn test_step_changes_blocked_while_running() {
+        let mut seq = SequenceState::new(3);
+        seq.start_execution();
+
+        seq.add_step();
+        assert!(!seq.remove_step(0));
+
+        assert_eq!(seq.num_steps, 3);
+    }
+
+    #[test]
+    fn test_new_sequence_defaults_to_stop_policy() {
+        let seq = SequenceState::new(3);
+        assert_eq!(seq.step_policies, vec![StepFailurePolicy::Stop; 3]);
+        assert_eq!(seq.step_policy(7), StepFailurePolicy::Stop);
+    }
+
+    #[test]
+    fn test_cycle_step_policy() {
+        let mut seq = SequenceState::new(3);
+
+        let mut seen = Vec::new();
+        for _ in 0..8 {
+            seq.cycle_step_policy(0);
+            seen.push(seq.step_policy(0));
+        }
+
+        assert_eq!(
+            seen,
+            vec![
+                StepFailurePolicy::Continue,
+                StepFailurePolicy::Retry(1),
+                StepFailurePolicy::Retry(2),
+                StepFailurePolicy::Retry(3),
+                StepFailurePolicy::JumpTo(1),
+                StepFailurePolicy::JumpTo(2),
+                StepFailurePolicy::Stop,
+                StepFailurePolicy::Continue,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_cycle_last_step_policy_has_no_jump() {
+        let mut seq = SequenceState::new(2);
+        seq.step_policies[1] = StepFailurePolicy::Retry(3);
+
+        seq.cycle_step_policy(1);
+
+        assert_eq!(seq.step_policy(1), StepFailurePolicy::Stop);
+    }
+
+    #[test]
+    fn test_policy_short_labels() {
+        assert_eq!(StepFailurePolicy::Stop.short_label(), "stop");
+        assert_eq!(StepFailurePolicy::Continue.short_label(), "cont");
+        assert_eq!(StepFailurePolicy::Retry(2).short_label(), "↻2");
+        assert_eq!(StepFailurePolicy::JumpTo(4).short_label(), "→5");
+    }
+
+    #[test]
+    fn test_record_step_failure_stop() {
+        let mut seq = SequenceState::new(3);
+        seq.start_execution();
+
+        assert_eq!(seq.record_step_failure("boom"), StepFailureAction::Abort);
+        assert!(seq.failed_steps[0]);
+    }
+
+    #[test]
+    fn test_record_step_failure_continue() {
+        let mut seq = SequenceState::new(3);
+        seq.step_policies[0] = StepFailurePolicy::Continue;
+        seq.start_execution();
+
+        assert_eq!(seq.record_step_failure("boom"), StepFailureAction::Continue);
+        assert!(seq.failed_steps[0]);
+        assert!(seq.advance_step());
+        assert_eq!(seq.current_step, Some(1));
+    }
+
+    #[test]
+    fn test_record_step_failure_retry() {
+        let mut seq = SequenceState::new(3);
+        seq.step_policies[0] = StepFailurePolicy::Retry(2);
+        seq.start_execution();
+
+        assert_eq!(
+            seq.record_step_failure("boom"),
+            StepFailureAction::Retry {
+                attempt: 1,
+                max_attempts: 2
+            }
+        );
+        assert_eq!(
+            seq.record_step_failure("boom"),
+            StepFailureAction::Retry {
+                attempt: 2,
+                max_attempts: 2
+            }
+        );
+        assert_eq!(seq.record_step_failure("boom"), StepFailureAction::Abort);
+    }
+
+    #[test]
+    fn test_retried_step_that_succeeds_is_not_failed() {
+        let mut seq = SequenceState::new(2);
+        seq.step_policies[0] = StepFailurePolicy::Retry(1);
+        seq.start_execution();
+
+        assert_eq!(
+            seq.record_step_failure("boom"),
+            StepFailureAction::Retry {
+                attempt: 1,
+                max_attempts: 1
+            }
+        );
+        assert!(seq.failed_steps[0]);
+
+        seq.record_step_success();
+        assert!(!seq.failed_steps[0]);
+        assert!(seq.advance_step());
+        assert_eq!(seq.failed_steps, vec![false, false]);
+    }
+
+    #[test]
+    fn test_record_step_failure_jumps_to_cleanup_once() {
+        let mut seq = SequenceState::new(4);
+        seq.step_policies[0] = StepFailurePolicy::JumpTo(3);
+        seq.step_policies[3] = StepFailurePolicy::JumpTo(1);
+        seq.start_execution();
+
+        assert_eq!(
+            seq.record_step_failure("tests failed"),
+            StepFailureAction::Cleanup(3)
+        );
+        assert_eq!(seq.current_step, Some(3));
+        assert_eq!(seq.pending_failure.as_deref(), Some("tests failed"));
+
+        // A failing cleanup step does not jump again
+        assert_eq!(
+            seq.record_step_failure("cleanup failed"),
+            StepFailureAction::Abort
+        );
+    }
+
+    #[test]
+    fn test_start_execution_resets_failure_tracking() {
+        let mut seq = SequenceState::new(2);
+        seq.step_policies[0] = StepFailurePolicy::Retry(1);
+        seq.start_execution();
+        seq.record_step_failure("boom");
+
+        seq.start_execution();
+
+        assert_eq!(seq.failed_steps, vec![false, false]);
+        assert_eq!(seq.step_attempts, vec![0, 0]);
+        assert!(seq.pending_failure.is_none());
+    }
+
+    #[test]
+    fn test_step_results_outlive_the_run() {
+        use crate::models::task_run::TaskExit;
+        use std::time::SystemTime;
+
+        let result = |task_name: &str, exit| TaskResult {
+            task_name: task_name.to_string(),
+            exit,
+            started_at: SystemTime::UNIX_EPOCH,
+            finished_at: SystemTime::UNIX_EPOCH,
+            stdout_lines: 0,
+            stderr_lines: 0,
+            peak_usage: None,
+        };
+        let mut seq = SequenceState::new(2);
+        seq.start_execution();
+
+        seq.record_step_results(vec![
+            result("build", TaskExit::Code(0)),
+            result("lint", TaskExit::Code(1)),
+        ]);
+        seq.advance_step();
+        seq.record_step_results(vec![result("test", TaskExit::Code(0))]);
+        seq.advance_step();
+        seq.reset_execution();
+
+        assert_eq!(seq.step_outcome(0), Some(false));
+        assert_eq!(seq.step_outcome(1), Some(true));
+        assert_eq!(
+            seq.task_result("lint", 0).map(|result| &result.exit),
+            Some(&TaskExit::Code(1))
+        );
+        assert!(seq.task_result("test", 0).is_none());
+
+        seq.start_execution();
+        assert_eq!(seq.step_outcome(0), None);
+    }
+
+    #[test]
+    fn test_insert_and_remove_step_keep_cleanup_targets() {
+        let mut seq = SequenceState::new(3);
+        seq.step_policies[0] = StepFailurePolicy::JumpTo(2);
+        seq.step_policies[1] = StepFailurePolicy::Retry(2);
+
+        seq.insert_step(1);
+        assert_eq!(
+            seq.step_policies,
+            vec![
+                StepFailurePolicy::JumpTo(3),
+                StepFailurePolicy::Stop,
+                StepFailurePolicy::Retry(2),
+                StepFailurePolicy::Stop,
+            ]
+        );
+
+        seq.remove_step(3);
+        assert_eq!(
+            seq.step_policies,
+            vec![
+                StepFailurePolicy::Stop,
+                StepFailurePolicy::Stop,
+                StepFailurePolicy::Retry(2),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_clear_all_resets_policies() {
+        let mut seq = SequenceState::new(2);
+        seq.step_policies[0] = StepFailurePolicy::Continue;
+
+        seq.clear_all();
+
+        assert_eq!(seq.step_policies, vec![StepFailurePolicy::Stop; 2]);
     }
 
     #[test]
@@ -0,0 +1,500 @@
+use serde_json::Value;
+use tui_input::Input;
+
+/// How an argument declared in a task's usage spec is passed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArgKind {
+    /// Passed by position, after the flags
+    Positional,
+    /// Flag followed by a value, e.g. `--env prod`
+    Option,
+    /// Flag that is either passed or not, e.g. `--force`
+    Switch,
+}
+
+/// One argument or flag a task declares in its usage spec
+#[derive(Debug, Clone, PartialEq)]
+pub struct TaskArg {
+    pub name: String,
+    pub kind: ArgKind,
+    /// How the flag is passed, e.g. `--env` or `-f`, empty for positionals
+    pub flag: String,
+    pub help: String,
+    pub required: bool,
+    /// Takes several values, entered separated by spaces
+    pub variadic: bool,
+    pub choices: Vec<String>,
+    pub default: Option<String>,
+}
+
+impl TaskArg {
+    /// Name shown in the form, e.g. `<file>`, `--env <env>` or `--force`
+    pub fn label(&self) -> String {
+        let value = if self.variadic {
+            format!("<{}>...", self.name)
+        } else {
+            format!("<{}>", self.name)
+        };
+        match self.kind {
+            ArgKind::Positional => value,
+            ArgKind::Option => format!("{} {value}", self.flag),
+            ArgKind::Switch => self.flag.clone(),
+        }
+    }
+}
+
+/// Arguments a task declares in `usage_spec`, positionals first. Hidden and global flags
+/// are left out, since they are not the task's to prompt for
+pub fn task_args(usage_spec: &Value) -> Vec<TaskArg> {
+    let cmd = usage_spec.get("cmd").unwrap_or(usage_spec);
+    let entries = |key: &str| -> Vec<&Value> {
+        cmd.get(key)
+            .and_then(Value::as_array)
+            .map(|entries| {
+                entries
+                    .iter()
+                    .filter(|entry| !flag_set(entry, "hide"))
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+
+    let positionals = entries("args").into_iter().filter_map(|arg| {
+        Some(TaskArg {
+            name: text(arg, "name")?,
+            kind: ArgKind::Positional,
+            flag: String::new(),
+            help: text(arg, "help").unwrap_or_default(),
+            // Positionals are required unless declared otherwise, e.g. `[file]`
+            required: arg.get("required").and_then(Value::as_bool).unwrap_or(true),
+            variadic: flag_set(arg, "var"),
+            choices: choices(arg),
+            default: default(arg),
+        })
+    });
+
+    let flags = entries("flags")
+        .into_iter()
+        .filter(|flag| !flag_set(flag, "global"))
+        .filter_map(|flag| {
+            let name = text(flag, "name")?;
+            let first = |key: &str| {
+                flag.get(key)
+                    .and_then(Value::as_array)
+                    .and_then(|names| names.first())
+                    .and_then(Value::as_str)
+                    .map(str::to_string)
+            };
+            let flag_name = match (first("long"), first("short")) {
+                (Some(long), _) => format!("--{long}"),
+                (None, Some(short)) => format!("-{short}"),
+                (None, None) => format!("--{name}"),
+            };
+            let value = flag.get("arg").filter(|arg| arg.is_object());
+
+            Some(TaskArg {
+                name: value.and_then(|arg| text(arg, "name")).unwrap_or(name),
+                kind: if value.is_some() {
+                    ArgKind::Option
+                } else {
+                    ArgKind::Switch
+                },
+                flag: flag_name,
+                help: text(flag, "help").unwrap_or_default(),
+                required: flag_set(flag, "required"),
+                variadic: flag_set(flag, "var"),
+                choices: value.map(choices).unwrap_or_default(),
+                default: default(flag),
+            })
+        });
+
+    positionals.chain(flags).collect()
+}
+
+fn text(entry: &Value, key: &str) -> Option<String> {
+    entry
+        .get(key)
+        .and_then(Value::as_str)
+        .filter(|text| !text.is_empty())
+        .map(str::to_string)
+}
+
+fn flag_set(entry: &Value, key: &str) -> bool {
+    entry.get(key).and_then(Value::as_bool).unwrap_or(false)
+}
+
+/// Choices are `{ "choices": [...] }` in usage specs, a plain list is accepted as well
+fn choices(entry: &Value) -> Vec<String> {
+    let choices = entry.get("choices");
+    choices
+        .and_then(|choices| choices.get("choices"))
+        .or(choices)
+        .and_then(Value::as_array)
+        .map(|choices| {
+            choices
+                .iter()
+                .filter_map(Value::as_str)
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Defaults are a string in older usage specs and a list of values in newer ones
+fn default(entry: &Value) -> Option<String> {
+    match entry.get("default")? {
+        Value::String(value) => Some(value.clone()),
+        Value::Array(values) if !values.is_empty() => Some(
+            values
+                .iter()
+                .filter_map(Value::as_str)
+                .collect::<Vec<_>>()
+                .join(" "),
+        ),
+        Value::Bool(true) => Some("true".to_string()),
+        _ => None,
+    }
+}
+
+/// What the arguments entered in the form are used for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArgsTarget {
+    /// Run the task in the builder's output pane
+    Run,
+    /// Run the task in the full-screen task view
+    RunInTaskView,
+    /// Save them for the task in this step, replayed whenever the sequence runs
+    Step(usize),
+}
+
+/// A task argument and the value entered for it
+#[derive(Debug, Clone)]
+pub struct ArgField {
+    pub arg: TaskArg,
+    /// Text of positionals and options, the chosen value for arguments with choices
+    pub input: Input,
+    /// Whether a switch is passed
+    pub enabled: bool,
+}
+
+impl ArgField {
+    fn new(arg: TaskArg) -> Self {
+        let default = arg.default.clone().unwrap_or_default();
+        Self {
+            enabled: arg.kind == ArgKind::Switch && default == "true",
+            input: Input::new(if arg.kind == ArgKind::Switch {
+                String::new()
+            } else {
+                default
+            }),
+            arg,
+        }
+    }
+
+    pub fn value(&self) -> &str {
+        self.input.value().trim()
+    }
+}
+
+/// Dialog asking for the arguments of a task before it runs or is saved in a step
+#[derive(Debug, Clone)]
+pub struct ArgsForm {
+    pub task_name: String,
+    pub target: ArgsTarget,
+    pub fields: Vec<ArgField>,
+    pub selected: usize,
+    /// Why the entered arguments were rejected
+    pub error: Option<String>,
+}
+
+impl ArgsForm {
+    /// A form for `args`, filled with `previous` arguments if there are any and with the
+    /// declared defaults otherwise
+    pub fn new(
+        task_name: &str,
+        target: ArgsTarget,
+        args: Vec<TaskArg>,
+        previous: &[String],
+    ) -> Self {
+        let mut form = Self {
+            task_name: task_name.to_string(),
+            target,
+            fields: args.into_iter().map(ArgField::new).collect(),
+            selected: 0,
+            error: None,
+        };
+        if !previous.is_empty() {
+            form.fill(previous);
+        }
+        form
+    }
+
+    /// Spread command line arguments over the fields
+    fn fill(&mut self, args: &[String]) {
+        for field in &mut self.fields {
+            field.input = Input::default();
+            field.enabled = false;
+        }
+
+        let mut positionals = self
+            .fields
+            .iter()
+            .enumerate()
+            .filter(|(_, field)| field.arg.kind == ArgKind::Positional)
+            .map(|(index, _)| index)
+            .collect::<Vec<_>>()
+            .into_iter()
+            .peekable();
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            let flag = self
+                .fields
+                .iter_mut()
+                .find(|field| field.arg.kind != ArgKind::Positional && field.arg.flag == *arg);
+            match flag {
+                Some(field) if field.arg.kind == ArgKind::Switch => field.enabled = true,
+                Some(field) => {
+                    let value = args.next().cloned().unwrap_or_default();
+                    field.input = Input::new(value);
+                }
+                None => {
+                    let Some(&index) = positionals.peek() else {
+                        continue;
+                    };
+                    let field = &mut self.fields[index];
+                    if field.arg.variadic {
+                        let value = match field.value() {
+                            "" => arg.clone(),
+                            values => format!("{values} {arg}"),
+                        };
+                        field.input = Input::new(value);
+                    } else {
+                        field.input = Input::new(arg.clone());
+                        positionals.next();
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn selected_field(&self) -> Option<&ArgField> {
+        self.fields.get(self.selected)
+    }
+
+    pub fn selected_field_mut(&mut self) -> Option<&mut ArgField> {
+        self.fields.get_mut(self.selected)
+    }
+
+    pub fn select_next(&mut self) {
+        if !self.fields.is_empty() {
+            self.selected = (self.selected + 1) % self.fields.len();
+        }
+    }
+
+    pub fn select_previous(&mut self) {
+        if !self.fields.is_empty() {
+            self.selected = (self.selected + self.fields.len() - 1) % self.fields.len();
+        }
+    }
+
+    /// Flip the selected switch, or move to the next or previous choice of the selected
+    /// argument. Returns false if the field is free text
+    pub fn cycle(&mut self, forward: bool) -> bool {
+        let Some(field) = self.selected_field_mut() else {
+            return false;
+        };
+        if field.arg.kind == ArgKind::Switch {
+            field.enabled = !field.enabled;
+            return true;
+        }
+        let choices = &field.arg.choices;
+        if choices.is_empty() {
+            return false;
+        }
+
+        let next = match choices.iter().position(|choice| choice == field.value()) {
+            Some(index) if forward => (index + 1) % choices.len(),
+            Some(index) => (index + choices.len() - 1) % choices.len(),
+            None if forward => 0,
+            None => choices.len() - 1,
+        };
+        field.input = Input::new(choices[next].clone());
+        true
+    }
+
+    /// The command line arguments for the entered values: flags first, then positionals
+    pub fn to_args(&self) -> Result<Vec<String>, String> {
+        let mut flags = Vec::new();
+        let mut positionals = Vec::new();
+
+        for field in &self.fields {
+            let arg = &field.arg;
+            let value = field.value();
+            if arg.kind != ArgKind::Switch {
+                if value.is_empty() {
+                    if arg.required {
+                        return Err(format!("{} is required", arg.label()));
+                    }
+                    continue;
+                }
+                if !arg.choices.is_empty() && !arg.choices.iter().any(|choice| choice == value) {
+                    return Err(format!(
+                        "{} must be one of: {}",
+                        arg.label(),
+                        arg.choices.join(", ")
+                    ));
+                }
+            }
+
+            match arg.kind {
+                ArgKind::Switch if field.enabled => flags.push(arg.flag.clone()),
+                ArgKind::Switch => {}
+                ArgKind::Option => flags.extend([arg.flag.clone(), value.to_string()]),
+                ArgKind::Positional if arg.variadic => {
+                    positionals.extend(value.split_whitespace().map(str::to_string))
+                }
+                ArgKind::Positional => positionals.push(value.to_string()),
+            }
+        }
+
+        flags.extend(positionals);
+        Ok(flags)
+    }
+}
+
+/// `task` with its arguments as one command line, quoting arguments that need it,
+/// e.g. `deploy --env prod 'release notes.md'`
+pub fn task_command_line(task_name: &str, args: &[String]) -> String {
+    let mut line = task_name.to_string();
+    for arg in args {
+        line.push(' ');
+        let plain = !arg.is_empty()
+            && arg
+                .chars()
+                .all(|c| c.is_alphanumeric() || "-_./:=,@+%".contains(c));
+        if plain {
+            line.push_str(arg);
+        } else {
+            line.push_str(&format!("'{}'", arg.replace('\'', r"'\''")));
+        }
+    }
+    line
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn deploy_spec() -> Value {
+        json!({
+            "name": "deploy",
+            "cmd": {
+                "args": [
+                    { "name": "target", "usage": "<target>", "help": "Where to deploy",
+                      "required": true, "var": false, "hide": false,
+                      "choices": { "choices": ["staging", "prod"] } },
+                    { "name": "files", "usage": "[files]...", "required": false, "var": true,
+                      "hide": false },
+                    { "name": "secret", "required": false, "hide": true }
+                ],
+                "flags": [
+                    { "name": "force", "usage": "-f --force", "short": ["f"], "long": ["force"],
+                      "required": false, "hide": false, "global": false },
+                    { "name": "region", "usage": "--region <region>", "long": ["region"],
+                      "arg": { "name": "region", "usage": "<region>", "required": true },
+                      "default": ["eu-west-1"] },
+                    { "name": "verbose", "long": ["verbose"], "global": true }
+                ]
+            }
+        })
+    }
+
+    #[test]
+    fn test_task_args_from_usage_spec() {
+        let args = task_args(&deploy_spec());
+        let labels: Vec<String> = args.iter().map(TaskArg::label).collect();
+        assert_eq!(
+            labels,
+            vec!["<target>", "<files>...", "--force", "--region <region>"]
+        );
+
+        assert!(args[0].required);
+        assert_eq!(args[0].choices, vec!["staging", "prod"]);
+        assert_eq!(args[0].help, "Where to deploy");
+        assert!(!args[1].required && args[1].variadic);
+        assert_eq!(args[2].kind, ArgKind::Switch);
+        assert_eq!(args[3].kind, ArgKind::Option);
+        assert_eq!(args[3].default.as_deref(), Some("eu-west-1"));
+
+        assert!(task_args(&Value::Null).is_empty());
+    }
+
+    #[test]
+    fn test_form_builds_command_line_arguments() {
+        let mut form = ArgsForm::new("deploy", ArgsTarget::Run, task_args(&deploy_spec()), &[]);
+        assert_eq!(form.to_args(), Err("<target> is required".to_string()));
+
+        form.fields[0].input = Input::new("qa".to_string());
+        assert_eq!(
+            form.to_args(),
+            Err("<target> must be one of: staging, prod".to_string())
+        );
+
+        // Cycling starts over from the first choice
+        assert!(form.cycle(true));
+        assert_eq!(form.fields[0].value(), "staging");
+        form.select_next();
+        form.fields[1].input = Input::new("a.txt  b.txt".to_string());
+        form.select_next();
+        assert!(form.cycle(true));
+
+        assert_eq!(
+            form.to_args().unwrap(),
+            vec![
+                "--force",
+                "--region",
+                "eu-west-1",
+                "staging",
+                "a.txt",
+                "b.txt"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_form_is_filled_from_previous_arguments() {
+        let previous: Vec<String> = ["--region", "us-east-1", "prod", "--force", "a", "b"]
+            .map(String::from)
+            .to_vec();
+        let form = ArgsForm::new(
+            "deploy",
+            ArgsTarget::Step(1),
+            task_args(&deploy_spec()),
+            &previous,
+        );
+
+        assert_eq!(form.fields[0].value(), "prod");
+        assert_eq!(form.fields[1].value(), "a b");
+        assert!(form.fields[2].enabled);
+        assert_eq!(form.fields[3].value(), "us-east-1");
+        assert_eq!(
+            form.to_args().unwrap(),
+            vec!["--force", "--region", "us-east-1", "prod", "a", "b"]
+        );
+    }
+
+    #[test]
+    fn test_task_command_line_quotes_when_needed() {
+        let args = [
+            "--env".to_string(),
+            "prod".to_string(),
+            "it's here".to_string(),
+        ];
+        assert_eq!(
+            task_command_line("deploy", &args),
+            r"deploy --env prod 'it'\''s here'"
+        );
+        assert_eq!(task_command_line("build", &[]), "build");
+    }
+}
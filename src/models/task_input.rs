@@ -0,0 +1,193 @@
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use tokio::sync::mpsc;
+use tui_input::Input;
+
+/// Shown in the output pane while keys go to the task
+pub const INPUT_ESCAPE_CHORD: &str = "Ctrl+]";
+
+/// Whether `key` is the chord that returns focus from the task to the sequencer. Terminals
+/// report Ctrl+] either as such or, like its control code, as Ctrl+5
+pub fn is_escape_chord(key: &KeyEvent) -> bool {
+    key.modifiers.contains(KeyModifiers::CONTROL)
+        && matches!(key.code, KeyCode::Char(']') | KeyCode::Char('5'))
+}
+
+/// What a terminal sends for `key`, for tasks running on a pseudo-terminal
+pub fn key_bytes(key: &KeyEvent) -> Option<Vec<u8>> {
+    let bytes = match key.code {
+        KeyCode::Char(c) if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            vec![control_code(c)?]
+        }
+        KeyCode::Char(c) => {
+            let mut bytes = Vec::new();
+            if key.modifiers.contains(KeyModifiers::ALT) {
+                bytes.push(0x1b);
+            }
+            bytes.extend(c.to_string().as_bytes());
+            bytes
+        }
+        KeyCode::Enter => b"\r".to_vec(),
+        KeyCode::Tab => b"\t".to_vec(),
+        KeyCode::BackTab => b"\x1b[Z".to_vec(),
+        KeyCode::Backspace => b"\x7f".to_vec(),
+        KeyCode::Esc => b"\x1b".to_vec(),
+        KeyCode::Up => b"\x1b[A".to_vec(),
+        KeyCode::Down => b"\x1b[B".to_vec(),
+        KeyCode::Right => b"\x1b[C".to_vec(),
+        KeyCode::Left => b"\x1b[D".to_vec(),
+        KeyCode::Home => b"\x1b[H".to_vec(),
+        KeyCode::End => b"\x1b[F".to_vec(),
+        KeyCode::Insert => b"\x1b[2~".to_vec(),
+        KeyCode::Delete => b"\x1b[3~".to_vec(),
+        KeyCode::PageUp => b"\x1b[5~".to_vec(),
+        KeyCode::PageDown => b"\x1b[6~".to_vec(),
+        _ => return None,
+    };
+    Some(bytes)
+}
+
+/// Control code of Ctrl+`c`, e.g. 0x03 for Ctrl+C
+fn control_code(c: char) -> Option<u8> {
+    match c.to_ascii_lowercase() {
+        c @ 'a'..='z' => Some(c as u8 - b'a' + 1),
+        '@' | ' ' => Some(0),
+        '[' => Some(0x1b),
+        '\\' => Some(0x1c),
+        ']' => Some(0x1d),
+        '^' => Some(0x1e),
+        '_' => Some(0x1f),
+        _ => None,
+    }
+}
+
+/// Keyboard input for the running task of a tab
+pub struct TaskInput {
+    /// Stdin of the task, `None` once it has been closed
+    input_tx: Option<mpsc::UnboundedSender<Vec<u8>>>,
+    /// Keys go to the task instead of being sequencer shortcuts
+    pub focused: bool,
+    /// Line being typed for a task on pipes, which only gets it on Enter
+    pub line: Input,
+}
+
+impl TaskInput {
+    pub fn new(input_tx: mpsc::UnboundedSender<Vec<u8>>) -> Self {
+        Self {
+            input_tx: Some(input_tx),
+            focused: false,
+            line: Input::default(),
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.input_tx.is_some()
+    }
+
+    /// Write `bytes` to the task's stdin, returning whether it still accepts input
+    pub fn send(&mut self, bytes: Vec<u8>) -> bool {
+        let sent = self
+            .input_tx
+            .as_ref()
+            .is_some_and(|input_tx| input_tx.send(bytes).is_ok());
+        if !sent {
+            self.input_tx = None;
+        }
+        sent
+    }
+
+    /// Send the typed line followed by a newline, returning the line
+    pub fn submit_line(&mut self) -> String {
+        let line = self.line.value().to_string();
+        self.line.reset();
+        self.send(format!("{line}\n").into_bytes());
+        line
+    }
+
+    /// Add pasted text to the typed line, submitting every line it completes
+    pub fn paste_into_line(&mut self, text: &str) -> Vec<String> {
+        let mut submitted = Vec::new();
+        let mut segments = text.split('\n').peekable();
+        while let Some(segment) = segments.next() {
+            let segment = segment.strip_suffix('\r').unwrap_or(segment);
+            let value = format!("{}{segment}", self.line.value());
+            self.line = Input::new(value);
+            if segments.peek().is_some() {
+                submitted.push(self.submit_line());
+            }
+        }
+        submitted
+    }
+
+    /// Signal end of input, e.g. for tasks reading until EOF
+    pub fn close(&mut self) {
+        self.input_tx = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    #[test]
+    fn test_key_bytes() {
+        let none = KeyModifiers::NONE;
+        assert_eq!(
+            key_bytes(&key(KeyCode::Char('y'), none)),
+            Some(b"y".to_vec())
+        );
+        assert_eq!(
+            key_bytes(&key(KeyCode::Char('é'), none)),
+            Some("é".as_bytes().to_vec())
+        );
+        assert_eq!(
+            key_bytes(&key(KeyCode::Char('c'), KeyModifiers::CONTROL)),
+            Some(vec![0x03])
+        );
+        assert_eq!(
+            key_bytes(&key(KeyCode::Char('b'), KeyModifiers::ALT)),
+            Some(b"\x1bb".to_vec())
+        );
+        assert_eq!(key_bytes(&key(KeyCode::Enter, none)), Some(b"\r".to_vec()));
+        assert_eq!(key_bytes(&key(KeyCode::Up, none)), Some(b"\x1b[A".to_vec()));
+        assert_eq!(key_bytes(&key(KeyCode::F(1), none)), None);
+    }
+
+    #[test]
+    fn test_escape_chord() {
+        assert!(is_escape_chord(&key(
+            KeyCode::Char(']'),
+            KeyModifiers::CONTROL
+        )));
+        assert!(is_escape_chord(&key(
+            KeyCode::Char('5'),
+            KeyModifiers::CONTROL
+        )));
+        assert!(!is_escape_chord(&key(
+            KeyCode::Char(']'),
+            KeyModifiers::NONE
+        )));
+    }
+
+    #[test]
+    fn test_lines_are_sent_on_enter_and_paste() {
+        let (input_tx, mut input_rx) = mpsc::unbounded_channel();
+        let mut input = TaskInput::new(input_tx);
+
+        input.line = Input::new("y".to_string());
+        assert_eq!(input.submit_line(), "y");
+        assert_eq!(input_rx.try_recv().unwrap(), b"y\n");
+
+        assert_eq!(input.paste_into_line("one\r\ntwo\nthr"), vec!["one", "two"]);
+        assert_eq!(input.line.value(), "thr");
+        assert_eq!(input_rx.try_recv().unwrap(), b"one\n");
+        assert_eq!(input_rx.try_recv().unwrap(), b"two\n");
+
+        input.close();
+        assert!(!input.is_open());
+        assert!(!input.send(b"ignored".to_vec()));
+    }
+}
@@ -0,0 +1,219 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::time::{Duration, SystemTime};
+
+use super::resource_usage::{format_bytes, ResourceUsage};
+
+/// How a task run ended
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskExit {
+    /// The process exited with this code
+    Code(i32),
+    /// The process was killed by this signal
+    Signal(i32),
+    /// The run was cancelled and its process tree shut down
+    Cancelled,
+    /// The task could not be started
+    Error(String),
+}
+
+impl fmt::Display for TaskExit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Code(code) => write!(f, "exit code {code}"),
+            Self::Signal(signal) => write!(f, "signal {signal}"),
+            Self::Cancelled => write!(f, "cancelled"),
+            Self::Error(error) => write!(f, "error: {error}"),
+        }
+    }
+}
+
+/// Outcome of one finished run of a task
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TaskResult {
+    pub task_name: String,
+    pub exit: TaskExit,
+    pub started_at: SystemTime,
+    pub finished_at: SystemTime,
+    pub stdout_lines: usize,
+    pub stderr_lines: usize,
+    /// Highest CPU and memory use of the task's process tree, missing for runs too short
+    /// to be sampled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub peak_usage: Option<ResourceUsage>,
+}
+
+impl TaskResult {
+    /// Result for a task that could not be started
+    pub fn error(task_name: &str, error: impl fmt::Display) -> Self {
+        let now = SystemTime::now();
+        Self {
+            task_name: task_name.to_string(),
+            exit: TaskExit::Error(error.to_string()),
+            started_at: now,
+            finished_at: now,
+            stdout_lines: 0,
+            stderr_lines: 0,
+            peak_usage: None,
+        }
+    }
+
+    pub fn success(&self) -> bool {
+        self.exit == TaskExit::Code(0)
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.finished_at
+            .duration_since(self.started_at)
+            .unwrap_or_default()
+    }
+
+    pub fn badge(&self) -> &'static str {
+        result_badge(self.success())
+    }
+
+    /// One-line report, e.g. `✓ build finished in 1.2s (exit code 0, 42 lines)`, with the
+    /// peak CPU and memory use when the run was sampled
+    pub fn summary(&self) -> String {
+        let verb = if self.success() { "finished" } else { "failed" };
+        let peak = match &self.peak_usage {
+            Some(peak) => format!(
+                ", peak CPU {:.0}%, {}",
+                peak.cpu_percent,
+                format_bytes(peak.rss_bytes)
+            ),
+            None => String::new(),
+        };
+        format!(
+            "{} {} {verb} in {} ({}, {} lines{peak})",
+            self.badge(),
+            self.task_name,
+            format_elapsed(self.elapsed()),
+            self.exit,
+            self.stdout_lines + self.stderr_lines
+        )
+    }
+}
+
+/// Why a step failed, e.g. `build failed with exit code 2, lint was cancelled`
+pub fn failure_reason(results: &[TaskResult]) -> String {
+    let failures: Vec<String> = results
+        .iter()
+        .filter(|result| !result.success())
+        .map(|result| match &result.exit {
+            TaskExit::Cancelled => format!("{} was cancelled", result.task_name),
+            exit => format!("{} failed with {exit}", result.task_name),
+        })
+        .collect();
+    if failures.is_empty() {
+        "One or more tasks failed".to_string()
+    } else {
+        failures.join(", ")
+    }
+}
+
+pub fn result_badge(success: bool) -> &'static str {
+    if success {
+        "✓"
+    } else {
+        "✗"
+    }
+}
+
+/// Time span between the first start and the last finish of `results`
+pub fn total_elapsed(results: &[TaskResult]) -> Duration {
+    let started_at = results.iter().map(|result| result.started_at).min();
+    let finished_at = results.iter().map(|result| result.finished_at).max();
+    match (started_at, finished_at) {
+        (Some(started_at), Some(finished_at)) => {
+            finished_at.duration_since(started_at).unwrap_or_default()
+        }
+        _ => Duration::ZERO,
+    }
+}
+
+/// Compact elapsed time, e.g. `0.4s`, `12.3s` or `2m05s`
+pub fn format_elapsed(elapsed: Duration) -> String {
+    let secs = elapsed.as_secs();
+    if secs < 60 {
+        format!("{:.1}s", elapsed.as_secs_f64())
+    } else if secs < 3600 {
+        format!("{}m{:02}s", secs / 60, secs % 60)
+    } else {
+        format!("{}h{:02}m", secs / 3600, secs % 3600 / 60)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(exit: TaskExit, start_secs: u64, end_secs: u64) -> TaskResult {
+        TaskResult {
+            task_name: "build".to_string(),
+            exit,
+            started_at: SystemTime::UNIX_EPOCH + Duration::from_secs(start_secs),
+            finished_at: SystemTime::UNIX_EPOCH + Duration::from_secs(end_secs),
+            stdout_lines: 40,
+            stderr_lines: 2,
+            peak_usage: None,
+        }
+    }
+
+    #[test]
+    fn test_summary() {
+        assert_eq!(
+            result(TaskExit::Code(0), 10, 13).summary(),
+            "✓ build finished in 3.0s (exit code 0, 42 lines)"
+        );
+        assert_eq!(
+            result(TaskExit::Signal(9), 10, 11).summary(),
+            "✗ build failed in 1.0s (signal 9, 42 lines)"
+        );
+        assert!(!result(TaskExit::Cancelled, 0, 0).success());
+
+        let mut sampled = result(TaskExit::Code(0), 10, 13);
+        sampled.peak_usage = Some(ResourceUsage {
+            cpu_percent: 187.6,
+            rss_bytes: 300 * 1024 * 1024,
+            children: 4,
+        });
+        assert_eq!(
+            sampled.summary(),
+            "✓ build finished in 3.0s (exit code 0, 42 lines, peak CPU 188%, 300.0 MiB)"
+        );
+    }
+
+    #[test]
+    fn test_failure_reason_lists_failed_tasks() {
+        let mut lint = result(TaskExit::Cancelled, 0, 1);
+        lint.task_name = "lint".to_string();
+        let results = vec![
+            result(TaskExit::Code(2), 0, 1),
+            result(TaskExit::Code(0), 0, 1),
+            lint,
+        ];
+        assert_eq!(
+            failure_reason(&results),
+            "build failed with exit code 2, lint was cancelled"
+        );
+    }
+
+    #[test]
+    fn test_total_elapsed_spans_parallel_tasks() {
+        let results = vec![
+            result(TaskExit::Code(0), 10, 12),
+            result(TaskExit::Code(0), 11, 15),
+        ];
+        assert_eq!(total_elapsed(&results), Duration::from_secs(5));
+        assert_eq!(total_elapsed(&[]), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_format_elapsed() {
+        assert_eq!(format_elapsed(Duration::from_millis(420)), "0.4s");
+        assert_eq!(format_elapsed(Duration::from_secs(125)), "2m05s");
+        assert_eq!(format_elapsed(Duration::from_secs(7260)), "2h01m");
+    }
+}
@@ -0,0 +1,121 @@
+use tokio::sync::{mpsc, watch};
+
+/// Lines of scrolled-off terminal output kept by the emulator
+const TERMINAL_SCROLLBACK: usize = 1000;
+
+/// Rows and columns of a pseudo-terminal
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PtySize {
+    pub rows: u16,
+    pub cols: u16,
+}
+
+impl Default for PtySize {
+    fn default() -> Self {
+        Self { rows: 24, cols: 80 }
+    }
+}
+
+/// Screen of a task running on a pseudo-terminal, emulated from the bytes it writes
+pub struct TerminalSession {
+    parser: vt100::Parser,
+    output_rx: mpsc::UnboundedReceiver<Vec<u8>>,
+    size_tx: watch::Sender<PtySize>,
+}
+
+impl TerminalSession {
+    /// A blank screen of `size`, with the ends the running task writes to and reads its size from
+    pub fn new(
+        size: PtySize,
+    ) -> (
+        Self,
+        mpsc::UnboundedSender<Vec<u8>>,
+        watch::Receiver<PtySize>,
+    ) {
+        let (output_tx, output_rx) = mpsc::unbounded_channel();
+        let (size_tx, size_rx) = watch::channel(size);
+        let session = Self {
+            parser: vt100::Parser::new(size.rows, size.cols, TERMINAL_SCROLLBACK),
+            output_rx,
+            size_tx,
+        };
+        (session, output_tx, size_rx)
+    }
+
+    pub fn screen(&self) -> &vt100::Screen {
+        self.parser.screen()
+    }
+
+    pub fn size(&self) -> PtySize {
+        *self.size_tx.borrow()
+    }
+
+    /// Feed everything the task wrote so far into the emulator
+    pub fn drain(&mut self) {
+        while let Ok(bytes) = self.output_rx.try_recv() {
+            self.parser.process(&bytes);
+        }
+    }
+
+    /// Resize the emulated screen and the task's terminal along with it
+    pub fn resize(&mut self, size: PtySize) {
+        if size != self.size() && size.rows > 0 && size.cols > 0 {
+            self.parser.set_size(size.rows, size.cols);
+            self.size_tx.send_replace(size);
+        }
+    }
+
+    /// The screen as lines with ANSI colors, up to the last non-blank row, for keeping it
+    /// in the line-based output once the task is done
+    pub fn screen_lines(&self) -> Vec<String> {
+        let screen = self.screen();
+        let cols = screen.size().1;
+        let plain: Vec<String> = screen.rows(0, cols).collect();
+        let used = plain
+            .iter()
+            .rposition(|row| !row.trim().is_empty())
+            .map_or(0, |last| last + 1);
+        screen
+            .rows_formatted(0, cols)
+            .take(used)
+            .map(|row| String::from_utf8_lossy(&row).into_owned())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_drain_and_resize() {
+        let (mut session, output_tx, size_rx) = TerminalSession::new(PtySize::default());
+        output_tx
+            .send(b"Password: \x1b[31mwrong\x1b[0m\r\nok".to_vec())
+            .unwrap();
+        session.drain();
+        assert_eq!(session.screen().contents(), "Password: wrong\nok");
+
+        session.resize(PtySize { rows: 10, cols: 40 });
+        assert_eq!(*size_rx.borrow(), PtySize { rows: 10, cols: 40 });
+        assert_eq!(session.screen().size(), (10, 40));
+
+        // A collapsed pane leaves the terminal alone
+        session.resize(PtySize { rows: 0, cols: 40 });
+        assert_eq!(session.size(), PtySize { rows: 10, cols: 40 });
+    }
+
+    #[test]
+    fn test_screen_lines_keep_colors_and_drop_blank_rows() {
+        let (mut session, output_tx, _size_rx) = TerminalSession::new(PtySize::default());
+        output_tx
+            .send(b"\x1b[31mred\x1b[0m\r\nplain".to_vec())
+            .unwrap();
+        session.drain();
+
+        let lines = session.screen_lines();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].contains("\x1b[31m") && lines[0].contains("red"));
+        assert!(lines[1].ends_with("plain"));
+    }
+}
@@ -11,71 +11,52 @@ pub fn spawn_input_handler(event_tx: mpsc::UnboundedSender<AppEvent>) {
 
         loop {
             if event::poll(Duration::from_millis(16)).unwrap_or(false) {
-                match event::read() {
-                    Ok(Event::Key(key)) => {
-                        if key.kind == KeyEventKind::Press
-                            && event_tx.send(AppEvent::KeyPress(key)).is_err()
-                        {
-                            break;
-                        }
+                let app_event = match event::read() {
+                    Ok(Event::Key(key)) if key.kind == KeyEventKind::Press => {
+                        Some(AppEvent::KeyPress(key))
                     }
-                    Ok(Event::Mouse(mouse)) => match mouse.kind {
-                        MouseEventKind::Down(button) => {
-                            if event_tx
-                                .send(AppEvent::MouseClick {
-                                    button,
-                                    row: mouse.row,
-                                    col: mouse.column,
-                                })
-                                .is_err()
-                            {
-                                break;
-                            }
-                        }
-                        MouseEventKind::ScrollUp => {
-                            if event_tx
-                                .send(AppEvent::MouseScroll {
-                                    direction: ScrollDirection::Up,
-                                    row: mouse.row,
-                                    col: mouse.column,
-                                })
-                                .is_err()
-                            {
-                                break;
-                            }
-                        }
-                        MouseEventKind::ScrollDown => {
-                            if event_tx
-                                .send(AppEvent::MouseScroll {
-                                    direction: ScrollDirection::Down,
-                                    row: mouse.row,
-                                    col: mouse.column,
-                                })
-                                .is_err()
-                            {
-                                break;
-                            }
-                        }
-                        MouseEventKind::Moved => {
-                            let current_pos = (mouse.row, mouse.column);
+                    Ok(Event::Paste(text)) => Some(AppEvent::Paste(text)),
+                    Ok(Event::Mouse(mouse)) => {
+                        let scroll = |direction| AppEvent::MouseScroll {
+                            direction,
+                            row: mouse.row,
+                            col: mouse.column,
+                        };
+
+                        match mouse.kind {
+                            MouseEventKind::Down(button) => Some(AppEvent::MouseClick {
+                                button,
+                                row: mouse.row,
+                                col: mouse.column,
+                            }),
+                            MouseEventKind::ScrollUp => Some(scroll(ScrollDirection::Up)),
+                            MouseEventKind::ScrollDown => Some(scroll(ScrollDirection::Down)),
+                            MouseEventKind::ScrollLeft => Some(scroll(ScrollDirection::Left)),
+                            MouseEventKind::ScrollRight => Some(scroll(ScrollDirection::Right)),
+                            MouseEventKind::Moved => {
+                                let current_pos = (mouse.row, mouse.column);
 
-                            // Only send if position actually changed
-                            if current_pos != last_mouse_pos {
-                                if event_tx
-                                    .send(AppEvent::MouseMove {
+                                // Only send if position actually changed
+                                if current_pos != last_mouse_pos {
+                                    last_mouse_pos = current_pos;
+                                    Some(AppEvent::MouseMove {
                                         row: mouse.row,
                                         col: mouse.column,
                                     })
-                                    .is_err()
-                                {
-                                    break;
+                                } else {
+                                    None
                                 }
-                                last_mouse_pos = current_pos;
                             }
+                            _ => None,
                         }
-                        _ => {}
-                    },
-                    _ => {}
+                    }
+                    _ => None,
+                };
+
+                if let Some(app_event) = app_event
+                    && event_tx.send(app_event).is_err()
+                {
+                    break;
                 }
             }
             sleep(Duration::from_millis(8)).await; // ~120 FPS for smoother input
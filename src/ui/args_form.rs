@@ -0,0 +1,163 @@
+use ratatui::{prelude::*, widgets::*};
+
+use crate::models::task_args::{ArgField, ArgKind, ArgsForm, ArgsTarget};
+use crate::ui::constants::*;
+
+/// Draw the arguments form centered over the current view: one row per argument, then
+/// the help of the selected one or why the entered values were rejected
+pub fn draw_args_form(f: &mut Frame, form: &ArgsForm) {
+    let area = f.area();
+    let dialog_width = 70.min(area.width.saturating_sub(4));
+    let dialog_height = (form.fields.len() as u16 + 4).min(area.height);
+
+    let dialog_area = Rect {
+        x: area.x + (area.width - dialog_width) / 2,
+        y: area.y + area.height.saturating_sub(dialog_height) / 2,
+        width: dialog_width,
+        height: dialog_height,
+    };
+
+    let title = match form.target {
+        ArgsTarget::Step(step) => format!(" Arguments of {} in step {} ", form.task_name, step + 1),
+        _ => format!(" Run {} ", form.task_name),
+    };
+    let block = Block::default()
+        .title(title)
+        .title_bottom(format!(" {ARGS_FORM_INSTRUCTIONS} "))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Yellow));
+    let inner = block.inner(dialog_area);
+
+    let labels: Vec<String> = form.fields.iter().map(|field| field.arg.label()).collect();
+    let label_width = labels
+        .iter()
+        .map(|label| label.chars().count())
+        .max()
+        .unwrap_or(0);
+
+    let mut lines: Vec<Line> = form
+        .fields
+        .iter()
+        .zip(&labels)
+        .enumerate()
+        .map(|(index, (field, label))| {
+            field_line(field, label, label_width, index == form.selected)
+        })
+        .collect();
+    lines.push(Line::default());
+    lines.push(match (&form.error, form.selected_field()) {
+        (Some(error), _) => Line::styled(error.clone(), Style::default().fg(Color::Red)),
+        (None, Some(field)) => {
+            Line::styled(field.arg.help.clone(), Style::default().fg(Color::Gray))
+        }
+        (None, None) => Line::default(),
+    });
+
+    f.render_widget(Clear, dialog_area);
+    f.render_widget(Paragraph::new(lines).block(block), dialog_area);
+
+    // Free text fields get the terminal cursor
+    if let Some(field) = form.selected_field()
+        && field.arg.kind != ArgKind::Switch
+        && field.arg.choices.is_empty()
+    {
+        let value_column = (label_width + 4) as u16;
+        let cursor = field.input.visual_cursor() as u16;
+        let x = (inner.x + value_column + cursor).min(inner.right().saturating_sub(1));
+        f.set_cursor_position((x, inner.y + form.selected as u16));
+    }
+}
+
+/// `> --env <env>*  ‹ prod ›`, with the marker on the selected row
+fn field_line<'a>(
+    field: &'a ArgField,
+    label: &str,
+    label_width: usize,
+    selected: bool,
+) -> Line<'a> {
+    let label_style = if selected {
+        Style::default()
+            .fg(Color::Yellow)
+            .add_modifier(Modifier::BOLD)
+    } else {
+        Style::default()
+    };
+    let required = if field.arg.required { "*" } else { " " };
+
+    let mut spans = vec![
+        Span::raw(if selected { "> " } else { "  " }),
+        Span::styled(format!("{label:<label_width$}"), label_style),
+        Span::styled(required, Style::default().fg(Color::Red)),
+        Span::raw(" "),
+    ];
+    match field.arg.kind {
+        ArgKind::Switch => spans.push(Span::raw(if field.enabled { "[x]" } else { "[ ]" })),
+        _ if !field.arg.choices.is_empty() => {
+            spans.push(Span::styled(
+                format!("‹ {} ›", field.value()),
+                Style::default().fg(Color::Cyan),
+            ));
+            spans.push(Span::styled(
+                format!(" ({})", field.arg.choices.join("|")),
+                Style::default().fg(Color::DarkGray),
+            ));
+        }
+        _ => spans.push(Span::styled(
+            field.input.value(),
+            Style::default().fg(Color::Cyan),
+        )),
+    }
+    Line::from(spans)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::task_args::TaskArg;
+
+    fn text(line: &Line) -> String {
+        line.spans
+            .iter()
+            .map(|span| span.content.as_ref())
+            .collect()
+    }
+
+    #[test]
+    fn test_field_line_shows_value_by_kind() {
+        let arg = |kind, choices: &[&str]| TaskArg {
+            name: "env".to_string(),
+            kind,
+            flag: "--env".to_string(),
+            help: String::new(),
+            required: kind == ArgKind::Option,
+            variadic: false,
+            choices: choices.iter().map(|choice| choice.to_string()).collect(),
+            default: Some("prod".to_string()),
+        };
+        let form = ArgsForm::new(
+            "deploy",
+            ArgsTarget::Run,
+            vec![
+                arg(ArgKind::Option, &["staging", "prod"]),
+                arg(ArgKind::Switch, &[]),
+                arg(ArgKind::Positional, &[]),
+            ],
+            &[],
+        );
+
+        let lines: Vec<String> = form
+            .fields
+            .iter()
+            .enumerate()
+            .map(|(index, field)| text(&field_line(field, &field.arg.label(), 11, index == 0)))
+            .collect();
+        assert_eq!(
+            lines,
+            vec![
+                "> --env <env>* ‹ prod › (staging|prod)",
+                "  --env        [ ]",
+                "  <env>        prod",
+            ]
+        );
+    }
+}
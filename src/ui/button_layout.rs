@@ -1,5 +1,6 @@
 use ratatui::layout::Rect;
 use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::Span;
 
 use super::constants::*;
 
@@ -67,13 +68,6 @@ pub enum DialogButton {
     Cancel,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
-pub enum StepButton {
-    Step1,
-    Step2,
-    Step3,
-}
-
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum ButtonType {
     Action {
@@ -290,19 +284,40 @@ pub fn get_dialog_button_at_position(
     }
 }
 
+/// Text for an enabled step button, e.g. `[  1  ]` or `[ 12  ]`
+/// Always as wide as `STEP_DISABLED_TEXT` so columns stay aligned
+pub fn step_button_text(step_index: usize) -> String {
+    let inner_width = STEP_DISABLED_TEXT.len() - 2;
+    format!("[{:^inner_width$}]", step_index + 1)
+}
+
+/// Step button of a task that ran in the step, with its ✓/✗ badge next to the step number
+pub fn step_result_button_text(step_index: usize, badge: &str) -> String {
+    let inner_width = STEP_DISABLED_TEXT.len() - 2;
+    format!("[{:^inner_width$}]", format!("{} {badge}", step_index + 1))
+}
+
+/// Step button of a task with saved arguments, marked next to the step number
+pub fn step_args_button_text(step_index: usize, badge: Option<&str>) -> String {
+    let inner_width = STEP_DISABLED_TEXT.len() - 2;
+    let label = match badge {
+        Some(badge) => format!("{}{STEP_ARGS_MARKER} {badge}", step_index + 1),
+        None => format!("{}{STEP_ARGS_MARKER}", step_index + 1),
+    };
+    format!("[{label:^inner_width$}]")
+}
+
 pub struct StepButtonLayout {
     range: ButtonRange,
     num_steps: usize,
 }
 
 impl StepButtonLayout {
-    pub fn new(_step_rect: &Rect) -> Self {
-        use crate::ui::constants::STEP_1_TEXT; // All step texts have the same length
-
-        // Step buttons start at position 0 and have the same width
+    pub fn new(_step_rect: &Rect, num_steps: usize) -> Self {
+        // Step buttons start at position 0 and all have the same width
         Self {
-            range: (0, STEP_1_TEXT.len() as u16 - 1),
-            num_steps: 3,
+            range: (0, STEP_DISABLED_TEXT.len() as u16 - 1),
+            num_steps,
         }
     }
 
@@ -324,15 +339,40 @@ impl StepButtonLayout {
         }
     }
 
-    // Compatibility methods for existing code
-    pub fn step_1_range(&self) -> ButtonRange {
+    pub fn range(&self) -> ButtonRange {
         self.range
     }
-    pub fn step_2_range(&self) -> ButtonRange {
-        self.range
+}
+
+/// Label for a tab in the tab bar, e.g. ` 2: release `
+pub fn tab_label(tab_index: usize, title: &str) -> String {
+    format!(" {}: {title} ", tab_index + 1)
+}
+
+/// Tab labels laid out left to right, separated by `TAB_SEPARATOR`
+pub struct TabBarLayout {
+    ranges: Vec<ButtonRange>,
+}
+
+impl TabBarLayout {
+    pub fn new(titles: &[String]) -> Self {
+        let separator_width = Span::raw(TAB_SEPARATOR).width() as u16;
+        let mut ranges = Vec::new();
+        let mut current_pos = 0;
+
+        for (index, title) in titles.iter().enumerate() {
+            let width = Span::raw(tab_label(index, title)).width() as u16;
+            ranges.push((current_pos, current_pos + width.saturating_sub(1)));
+            current_pos += width + separator_width;
+        }
+
+        Self { ranges }
     }
-    pub fn step_3_range(&self) -> ButtonRange {
-        self.range
+
+    pub fn get_tab_at_position(&self, relative_col: u16) -> Option<usize> {
+        self.ranges
+            .iter()
+            .position(|range| (range.0..=range.1).contains(&relative_col))
     }
 }
 
@@ -932,6 +972,52 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_step_button_text() {
+        assert_eq!(step_button_text(0), "[  1  ]");
+        assert_eq!(step_button_text(2), "[  3  ]");
+        assert_eq!(step_button_text(9), "[ 10  ]");
+        assert_eq!(step_button_text(99).len(), STEP_DISABLED_TEXT.len());
+    }
+
+    #[test]
+    fn test_step_result_button_text() {
+        assert_eq!(step_result_button_text(0, "✓"), "[ 1 ✓ ]");
+        assert_eq!(step_result_button_text(9, "✗"), "[10 ✗ ]");
+        assert_eq!(step_args_button_text(0, None), "[ 1*  ]");
+        assert_eq!(step_args_button_text(0, Some("✓")), "[1* ✓ ]");
+        assert_eq!(
+            step_result_button_text(99, "✓").chars().count(),
+            STEP_DISABLED_TEXT.len()
+        );
+    }
+
+    #[test]
+    fn test_step_button_layout_respects_num_steps() {
+        let rect = create_test_rect();
+        let layout = StepButtonLayout::new(&rect, 8);
+
+        assert_eq!(layout.range(), (0, 6));
+        assert_eq!(layout.get_step_button_at_position(7, 3), Some(7));
+        assert_eq!(layout.get_step_button_at_position(8, 3), None);
+        assert_eq!(layout.get_step_button_at_position(0, 7), None);
+    }
+
+    #[test]
+    fn test_tab_bar_layout() {
+        let titles = vec!["Sequence 1".to_string(), "▶ release".to_string()];
+        let layout = TabBarLayout::new(&titles);
+
+        // " 1: Sequence 1 " is 15 columns wide, then the separator
+        assert_eq!(layout.get_tab_at_position(0), Some(0));
+        assert_eq!(layout.get_tab_at_position(14), Some(0));
+        assert_eq!(layout.get_tab_at_position(15), None);
+        // " 2: ▶ release " is 14 columns wide
+        assert_eq!(layout.get_tab_at_position(16), Some(1));
+        assert_eq!(layout.get_tab_at_position(29), Some(1));
+        assert_eq!(layout.get_tab_at_position(30), None);
+    }
+
     #[test]
     fn test_action_button_layout_rename_mode() {
         let rect = create_test_rect();
@@ -39,12 +39,35 @@ pub const DELETE_DIALOG_CANCEL_KEYS: &str = "N";
 pub const DELETE_DIALOG_CANCEL_KEYS_ALT: &str = "ESC";
 pub const DELETE_DIALOG_CANCEL_ACTION: &str = " to cancel";
 
-// Step button texts
-pub const STEP_1_TEXT: &str = "[  1  ]";
-pub const STEP_2_TEXT: &str = "[  2  ]";
-pub const STEP_3_TEXT: &str = "[  3  ]";
+// Step button texts (enabled buttons show the step number, see `step_button_text`)
 pub const STEP_DISABLED_TEXT: &str = "[     ]";
+/// Follows the step number of tasks that have arguments saved for the step
+pub const STEP_ARGS_MARKER: &str = "*";
+pub const STEP_HEADER_PREFIX: &str = "Step ";
+pub const STEP_SCROLL_LEFT_MARKER: &str = "◀ ";
+pub const STEP_SCROLL_RIGHT_MARKER: &str = " ▶";
+
+// Saved sequences texts
+pub const SEQUENCE_NAME_PREFIX: &str = "Sequence: ";
+pub const SAVED_SEQUENCES_TITLE: &str = "Saved Sequences";
+pub const NO_SAVED_SEQUENCES_TEXT: &str =
+    "No saved sequences yet. Press s in the builder to save one.";
+pub const RUN_HISTORY_TITLE: &str = "Run History";
+pub const NO_RUN_HISTORY_TEXT: &str =
+    "No runs recorded yet. Runs of tasks and sequences show up here.";
+pub const PROMPT_INSTRUCTIONS: &str = "Enter: Confirm | Esc: Cancel";
+pub const ARGS_FORM_INSTRUCTIONS: &str =
+    "Enter: Confirm | Esc: Cancel | ↑/↓: Field | Space: Toggle | ←/→: Choice";
+pub const ERROR_NAVIGATOR_INSTRUCTIONS: &str = "Enter: Open in editor | ↑/↓: Select | Esc: Close";
+
+// Tab bar texts
+pub const TAB_SEPARATOR: &str = "│";
 
 // Other UI texts
 pub const TASK_OUTPUT_TITLE: &str = "Task Output";
+/// Appended to the output pane title while only stderr lines are shown
+pub const STDERR_ONLY_TITLE: &str = " [stderr only]";
+pub const PTY_OUTPUT_TITLE: &str = "Task Output (PTY)";
+/// Shown after the name of tasks that run on a pseudo-terminal
+pub const PTY_TASK_MARKER: &str = " [pty]";
 pub const CONTROLS_TITLE: &str = "Controls";
@@ -0,0 +1,54 @@
+use ratatui::{prelude::*, widgets::*};
+
+use crate::models::error_location::FoundLocation;
+use crate::models::ErrorNavigator;
+use crate::ui::constants::*;
+
+fn location_item(found: &FoundLocation) -> ListItem<'static> {
+    ListItem::new(Line::from(vec![
+        Span::styled(
+            found.location.label(),
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::raw("  "),
+        Span::styled(found.text.clone(), Style::default().fg(Color::Gray)),
+    ]))
+}
+
+/// Draw the locations found in the output over the lower part of the screen, leaving the
+/// output line of the selected one visible above it
+pub fn draw_error_navigator(f: &mut Frame, navigator: &ErrorNavigator, editor: &str) {
+    let area = f.area();
+    let dialog_width = 100.min(area.width.saturating_sub(4));
+    let dialog_height = (navigator.locations.len() as u16 + 2)
+        .min(12)
+        .min(area.height);
+
+    let dialog_area = Rect {
+        x: area.x + (area.width - dialog_width) / 2,
+        y: area.bottom().saturating_sub(dialog_height),
+        width: dialog_width,
+        height: dialog_height,
+    };
+
+    let block = Block::default()
+        .title(format!(
+            " File locations ({}/{}), opened with {editor} ",
+            navigator.selected + 1,
+            navigator.locations.len()
+        ))
+        .title_bottom(format!(" {ERROR_NAVIGATOR_INSTRUCTIONS} "))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Yellow));
+
+    let items: Vec<ListItem> = navigator.locations.iter().map(location_item).collect();
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+    let mut list_state = ListState::default().with_selected(Some(navigator.selected));
+
+    f.render_widget(Clear, dialog_area);
+    f.render_stateful_widget(list, dialog_area, &mut list_state);
+}
@@ -1,21 +1,48 @@
 use ratatui::prelude::*;
 
 use crate::app::App;
-use crate::models::AppState;
+use crate::models::{AppState, PromptKind};
 
+pub mod args_form;
 pub mod button_layout;
 pub mod constants;
+pub mod error_navigator;
+pub mod output_line;
+pub mod output_search;
+pub mod prompt;
+pub mod run_history;
 pub mod sequence_builder;
+pub mod sequence_list;
 pub mod task_detail;
+pub mod task_input;
 pub mod task_running;
+pub mod terminal_screen;
 
 impl App {
     pub fn draw(&mut self, f: &mut Frame) {
         match &self.state {
             AppState::Detail(task_name) => task_detail::draw_task_detail(self, f, task_name),
-            AppState::Running(task_name) => task_running::draw_task_running(self, f, task_name),
+            AppState::Running(task_name) => {
+                let task_name = task_name.clone();
+                task_running::draw_task_running(self, f, &task_name)
+            }
             AppState::SequenceBuilder => sequence_builder::draw_sequence_builder(self, f),
             AppState::Renaming(_) => sequence_builder::draw_sequence_builder(self, f),
+            AppState::Sequences => sequence_list::draw_sequence_list(self, f),
+            AppState::History => run_history::draw_run_history(self, f),
+        }
+
+        // The search prompt sits at the bottom of the output pane instead of over it
+        if let Some(prompt) = &self.prompt
+            && prompt.kind != PromptKind::SearchOutput
+        {
+            prompt::draw_prompt(f, prompt);
+        }
+        if let Some(form) = &self.args_form {
+            args_form::draw_args_form(f, form);
+        }
+        if let Some(navigator) = &self.error_navigator {
+            error_navigator::draw_error_navigator(f, navigator, &self.editor);
         }
     }
 }
@@ -0,0 +1,211 @@
+use ratatui::prelude::*;
+use std::time::SystemTime;
+
+use crate::models::task_run::format_elapsed;
+use crate::models::{LineSource, OutputLine};
+
+/// Text colors of lines that are not a task's standard output. Colors the task printed
+/// itself take precedence
+const STDERR_STYLE: Style = Style::new().fg(Color::LightRed);
+const STDIN_STYLE: Style = Style::new().fg(Color::Green);
+const NOTICE_STYLE: Style = Style::new().fg(Color::Cyan);
+
+/// Style of the timestamp and task name in front of a line
+const ORIGIN_STYLE: Style = Style::new().fg(Color::DarkGray);
+
+fn ensure_ansi_reset(line: &str) -> String {
+    const ANSI_RESET: &str = "\x1b[0m";
+
+    // Check if line already ends with ANSI reset sequence
+    if line.ends_with(ANSI_RESET) {
+        line.to_string()
+    } else {
+        // Add reset sequence to end of line to prevent color bleeding
+        format!("{line}{ANSI_RESET}")
+    }
+}
+
+/// The text of `line` with its ANSI colors, without anything in front of it
+pub fn text_line(line: &OutputLine) -> Line<'static> {
+    // Ensure line has ANSI reset to prevent color bleeding
+    let normalized_line = ensure_ansi_reset(&line.text);
+
+    // Parse ANSI escape sequences and convert to ratatui Text, falling back to raw text
+    match ansi_to_tui::IntoText::into_text(&normalized_line) {
+        Ok(parsed_text) => Line::from(
+            parsed_text
+                .lines
+                .into_iter()
+                .flat_map(|parsed_line| parsed_line.spans)
+                .collect::<Vec<_>>(),
+        ),
+        Err(_) => Line::raw(normalized_line),
+    }
+}
+
+/// `text` colored by where `line` came from, preceded by its time relative to
+/// `started_at` when given, and by the task name for tasks sharing a step
+pub fn with_origin(
+    text: Line<'static>,
+    line: &OutputLine,
+    started_at: Option<SystemTime>,
+) -> Line<'static> {
+    let mut spans = Vec::new();
+    if let Some(started_at) = started_at {
+        let offset = line.time.duration_since(started_at).unwrap_or_default();
+        spans.push(Span::styled(
+            format!("{:>7} ", format!("+{}", format_elapsed(offset))),
+            ORIGIN_STYLE,
+        ));
+    }
+    if line.tagged
+        && let Some(task) = &line.task
+    {
+        spans.push(Span::styled(format!("[{task}] "), ORIGIN_STYLE));
+    }
+    let style = match line.source {
+        LineSource::Stdout => Style::default(),
+        LineSource::Stderr => STDERR_STYLE,
+        LineSource::Stdin => {
+            spans.push(Span::styled("> ", STDIN_STYLE));
+            STDIN_STYLE
+        }
+        LineSource::Notice => NOTICE_STYLE,
+    };
+    spans.extend(
+        text.spans
+            .into_iter()
+            .map(|span| Span::styled(span.content, style.patch(own_colors(span.style)))),
+    );
+    Line::from(spans)
+}
+
+/// `style` without the colors reset by an escape code, which fall back to the line's colors
+/// instead of the terminal's
+fn own_colors(style: Style) -> Style {
+    Style {
+        fg: style.fg.filter(|&color| color != Color::Reset),
+        bg: style.bg.filter(|&color| color != Color::Reset),
+        ..style
+    }
+}
+
+/// `line` as the output pane shows it, see `with_origin`
+pub fn output_line(line: &OutputLine, started_at: Option<SystemTime>) -> Line<'static> {
+    with_origin(text_line(line), line, started_at)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn spans(line: &Line) -> Vec<(String, Style)> {
+        line.spans
+            .iter()
+            .map(|span| (span.content.to_string(), span.style))
+            .collect()
+    }
+
+    #[test]
+    fn test_stderr_line_keeps_its_own_colors() {
+        let line = OutputLine::stderr("\x1b[33mwarning\x1b[0m: unused");
+        let rendered = output_line(&line, None);
+        assert_eq!(rendered.spans[0].content, "warning");
+        assert_eq!(rendered.spans[0].style.fg, Some(Color::Yellow));
+        let text: String = rendered
+            .spans
+            .iter()
+            .map(|span| span.content.as_ref())
+            .collect();
+        assert_eq!(text, "warning: unused");
+        let unused = rendered
+            .spans
+            .iter()
+            .find(|span| span.content == ": unused");
+        assert_eq!(unused.unwrap().style.fg, Some(Color::LightRed));
+    }
+
+    #[test]
+    fn test_timestamp_and_task_in_front() {
+        let started_at = SystemTime::UNIX_EPOCH;
+        let mut line = OutputLine::stdout("ok").with_task("lint");
+        line.time = started_at + Duration::from_millis(12_300);
+        line.tagged = true;
+
+        let rendered = output_line(&line, Some(started_at));
+        assert_eq!(
+            spans(&rendered),
+            vec![
+                (" +12.3s ".to_string(), ORIGIN_STYLE),
+                ("[lint] ".to_string(), ORIGIN_STYLE),
+                ("ok".to_string(), Style::default()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_stdin_and_notice_lines() {
+        let rendered = output_line(&OutputLine::stdin("y"), None);
+        assert_eq!(rendered.spans[0].content, "> ");
+        assert_eq!(rendered.spans[1].style, STDIN_STYLE);
+
+        let rendered = output_line(&OutputLine::notice("Sequence stopped by user"), None);
+        assert_eq!(rendered.spans[0].style, NOTICE_STYLE);
+    }
+
+    #[test]
+    fn test_ensure_ansi_reset_already_has_reset() {
+        let line = "STDOUT: Some output\x1b[0m";
+        let result = ensure_ansi_reset(line);
+        assert_eq!(result, line);
+    }
+
+    #[test]
+    fn test_ensure_ansi_reset_missing_reset() {
+        let line = "STDERR: Error message";
+        let result = ensure_ansi_reset(line);
+        assert_eq!(result, "STDERR: Error message\x1b[0m");
+    }
+
+    #[test]
+    fn test_ensure_ansi_reset_empty_string() {
+        let line = "";
+        let result = ensure_ansi_reset(line);
+        assert_eq!(result, "\x1b[0m");
+    }
+
+    #[test]
+    fn test_ensure_ansi_reset_with_color_codes() {
+        let line = "\x1b[31mSTDOUT: Red text\x1b[32m";
+        let result = ensure_ansi_reset(line);
+        assert_eq!(result, "\x1b[31mSTDOUT: Red text\x1b[32m\x1b[0m");
+    }
+
+    #[test]
+    fn test_ensure_ansi_reset_partial_escape_sequence() {
+        let line = "Normal text\x1b[31";
+        let result = ensure_ansi_reset(line);
+        assert_eq!(result, "Normal text\x1b[31\x1b[0m");
+    }
+
+    #[test]
+    fn test_ensure_ansi_reset_multiple_resets_in_middle() {
+        let line = "Text\x1b[0m more text";
+        let result = ensure_ansi_reset(line);
+        assert_eq!(result, "Text\x1b[0m more text\x1b[0m");
+    }
+
+    #[test]
+    fn test_ensure_ansi_reset_stdout_stderr_prefixes() {
+        // Test the specific use case that was causing bleeding
+        let stdout_line = "STDOUT: \x1b[32mSuccess message";
+        let stderr_line = "STDERR: \x1b[31mError message";
+
+        let stdout_result = ensure_ansi_reset(stdout_line);
+        let stderr_result = ensure_ansi_reset(stderr_line);
+
+        assert_eq!(stdout_result, "STDOUT: \x1b[32mSuccess message\x1b[0m");
+        assert_eq!(stderr_result, "STDERR: \x1b[31mError message\x1b[0m");
+    }
+}
@@ -0,0 +1,163 @@
+use ratatui::prelude::*;
+use regex::Regex;
+
+use crate::app::App;
+use crate::models::PromptKind;
+
+/// Matches on other lines than the current one
+const MATCH_STYLE: Style = Style::new().fg(Color::Black).bg(Color::Yellow);
+/// Matches on the line last jumped to
+const CURRENT_MATCH_STYLE: Style = Style::new().fg(Color::Black).bg(Color::LightRed);
+
+/// `line` with every match of `regex` highlighted, keeping the colors of the rest.
+/// Matching runs on the parsed text, so escape codes never match
+pub fn highlight_matches(line: Line<'static>, regex: &Regex, current: bool) -> Line<'static> {
+    let text: String = line
+        .spans
+        .iter()
+        .map(|span| span.content.as_ref())
+        .collect();
+    let ranges: Vec<_> = regex
+        .find_iter(&text)
+        .filter(|found| !found.is_empty())
+        .map(|found| found.range())
+        .collect();
+    if ranges.is_empty() {
+        return line;
+    }
+    let highlight = if current {
+        CURRENT_MATCH_STYLE
+    } else {
+        MATCH_STYLE
+    };
+
+    let Line {
+        spans: old_spans,
+        style,
+        alignment,
+    } = line;
+    let mut spans = Vec::new();
+    let mut span_start = 0;
+    for span in old_spans {
+        let span_end = span_start + span.content.len();
+        let mut position = span_start;
+        for range in ranges
+            .iter()
+            .filter(|range| range.start < span_end && range.end > span_start)
+        {
+            let start = range.start.max(span_start);
+            let end = range.end.min(span_end);
+            if start > position {
+                spans.push(Span::styled(text[position..start].to_string(), span.style));
+            }
+            spans.push(Span::styled(
+                text[start..end].to_string(),
+                span.style.patch(highlight),
+            ));
+            position = end;
+        }
+        if position < span_end {
+            spans.push(Span::styled(
+                text[position..span_end].to_string(),
+                span.style,
+            ));
+        }
+        span_start = span_end;
+    }
+
+    Line {
+        spans,
+        style,
+        alignment,
+    }
+}
+
+/// The search prompt while it is open: `/pattern` with its cursor, then the position of
+/// the current match or why the pattern is invalid
+pub fn search_line(app: &App) -> Option<Line<'static>> {
+    let prompt = app
+        .prompt
+        .as_ref()
+        .filter(|prompt| prompt.kind == PromptKind::SearchOutput)?;
+    let value = prompt.input.value();
+    let cursor = prompt.input.visual_cursor();
+    let before: String = value.chars().take(cursor).collect();
+    let at_cursor: String = value
+        .chars()
+        .nth(cursor)
+        .map_or(" ".to_string(), String::from);
+    let after: String = value.chars().skip(cursor + 1).collect();
+
+    let style = Style::default().fg(Color::Yellow);
+    let mut spans = vec![
+        Span::styled("/", style.add_modifier(Modifier::BOLD)),
+        Span::styled(before, style),
+        Span::styled(at_cursor, style.add_modifier(Modifier::REVERSED)),
+        Span::styled(after, style),
+    ];
+    match (&app.output_search_error, &app.output_search) {
+        (Some(error), _) => spans.push(Span::styled(
+            format!("  {error}"),
+            Style::default().fg(Color::Red),
+        )),
+        (None, Some(search)) => spans.push(Span::styled(
+            format!("  {}", search.status()),
+            Style::default().fg(Color::DarkGray),
+        )),
+        (None, None) => {}
+    }
+    Some(Line::from(spans))
+}
+
+/// Title suffix of the output pane while a search is active, e.g. ` [/error 3/15]`
+pub fn search_title(app: &App) -> Option<String> {
+    let search = app.output_search.as_ref()?;
+    Some(format!(" [/{} {}]", search.pattern, search.status()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn contents(line: &Line) -> Vec<(String, Style)> {
+        line.spans
+            .iter()
+            .map(|span| (span.content.to_string(), span.style))
+            .collect()
+    }
+
+    #[test]
+    fn test_highlight_matches_across_spans() {
+        let red = Style::default().fg(Color::Red);
+        let line = Line::from(vec![Span::styled("error", red), Span::raw(": no errors")]);
+        let regex = Regex::new("rror|no").unwrap();
+
+        let highlighted = highlight_matches(line, &regex, false);
+        assert_eq!(
+            contents(&highlighted),
+            vec![
+                ("e".to_string(), red),
+                ("rror".to_string(), red.patch(MATCH_STYLE)),
+                (": ".to_string(), Style::default()),
+                ("no".to_string(), MATCH_STYLE),
+                (" e".to_string(), Style::default()),
+                ("rror".to_string(), MATCH_STYLE),
+                ("s".to_string(), Style::default()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_match_spanning_two_spans() {
+        let line = Line::from(vec![Span::raw("ab"), Span::raw("cd")]);
+        let regex = Regex::new("bc").unwrap();
+
+        let highlighted = highlight_matches(line, &regex, true);
+        let text: Vec<String> = contents(&highlighted)
+            .into_iter()
+            .map(|(text, _)| text)
+            .collect();
+        assert_eq!(text, vec!["a", "b", "c", "d"]);
+        assert_eq!(highlighted.spans[1].style, CURRENT_MATCH_STYLE);
+    }
+}
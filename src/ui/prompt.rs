@@ -0,0 +1,38 @@
+use ratatui::{prelude::*, widgets::*};
+
+use crate::models::Prompt;
+use crate::ui::constants::*;
+
+/// Draw a single-line text prompt centered over the current view
+pub fn draw_prompt(f: &mut Frame, prompt: &Prompt) {
+    let area = f.area();
+    let dialog_width = 60.min(area.width.saturating_sub(4));
+    let dialog_height = 3;
+
+    let dialog_area = Rect {
+        x: area.x + (area.width - dialog_width) / 2,
+        y: area.y + area.height.saturating_sub(dialog_height) / 2,
+        width: dialog_width,
+        height: dialog_height.min(area.height),
+    };
+
+    // Keep the cursor visible when the text is wider than the input field
+    let field_width = dialog_width.saturating_sub(2) as usize;
+    let scroll = prompt.input.visual_scroll(field_width.saturating_sub(1));
+
+    let input = Paragraph::new(prompt.input.value())
+        .scroll((0, scroll as u16))
+        .block(
+            Block::default()
+                .title(prompt.kind.title())
+                .title_bottom(format!(" {PROMPT_INSTRUCTIONS} "))
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Yellow)),
+        );
+
+    f.render_widget(Clear, dialog_area);
+    f.render_widget(input, dialog_area);
+
+    let cursor_offset = prompt.input.visual_cursor().saturating_sub(scroll) as u16;
+    f.set_cursor_position((dialog_area.x + 1 + cursor_offset, dialog_area.y + 1));
+}
@@ -0,0 +1,112 @@
+use ratatui::{prelude::*, widgets::*};
+use std::time::{Duration, SystemTime};
+
+use crate::app::App;
+use crate::models::run_history::RunKind;
+use crate::models::task_run::format_elapsed;
+use crate::models::RunRecord;
+use crate::ui::constants::*;
+
+/// How long ago something happened, e.g. `42s ago`, `5m ago` or `3d ago`
+fn format_age(age: Duration) -> String {
+    let secs = age.as_secs();
+    if secs < 60 {
+        format!("{secs}s ago")
+    } else if secs < 3600 {
+        format!("{}m ago", secs / 60)
+    } else if secs < 86400 {
+        format!("{}h ago", secs / 3600)
+    } else {
+        format!("{}d ago", secs / 86400)
+    }
+}
+
+fn run_item(run: &RunRecord, now: SystemTime) -> ListItem<'static> {
+    let (badge, badge_color) = if run.success() {
+        ("✓ ", Color::Green)
+    } else {
+        ("✗ ", Color::Red)
+    };
+    let kind = match run.kind {
+        RunKind::Task => "task",
+        RunKind::Sequence => "sequence",
+    };
+    let age = now.duration_since(run.started_at).unwrap_or_default();
+
+    ListItem::new(Line::from(vec![
+        Span::styled(badge, Style::default().fg(badge_color)),
+        Span::styled(
+            run.name.clone(),
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(
+            format!(
+                "  [{kind}, {}, {}]  ",
+                format_elapsed(run.elapsed()),
+                format_age(age)
+            ),
+            Style::default().fg(Color::Gray),
+        ),
+        Span::raw(run.command.clone()),
+    ]))
+}
+
+pub fn draw_run_history(app: &App, f: &mut Frame) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(3)])
+        .split(f.area());
+
+    let mut title = format!(
+        "{RUN_HISTORY_TITLE} ({})",
+        app.run_history.project_dir().display()
+    );
+    if !app.history_filter.is_empty() {
+        title.push_str(&format!(" [filter: {}]", app.history_filter));
+    }
+    let block = Block::default().title(title).borders(Borders::ALL);
+
+    let runs = app.visible_history_runs();
+    if runs.is_empty() {
+        f.render_widget(
+            Paragraph::new(NO_RUN_HISTORY_TEXT)
+                .block(block)
+                .alignment(Alignment::Center),
+            chunks[0],
+        );
+    } else {
+        let now = SystemTime::now();
+        let items: Vec<ListItem> = runs.iter().map(|run| run_item(run, now)).collect();
+
+        let list = List::new(items)
+            .block(block)
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        let mut list_state = ListState::default().with_selected(Some(app.selected_history_run));
+        f.render_stateful_widget(list, chunks[0], &mut list_state);
+    }
+
+    let controls = Line::from(
+        "↑/↓: Select | Enter: Open output | x: Run again | /: Filter | Esc/b: Back | q: Quit",
+    );
+    f.render_widget(
+        Paragraph::new(controls)
+            .block(Block::default().borders(Borders::ALL).title(CONTROLS_TITLE))
+            .style(Style::default().fg(Color::Gray)),
+        chunks[1],
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_age() {
+        assert_eq!(format_age(Duration::from_secs(42)), "42s ago");
+        assert_eq!(format_age(Duration::from_secs(310)), "5m ago");
+        assert_eq!(format_age(Duration::from_secs(3 * 3600 + 5)), "3h ago");
+        assert_eq!(format_age(Duration::from_secs(2 * 86400)), "2d ago");
+    }
+}
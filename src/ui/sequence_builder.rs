@@ -1,49 +1,103 @@
 use ratatui::{
-    layout::{Constraint, Direction, Layout, Rect},
+    layout::{Constraint, Direction, Flex, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
     widgets::{Block, Borders, Cell, Clear, Paragraph, Row, Table},
     Frame,
 };
 
+use crate::app::file_watch::WatchTarget;
 use crate::app::App;
-use crate::models::AppState;
+use crate::models::freshness::Freshness;
+use crate::models::task_run::{format_elapsed, result_badge};
+use crate::models::{AppState, PtySize, StepFailurePolicy};
 use crate::ui::button_layout::{
-    ActionButton, ButtonStyleManager, ButtonTheme, ButtonType, DialogButton, SequenceButton,
+    step_args_button_text, step_button_text, step_result_button_text, tab_label, ActionButton,
+    ButtonStyleManager, ButtonTheme, ButtonType, DialogButton, SequenceButton,
 };
 use crate::ui::constants::*;
+use crate::ui::{output_line, output_search, task_input, terminal_screen};
+
+/// Width of a step column, matching the width of the step buttons
+const STEP_COLUMN_WIDTH: u16 = 7;
+/// Minimum width of the task name column
+const TASK_NAME_MIN_WIDTH: u16 = 20;
+/// Width of the freshness column, enough for its longest label
+const FRESHNESS_COLUMN_WIDTH: u16 = 5;
+/// Minimum width of the actions column, enough to fit every action button
+const ACTIONS_MIN_WIDTH: u16 = 33;
+/// Spacing ratatui's `Table` puts between columns
+const COLUMN_SPACING: u16 = 1;
 
-fn ensure_ansi_reset(line: &str) -> String {
-    const ANSI_RESET: &str = "\x1b[0m";
+pub struct TableLayout {
+    pub table_area: Rect,
+    pub column_rects: Vec<Rect>,
+    /// Index of the first step shown in the step columns
+    pub first_step: usize,
+    /// Number of step columns that fit in the table
+    pub visible_steps: usize,
+}
 
-    // Check if line already ends with ANSI reset sequence
-    if line.ends_with(ANSI_RESET) {
-        line.to_string()
-    } else {
-        // Add reset sequence to end of line to prevent color bleeding
-        format!("{line}{ANSI_RESET}")
+impl TableLayout {
+    /// Step columns currently on screen as (step index, column rect)
+    pub fn step_columns(&self) -> impl Iterator<Item = (usize, Rect)> + '_ {
+        // Columns 0 and 1 are the task name and freshness, steps follow, the actions
+        // column is last
+        self.column_rects
+            .iter()
+            .skip(2)
+            .take(self.visible_steps)
+            .enumerate()
+            .map(|(i, rect)| (self.first_step + i, *rect))
+    }
+
+    /// Find the step whose column contains the given screen column
+    pub fn step_at_column(&self, col: u16) -> Option<(usize, Rect)> {
+        self.step_columns()
+            .find(|(_, rect)| col >= rect.x && col < rect.x + rect.width)
     }
 }
 
-pub struct TableLayout {
-    pub table_area: Rect,
-    pub column_rects: Vec<Rect>,
+/// How many step columns fit next to the task name, freshness and actions columns
+pub fn visible_step_count(area_width: u16, num_steps: usize) -> usize {
+    let inner_width = area_width.saturating_sub(2); // Left/right borders
+    let fixed_width =
+        TASK_NAME_MIN_WIDTH + FRESHNESS_COLUMN_WIDTH + ACTIONS_MIN_WIDTH + 2 * COLUMN_SPACING;
+    let step_width = STEP_COLUMN_WIDTH + COLUMN_SPACING;
+    let fitting_steps = (inner_width.saturating_sub(fixed_width) / step_width) as usize;
+
+    // Always show at least one step column so the sequence stays editable
+    fitting_steps.max(1).min(num_steps)
 }
 
-pub fn calculate_table_layout(area: Rect, num_steps: usize) -> TableLayout {
-    // Create constraints matching the table in draw_matrix_interface
-    let mut constraints = vec![Constraint::Min(20)]; // Task name column
-    for _ in 0..num_steps {
-        constraints.push(Constraint::Length(8)); // Step columns (8 chars to fit "Sequence" header)
+fn table_constraints(visible_steps: usize) -> Vec<Constraint> {
+    let mut constraints = vec![
+        Constraint::Min(TASK_NAME_MIN_WIDTH),       // Task name column
+        Constraint::Length(FRESHNESS_COLUMN_WIDTH), // Freshness column
+    ];
+    for _ in 0..visible_steps {
+        constraints.push(Constraint::Length(STEP_COLUMN_WIDTH)); // Step columns
     }
-    constraints.push(Constraint::Min(20)); // Actions column
+    constraints.push(Constraint::Min(ACTIONS_MIN_WIDTH)); // Actions column
+    constraints
+}
+
+pub fn calculate_table_layout(area: Rect, num_steps: usize, first_step: usize) -> TableLayout {
+    let visible_steps = visible_step_count(area.width, num_steps);
+    let first_step = first_step.min(num_steps.saturating_sub(visible_steps));
 
-    // Calculate column positions using ratatui's layout system
-    let column_layout = Layout::horizontal(constraints).split(area);
+    // Mirror how ratatui's Table splits the area inside its border
+    let inner_area = Block::default().borders(Borders::ALL).inner(area);
+    let column_layout = Layout::horizontal(table_constraints(visible_steps))
+        .flex(Flex::Start)
+        .spacing(COLUMN_SPACING)
+        .split(inner_area);
 
     TableLayout {
         table_area: area,
         column_rects: column_layout.to_vec(),
+        first_step,
+        visible_steps,
     }
 }
 
@@ -52,6 +106,7 @@ pub fn draw_sequence_builder(app: &mut App, f: &mut Frame) {
         Layout::default()
             .direction(Direction::Vertical)
             .constraints([
+                Constraint::Length(1), // Tab bar
                 Constraint::Min(8),    // Matrix interface
                 Constraint::Min(5),    // Task output
                 Constraint::Length(5), // Controls (3 lines + borders)
@@ -61,18 +116,20 @@ pub fn draw_sequence_builder(app: &mut App, f: &mut Frame) {
         Layout::default()
             .direction(Direction::Vertical)
             .constraints([
+                Constraint::Length(1), // Tab bar
                 Constraint::Min(8),    // Matrix interface
                 Constraint::Length(5), // Controls (3 lines + borders)
             ])
             .split(f.area())
     };
 
-    draw_matrix_interface(app, f, chunks[0]);
+    draw_tab_bar(app, f, chunks[0]);
+    draw_matrix_interface(app, f, chunks[1]);
     if app.show_output_pane {
-        draw_task_output(app, f, chunks[1]);
-        draw_controls(f, chunks[2]);
+        draw_task_output(app, f, chunks[2]);
+        draw_controls(f, chunks[3]);
     } else {
-        draw_controls(f, chunks[1]);
+        draw_controls(f, chunks[2]);
     }
 
     // Position cursor appropriately based on state
@@ -106,11 +163,41 @@ pub fn draw_sequence_builder(app: &mut App, f: &mut Frame) {
     }
 }
 
+fn draw_tab_bar(app: &mut App, f: &mut Frame, area: Rect) {
+    app.tab_bar_area = Some(area);
+
+    let mut spans = Vec::new();
+    for (index, title) in app.tab_titles().iter().enumerate() {
+        if index > 0 {
+            spans.push(Span::styled(
+                TAB_SEPARATOR,
+                Style::default().fg(Color::DarkGray),
+            ));
+        }
+        let style = if index == app.active_tab {
+            Style::default()
+                .fg(Color::Black)
+                .bg(Color::Yellow)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Color::Gray)
+        };
+        spans.push(Span::styled(tab_label(index, title), style));
+    }
+
+    f.render_widget(Paragraph::new(Line::from(spans)), area);
+}
+
 fn draw_matrix_interface(app: &mut App, f: &mut Frame, area: Rect) {
-    let num_steps = 3; // Default to 3 steps for now
+    let num_steps = app.sequence_state.num_steps;
 
     // Calculate and store table layout for mouse click detection
-    app.table_layout = Some(calculate_table_layout(area, num_steps));
+    let table_layout = calculate_table_layout(area, num_steps, app.step_scroll_offset);
+    let first_step = table_layout.first_step;
+    let visible_steps = table_layout.visible_steps;
+    app.step_scroll_offset = first_step;
+    app.current_visible_steps = visible_steps;
+    app.table_layout = Some(table_layout);
 
     // Calculate available height for task rows (subtract header + borders)
     let available_height = area.height.saturating_sub(3); // Header + top/bottom borders
@@ -122,17 +209,20 @@ fn draw_matrix_interface(app: &mut App, f: &mut Frame, area: Rect) {
     // Get visible tasks without automatically adjusting scroll
     let (visible_tasks, _selected_in_visible) = app.get_visible_tasks(visible_height);
 
-    // Create headers: Task Name, Step 1, Step 2, Step 3, Actions
-    let mut header_cells =
-        vec![Cell::from("Task Name").style(Style::default().add_modifier(Modifier::BOLD))];
-
-    // Add "Sequence" header in the first step column
-    header_cells.push(Cell::from("Sequence").style(Style::default().add_modifier(Modifier::BOLD)));
+    // Create headers: Task Name, freshness, one column per visible step, Actions
+    let mut header_cells = vec![
+        Cell::from("Task Name").style(Style::default().add_modifier(Modifier::BOLD)),
+        Cell::from("State").style(Style::default().add_modifier(Modifier::BOLD)),
+    ];
 
-    // Add empty header for second step column
-    header_cells.push(Cell::from(""));
-    // Add empty header for third step column
-    header_cells.push(Cell::from(""));
+    for step in first_step..first_step + visible_steps {
+        header_cells.push(create_step_header_cell(
+            app,
+            step,
+            first_step,
+            visible_steps,
+        ));
+    }
 
     // Actions header - center it manually by adding equal padding on both sides
     // "Actions" is 7 chars, so we add spaces to center it
@@ -166,9 +256,10 @@ fn draw_matrix_interface(app: &mut App, f: &mut Frame, area: Rect) {
         // Task name cell with selection indicator, hover state, and rename input
         let task_name_cell = create_task_name_cell(app, actual_index, task, is_task_hovered);
         cells.push(task_name_cell);
+        cells.push(create_freshness_cell(app, &task.name));
 
         // Step button cells
-        for step in 0..num_steps {
+        for step in first_step..first_step + visible_steps {
             let step_button_cell = create_step_button_cell(app, actual_index, step);
             cells.push(step_button_cell);
         }
@@ -180,12 +271,8 @@ fn draw_matrix_interface(app: &mut App, f: &mut Frame, area: Rect) {
         rows.push(Row::new(cells).height(1));
     }
 
-    // Create the table with proper column constraints
-    let mut constraints = vec![Constraint::Min(20)]; // Task name column
-    for _ in 0..num_steps {
-        constraints.push(Constraint::Length(7)); // Step columns (8 chars to fit "Sequence" header)
-    }
-    constraints.push(Constraint::Min(20)); // Actions column
+    // Create the table with the same column constraints used for hit testing
+    let constraints = table_constraints(visible_steps);
 
     // Create table title with scroll indicators
     let mut title = APP_TITLE.to_string();
@@ -196,9 +283,30 @@ fn draw_matrix_interface(app: &mut App, f: &mut Frame, area: Rect) {
         title = format!("{APP_TITLE} ({start_task}-{end_task}/{total_tasks})");
     }
 
+    let mut block = Block::default().title(title).borders(Borders::ALL);
+    if let Some(name) = &app.current_sequence_name {
+        block = block.title_bottom(format!(" {SEQUENCE_NAME_PREFIX}{name} "));
+    }
+    if app.sequence_state.only_stale {
+        block = block.title_bottom(
+            Line::styled(" Only stale steps ", Style::default().fg(Color::Yellow)).right_aligned(),
+        );
+    }
+    if let Some(label) = app.watch_label(&WatchTarget::Sequence) {
+        block = block.title_bottom(
+            Line::styled(
+                format!(" Sequence {label} "),
+                Style::default().fg(Color::Cyan),
+            )
+            .right_aligned(),
+        );
+    }
+
     let table = Table::new(rows, constraints)
+        .column_spacing(COLUMN_SPACING)
+        .flex(Flex::Start)
         .header(header)
-        .block(Block::default().title(title).borders(Borders::ALL))
+        .block(block)
         .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED))
         .highlight_symbol("");
 
@@ -212,38 +320,60 @@ fn draw_task_output(app: &mut App, f: &mut Frame, area: Rect) {
     let mut output_text = Vec::new();
 
     // Show current step information if sequence is running
-    if app.sequence_state.is_running {
-        if let Some(current_step) = app.sequence_state.current_step {
-            let tasks_for_step = app.sequence_state.get_tasks_for_step(current_step);
-            if !tasks_for_step.is_empty() {
-                output_text.push(Line::from(vec![
-                    Span::styled(
-                        format!(
-                            "Step {}/{}: Running ",
-                            current_step + 1,
-                            app.sequence_state.num_steps
-                        ),
-                        Style::default()
-                            .fg(Color::Yellow)
-                            .add_modifier(Modifier::BOLD),
+    if app.sequence_state.is_running
+        && let Some(current_step) = app.sequence_state.current_step
+    {
+        let tasks_for_step = app.sequence_state.get_tasks_for_step(current_step);
+        if !tasks_for_step.is_empty() {
+            output_text.push(Line::from(vec![
+                Span::styled(
+                    format!(
+                        "Step {}/{}: Running ",
+                        current_step + 1,
+                        app.sequence_state.num_steps
                     ),
-                    Span::styled(tasks_for_step.join(", "), Style::default().fg(Color::Cyan)),
-                    Span::raw("..."),
-                ]));
-                output_text.push(Line::raw(""));
-            }
+                    Style::default()
+                        .fg(Color::Yellow)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(tasks_for_step.join(", "), Style::default().fg(Color::Cyan)),
+                Span::raw("..."),
+            ]));
+            output_text.push(Line::raw(""));
         }
     }
 
     // Calculate available height for actual task output
     // Account for borders (2) and any header lines we've added
     let header_lines = output_text.len() as u16;
-    let available_height = area.height.saturating_sub(2).saturating_sub(header_lines) as usize;
+    // Typing into the task takes the bottom line over the search prompt
+    let typed_line = task_input::typed_line(app)
+        .map(task_input::input_line)
+        .or_else(|| output_search::search_line(app));
+    let input_lines = u16::from(typed_line.is_some());
+    let available_height = area
+        .height
+        .saturating_sub(2)
+        .saturating_sub(header_lines + input_lines) as usize;
 
     // Store this for use in event handlers
     app.current_output_visible_height = available_height;
 
-    let total_output_lines = app.task_output.len();
+    // A task in PTY mode gets the pane below the header as its terminal
+    app.output_pane_size = PtySize {
+        rows: available_height as u16,
+        cols: area.width.saturating_sub(2),
+    };
+    if let Some(terminal) = &mut app.terminal {
+        terminal.resize(app.output_pane_size);
+        output_text.extend(terminal_screen::screen_lines(terminal.screen()));
+        let output = Paragraph::new(output_text)
+            .block(task_input::output_block(app, PTY_OUTPUT_TITLE.to_string()));
+        f.render_widget(output, area);
+        return;
+    }
+
+    let total_output_lines = app.output_view_len();
 
     // Ensure scroll offset is within bounds
     let max_scroll = if total_output_lines > available_height && available_height > 0 {
@@ -257,29 +387,27 @@ fn draw_task_output(app: &mut App, f: &mut Frame, area: Rect) {
     let start_index = app.output_scroll_offset;
     let end_index = (start_index + available_height).min(total_output_lines);
 
-    // Add visible lines with ANSI color support
-    for line in app
-        .task_output
-        .iter()
-        .skip(start_index)
-        .take(end_index.saturating_sub(start_index))
-    {
-        // Ensure line has ANSI reset to prevent color bleeding
-        let normalized_line = ensure_ansi_reset(line);
-
-        // Parse ANSI escape sequences and convert to ratatui Text
-        match ansi_to_tui::IntoText::into_text(&normalized_line) {
-            Ok(parsed_text) => {
-                // Extract lines from the parsed text and add them
-                for parsed_line in parsed_text.lines {
-                    output_text.push(parsed_line);
-                }
-            }
-            Err(_) => {
-                // Fallback to raw text if parsing fails
-                output_text.push(Line::raw(normalized_line));
+    if let Some(search) = &mut app.output_search {
+        search.update(&app.task_output);
+    }
+    let search = app.output_search.as_ref();
+    let current_match = search.and_then(|search| search.current_index(&app.task_output));
+
+    // Add visible lines with ANSI color support, matches are highlighted in the text only
+    // Only the visible lines are read, however long the scrollback is
+    let started_at = app
+        .output_timestamps
+        .then(|| app.task_output.started_at())
+        .flatten();
+    for (index, line) in app.output_view_lines(start_index..end_index) {
+        let text = output_line::text_line(&line);
+        let text = match search {
+            Some(search) => {
+                output_search::highlight_matches(text, search.regex(), current_match == Some(index))
             }
-        }
+            None => text,
+        };
+        output_text.push(output_line::with_origin(text, &line, started_at));
     }
 
     // Create title with scroll indicators
@@ -289,19 +417,46 @@ fn draw_task_output(app: &mut App, f: &mut Frame, area: Rect) {
         let visible_end = end_index;
         title = format!("{TASK_OUTPUT_TITLE} ({visible_start}-{visible_end}/{total_output_lines})");
     }
+    if app.output_stderr_only {
+        title.push_str(STDERR_ONLY_TITLE);
+    }
+    if let Some(search_title) = output_search::search_title(app) {
+        title.push_str(&search_title);
+    }
 
-    let output = Paragraph::new(output_text)
-        .block(Block::default().title(title).borders(Borders::ALL))
-        .wrap(ratatui::widgets::Wrap { trim: true });
+    let block = task_input::output_block(app, title);
+    let output_area = match typed_line {
+        Some(typed_line) => {
+            // The typed line stays at the bottom of the pane, below the output
+            let inner = block.inner(area);
+            let input_area = Rect {
+                y: inner.bottom().saturating_sub(1),
+                height: inner.height.min(1),
+                ..inner
+            };
+            f.render_widget(block, area);
+            f.render_widget(Paragraph::new(typed_line), input_area);
+            Rect {
+                height: inner.height.saturating_sub(1),
+                ..inner
+            }
+        }
+        None => {
+            let inner = block.inner(area);
+            f.render_widget(block, area);
+            inner
+        }
+    };
 
-    f.render_widget(output, area);
+    let output = Paragraph::new(output_text).wrap(ratatui::widgets::Wrap { trim: true });
+    f.render_widget(output, output_area);
 }
 
 fn draw_controls(f: &mut Frame, area: Rect) {
     let controls = Paragraph::new(vec![
-        Line::from("Navigation: ↑/↓: Select task | PgUp/PgDn/Mouse wheel: Scroll | q: Quit | g/G/F: Output scroll"),
-        Line::from("Task Actions: x: Run task | e: Edit | c: Rename | D: Delete | Tab: Info"),
-        Line::from("Sequence Actions: 1/2/3: Toggle step | Enter: Run sequence | a: Add as task | Ctrl+L: Clear"),
+        Line::from("Navigation: ↑/↓: Select task | ←/→: Select step | PgUp/PgDn/Mouse wheel: Scroll | q: Quit | g/G/F: Output scroll | /: Search | n/N: Next/Prev match | L: Error locations | E: Stderr only | C: Timestamps"),
+        Line::from("Task Actions: x: Run task | A: Step args | e: Edit | c: Rename | D: Delete | Tab: Info | O: Open in builder | T: Pipe/PTY | W: Watch | I: Type into task | Tabs: t/w: New/Close | [/]: Switch"),
+        Line::from("Sequence Actions: 1-9/Space: Toggle step | +/i/-: Add/Insert/Remove step | p: On failure | f: Only stale | Enter: Run sequence | a: Add as task | s/o: Save/Open | H: History | Ctrl+W: Watch | Ctrl+L: Clear"),
     ])
     .block(
         Block::default()
@@ -374,17 +529,60 @@ fn create_task_name_cell<'a>(
         };
 
         // Apply faded style if another task is being renamed
-        let style = if matches!(app.state, AppState::Renaming(_)) {
+        let is_renaming_other = matches!(app.state, AppState::Renaming(_));
+        let style = if is_renaming_other {
             Style::default().fg(Color::DarkGray)
         } else {
             task_name_style
         };
 
-        Cell::from(task_name_text).style(style)
+        let mut spans = vec![Span::styled(task_name_text, style)];
+        if app.runs_in_pty(&task.name) {
+            spans.push(Span::styled(
+                PTY_TASK_MARKER,
+                Style::default().fg(Color::DarkGray),
+            ));
+        }
+        if let Some(label) = app.watch_label(&WatchTarget::Task(task.name.clone())) {
+            spans.push(Span::styled(
+                format!(" [{label}]"),
+                Style::default().fg(Color::Cyan),
+            ));
+        }
+
+        // Badge and elapsed time of the task's latest run
+        if let Some(result) = app.last_task_results.get(&task.name) {
+            let badge_style = if is_renaming_other {
+                style
+            } else if result.success() {
+                Style::default().fg(Color::Green)
+            } else {
+                Style::default().fg(Color::Red)
+            };
+            spans.push(Span::styled(
+                format!(" {} {}", result.badge(), format_elapsed(result.elapsed())),
+                badge_style,
+            ));
+        }
+
+        Cell::from(Line::from(spans))
     }
 }
 
-fn create_action_buttons_cell(app: &App, task_index: usize) -> Cell {
+/// Whether the task's outputs are up to date with its sources, blank until checked
+fn create_freshness_cell<'a>(app: &App, task_name: &str) -> Cell<'a> {
+    let Some(freshness) = app.freshness_of(task_name) else {
+        return Cell::from("");
+    };
+    let color = match freshness {
+        Freshness::Fresh => Color::Green,
+        Freshness::Stale => Color::Yellow,
+        Freshness::Untracked => Color::DarkGray,
+    };
+    Cell::from(freshness.label()).style(Style::default().fg(color))
+}
+
+fn create_action_buttons_cell(app: &App, task_index: usize) -> Cell<'_> {
     // Check if any button in this row is being hovered
     let hover_button = if let Some(hover_state) = &app.button_hover_state {
         match hover_state.button_type {
@@ -494,9 +692,67 @@ fn create_action_buttons_cell(app: &App, task_index: usize) -> Cell {
     Cell::from(Line::from(spans))
 }
 
-fn create_step_button_cell(app: &App, task_index: usize, step_index: usize) -> Cell {
-    use crate::ui::constants::{STEP_1_TEXT, STEP_2_TEXT, STEP_3_TEXT, STEP_DISABLED_TEXT};
+fn create_step_header_cell<'a>(
+    app: &App,
+    step_index: usize,
+    first_step: usize,
+    visible_steps: usize,
+) -> Cell<'a> {
+    let step_number = step_index + 1;
+    let is_first_visible = step_index == first_step;
+    let is_last_visible = step_index + 1 == first_step + visible_steps;
+
+    // Non-default failure policies are shown next to the step number
+    let policy = app.sequence_state.step_policy(step_index);
+    let step_label = if policy == StepFailurePolicy::Stop {
+        step_number.to_string()
+    } else {
+        format!("{step_number}{}", policy.short_label())
+    };
+
+    // Point at step columns scrolled out of view on either side
+    let text = if is_first_visible && first_step > 0 {
+        format!("{STEP_SCROLL_LEFT_MARKER}{step_label}")
+    } else if is_last_visible && step_index + 1 < app.sequence_state.num_steps {
+        format!("{step_label}{STEP_SCROLL_RIGHT_MARKER}")
+    } else if policy == StepFailurePolicy::Stop {
+        format!("{STEP_HEADER_PREFIX}{step_number}")
+    } else {
+        format!("{step_number} {}", policy.short_label())
+    };
+
+    // Steps that ran in the last run carry a ✓/✗ badge
+    let outcome = app.sequence_state.step_outcome(step_index);
+    let text = match outcome {
+        Some(success) => format!("{text}{}", result_badge(success)),
+        None => text,
+    };
+
+    let is_failed = app
+        .sequence_state
+        .failed_steps
+        .get(step_index)
+        .copied()
+        .unwrap_or(false);
+
+    let style = if is_failed || outcome == Some(false) {
+        Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)
+    } else if step_index == app.selected_step {
+        Style::default()
+            .fg(Color::Yellow)
+            .add_modifier(Modifier::BOLD)
+    } else if outcome == Some(true) {
+        Style::default()
+            .fg(Color::Green)
+            .add_modifier(Modifier::BOLD)
+    } else {
+        Style::default().add_modifier(Modifier::BOLD)
+    };
+
+    Cell::from(text).style(style)
+}
 
+fn create_step_button_cell(app: &App, task_index: usize, step_index: usize) -> Cell<'_> {
     // Check if this step button is being hovered
     let is_hovered = if let Some(hover_state) = &app.button_hover_state {
         match hover_state.button_type {
@@ -517,15 +773,22 @@ fn create_step_button_cell(app: &App, task_index: usize, step_index: usize) -> C
         .is_task_enabled_for_step(task_name, step_index);
 
     // Determine the text to display
+    let has_args = !app
+        .sequence_state
+        .task_args(task_name, step_index)
+        .is_empty();
     let text = if is_enabled {
-        match step_index {
-            0 => STEP_1_TEXT,
-            1 => STEP_2_TEXT,
-            2 => STEP_3_TEXT,
-            _ => STEP_DISABLED_TEXT,
+        let badge = app
+            .sequence_state
+            .task_result(task_name, step_index)
+            .map(|result| result.badge());
+        match badge {
+            _ if has_args => step_args_button_text(step_index, badge),
+            Some(badge) => step_result_button_text(step_index, badge),
+            None => step_button_text(step_index),
         }
     } else {
-        STEP_DISABLED_TEXT
+        STEP_DISABLED_TEXT.to_string()
     };
 
     // Create spans with explicit positioning like action buttons
@@ -703,7 +966,7 @@ fn create_dialog_buttons_line(hover_button: Option<DialogButton>) -> Vec<Span<'s
     spans
 }
 
-fn create_sequence_controls_paragraph(app: &App) -> Paragraph {
+fn create_sequence_controls_paragraph(app: &App) -> Paragraph<'_> {
     // Check if any sequence button is being hovered
     let hover_button = if let Some(hover_state) = &app.button_hover_state {
         match hover_state.button_type {
@@ -815,57 +1078,57 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_ensure_ansi_reset_already_has_reset() {
-        let line = "STDOUT: Some output\x1b[0m";
-        let result = ensure_ansi_reset(line);
-        assert_eq!(result, line);
+    fn test_visible_step_count_fits_all_steps_on_wide_area() {
+        assert_eq!(visible_step_count(200, 3), 3);
+        assert_eq!(visible_step_count(200, 10), 10);
     }
 
     #[test]
-    fn test_ensure_ansi_reset_missing_reset() {
-        let line = "STDERR: Error message";
-        let result = ensure_ansi_reset(line);
-        assert_eq!(result, "STDERR: Error message\x1b[0m");
+    fn test_visible_step_count_limits_steps_on_narrow_area() {
+        // 2 borders + 20 name + 5 freshness + 33 actions + 2 spacing leaves 18 columns
+        // = 2 steps of 8
+        assert_eq!(visible_step_count(80, 10), 2);
+        assert_eq!(visible_step_count(88, 10), 3);
+        // Always keep one step visible even when nothing fits
+        assert_eq!(visible_step_count(20, 10), 1);
     }
 
     #[test]
-    fn test_ensure_ansi_reset_empty_string() {
-        let line = "";
-        let result = ensure_ansi_reset(line);
-        assert_eq!(result, "\x1b[0m");
-    }
+    fn test_calculate_table_layout_step_columns() {
+        let area = Rect::new(0, 0, 88, 20);
+        let layout = calculate_table_layout(area, 10, 4);
 
-    #[test]
-    fn test_ensure_ansi_reset_with_color_codes() {
-        let line = "\x1b[31mSTDOUT: Red text\x1b[32m";
-        let result = ensure_ansi_reset(line);
-        assert_eq!(result, "\x1b[31mSTDOUT: Red text\x1b[32m\x1b[0m");
-    }
+        assert_eq!(layout.visible_steps, 3);
+        assert_eq!(layout.first_step, 4);
+        assert_eq!(layout.column_rects.len(), 6);
 
-    #[test]
-    fn test_ensure_ansi_reset_partial_escape_sequence() {
-        let line = "Normal text\x1b[31";
-        let result = ensure_ansi_reset(line);
-        assert_eq!(result, "Normal text\x1b[31\x1b[0m");
-    }
+        let steps: Vec<usize> = layout.step_columns().map(|(step, _)| step).collect();
+        assert_eq!(steps, vec![4, 5, 6]);
 
-    #[test]
-    fn test_ensure_ansi_reset_multiple_resets_in_middle() {
-        let line = "Text\x1b[0m more text";
-        let result = ensure_ansi_reset(line);
-        assert_eq!(result, "Text\x1b[0m more text\x1b[0m");
+        for (_, rect) in layout.step_columns() {
+            assert_eq!(rect.width, STEP_COLUMN_WIDTH);
+        }
     }
 
     #[test]
-    fn test_ensure_ansi_reset_stdout_stderr_prefixes() {
-        // Test the specific use case that was causing bleeding
-        let stdout_line = "STDOUT: \x1b[32mSuccess message";
-        let stderr_line = "STDERR: \x1b[31mError message";
+    fn test_calculate_table_layout_clamps_first_step() {
+        let area = Rect::new(0, 0, 88, 20);
+        let layout = calculate_table_layout(area, 10, 9);
 
-        let stdout_result = ensure_ansi_reset(stdout_line);
-        let stderr_result = ensure_ansi_reset(stderr_line);
+        // Only 3 steps fit, so the last page starts at step 7
+        assert_eq!(layout.first_step, 7);
+    }
 
-        assert_eq!(stdout_result, "STDOUT: \x1b[32mSuccess message\x1b[0m");
-        assert_eq!(stderr_result, "STDERR: \x1b[31mError message\x1b[0m");
+    #[test]
+    fn test_step_at_column() {
+        let area = Rect::new(0, 0, 80, 20);
+        let layout = calculate_table_layout(area, 5, 1);
+        let (_, second_rect) = layout.step_columns().nth(1).unwrap();
+
+        assert_eq!(
+            layout.step_at_column(second_rect.x).map(|(step, _)| step),
+            Some(2)
+        );
+        assert_eq!(layout.step_at_column(0), None);
     }
 }
@@ -0,0 +1,135 @@
+use ratatui::{prelude::*, widgets::*};
+
+use crate::app::App;
+use crate::models::SavedSequence;
+use crate::ui::constants::*;
+
+/// One-line summary of a saved sequence's steps, e.g. `build + lint → test`
+fn summarize_steps(sequence: &SavedSequence) -> String {
+    let steps: Vec<String> = sequence
+        .steps
+        .iter()
+        .filter(|step| !step.tasks.is_empty())
+        .map(|step| step.tasks.join(" + "))
+        .collect();
+
+    if steps.is_empty() {
+        "(no tasks)".to_string()
+    } else {
+        steps.join(" → ")
+    }
+}
+
+pub fn draw_sequence_list(app: &App, f: &mut Frame) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(3)])
+        .split(f.area());
+
+    let title = format!(
+        "{SAVED_SEQUENCES_TITLE} ({})",
+        app.sequence_store.path().display()
+    );
+    let block = Block::default().title(title).borders(Borders::ALL);
+
+    if app.sequence_store.sequences.is_empty() {
+        f.render_widget(
+            Paragraph::new(NO_SAVED_SEQUENCES_TEXT)
+                .block(block)
+                .alignment(Alignment::Center),
+            chunks[0],
+        );
+    } else {
+        let items: Vec<ListItem> = app
+            .sequence_store
+            .sequences
+            .iter()
+            .map(|sequence| {
+                let is_current = app.current_sequence_name.as_deref() == Some(&sequence.name);
+                let marker = if is_current { "* " } else { "  " };
+                ListItem::new(Line::from(vec![
+                    Span::raw(marker),
+                    Span::styled(
+                        sequence.name.clone(),
+                        Style::default()
+                            .fg(Color::Yellow)
+                            .add_modifier(Modifier::BOLD),
+                    ),
+                    Span::styled(
+                        format!("  [{} steps]  ", sequence.steps.len()),
+                        Style::default().fg(Color::Gray),
+                    ),
+                    Span::raw(summarize_steps(sequence)),
+                ]))
+            })
+            .collect();
+
+        let list = List::new(items)
+            .block(block)
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        let mut list_state = ListState::default().with_selected(Some(app.selected_saved_sequence));
+        f.render_stateful_widget(list, chunks[0], &mut list_state);
+    }
+
+    // Footer doubles as the delete confirmation
+    let controls = match &app.pending_delete_sequence {
+        Some(name) => Line::from(vec![
+            Span::raw("Delete sequence "),
+            Span::styled(
+                format!("'{name}'"),
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("? "),
+            Span::styled(
+                "y",
+                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+            ),
+            Span::raw(": Delete | any other key: Cancel"),
+        ]),
+        None => {
+            Line::from("↑/↓: Select | Enter: Load | R: Rename | D: Delete | Esc/b: Back | q: Quit")
+        }
+    };
+
+    f.render_widget(
+        Paragraph::new(controls)
+            .block(Block::default().borders(Borders::ALL).title(CONTROLS_TITLE))
+            .style(Style::default().fg(Color::Gray)),
+        chunks[1],
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::saved_sequence::SavedStep;
+    use crate::models::StepFailurePolicy;
+
+    fn step(tasks: &[&str]) -> SavedStep {
+        SavedStep {
+            tasks: tasks.iter().map(|task| task.to_string()).collect(),
+            on_failure: StepFailurePolicy::Stop,
+            args: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_summarize_steps() {
+        let sequence = SavedSequence {
+            name: "ci".to_string(),
+            steps: vec![step(&["build", "lint"]), step(&[]), step(&["test"])],
+        };
+        assert_eq!(summarize_steps(&sequence), "build + lint → test");
+    }
+
+    #[test]
+    fn test_summarize_steps_without_tasks() {
+        let sequence = SavedSequence {
+            name: "empty".to_string(),
+            steps: vec![step(&[])],
+        };
+        assert_eq!(summarize_steps(&sequence), "(no tasks)");
+    }
+}
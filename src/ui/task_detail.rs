@@ -25,8 +25,12 @@ pub fn draw_task_detail(app: &App, f: &mut Frame, task_name: &str) {
         chunks[0],
     );
 
-    // Task details
-    if let Some(info) = &app.task_info {
+    // Task details, straight from the cache when they were fetched ahead
+    let info = app
+        .task_info
+        .clone()
+        .or_else(|| app.client.cached_task_info(task_name));
+    if let Some(info) = &info {
         let mut details = vec![
             format!("Name: {}", info.name),
             format!("Source: {}", info.source),
@@ -0,0 +1,72 @@
+use ratatui::{prelude::*, widgets::*};
+use tui_input::Input;
+
+use crate::app::App;
+
+/// Block around the task output, highlighted while keys go to the task
+pub fn output_block(app: &App, title: String) -> Block<'static> {
+    let block = match app.input_focus_hint() {
+        Some(hint) => Block::default()
+            .title(format!("{title}{hint}"))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan)),
+        None => Block::default().title(title).borders(Borders::ALL),
+    };
+    // CPU and memory use of the running tasks, on the right of the title bar
+    match app.running_usage() {
+        Some(usage) => block.title(Line::from(format!(" {} ", usage.label())).right_aligned()),
+        None => block,
+    }
+}
+
+/// Line being typed for a task on pipes, shown below its output while it has focus
+pub fn typed_line(app: &App) -> Option<&Input> {
+    if app.terminal.is_some() {
+        return None;
+    }
+    app.task_input
+        .as_ref()
+        .filter(|input| input.focused)
+        .map(|input| &input.line)
+}
+
+/// The typed line with its cursor
+pub fn input_line(input: &Input) -> Line<'static> {
+    let value = input.value();
+    let cursor = input.visual_cursor();
+    let before: String = value.chars().take(cursor).collect();
+    let at_cursor: String = value
+        .chars()
+        .nth(cursor)
+        .map_or(" ".to_string(), String::from);
+    let after: String = value.chars().skip(cursor + 1).collect();
+
+    let style = Style::default().fg(Color::Cyan);
+    Line::from(vec![
+        Span::styled("> ", style.add_modifier(Modifier::BOLD)),
+        Span::styled(before, style),
+        Span::styled(at_cursor, style.add_modifier(Modifier::REVERSED)),
+        Span::styled(after, style),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tui_input::InputRequest;
+
+    #[test]
+    fn test_input_line_shows_cursor() {
+        let mut input = Input::new("yes".to_string());
+        let text = |line: &Line| -> Vec<String> {
+            line.spans
+                .iter()
+                .map(|span| span.content.to_string())
+                .collect()
+        };
+        assert_eq!(text(&input_line(&input)), vec!["> ", "yes", " ", ""]);
+
+        input.handle(InputRequest::GoToPrevChar);
+        assert_eq!(text(&input_line(&input)), vec!["> ", "ye", "s", ""]);
+    }
+}
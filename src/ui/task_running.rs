@@ -1,8 +1,14 @@
 use ratatui::{prelude::*, widgets::*};
 
 use crate::app::App;
+use crate::models::PtySize;
+use crate::ui::constants::PTY_OUTPUT_TITLE;
+use crate::ui::{output_line, task_input, terminal_screen};
 
-pub fn draw_task_running(app: &App, f: &mut Frame, task_name: &str) {
+/// Lines at the end of the output shown while a task runs
+const TAIL_LINES: usize = 10;
+
+pub fn draw_task_running(app: &mut App, f: &mut Frame, task_name: &str) {
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
@@ -25,31 +31,53 @@ pub fn draw_task_running(app: &App, f: &mut Frame, task_name: &str) {
         chunks[0],
     );
 
-    // Task output
-    let output_text: String = app
+    // Task output, or the terminal of a task in PTY mode
+    app.output_pane_size = PtySize {
+        rows: chunks[1].height.saturating_sub(2),
+        cols: chunks[1].width.saturating_sub(2),
+    };
+    if let Some(terminal) = &mut app.terminal {
+        terminal.resize(app.output_pane_size);
+        f.render_widget(
+            Paragraph::new(terminal_screen::screen_lines(terminal.screen()))
+                .block(task_input::output_block(app, PTY_OUTPUT_TITLE.to_string())),
+            chunks[1],
+        );
+        draw_footer(f, chunks[2]);
+        return;
+    }
+
+    // The view follows the end of the output, only its last lines are read
+    let total_lines = app.task_output.len();
+    let mut output_text: Text = app
         .task_output
+        .lines(total_lines.saturating_sub(TAIL_LINES)..total_lines)
         .iter()
-        .cloned()
-        .collect::<Vec<_>>()
-        .join("\n");
+        .map(|line| output_line::output_line(line, None))
+        .collect();
+    if let Some(input) = task_input::typed_line(app) {
+        output_text.lines.push(task_input::input_line(input));
+    }
 
     f.render_widget(
         Paragraph::new(output_text)
-            .block(Block::default().borders(Borders::ALL).title("Output"))
-            .wrap(Wrap { trim: true })
-            .scroll((app.task_output.len().saturating_sub(10) as u16, 0)),
+            .block(task_input::output_block(app, "Output".to_string()))
+            .wrap(Wrap { trim: true }),
         chunks[1],
     );
 
-    // Footer
+    draw_footer(f, chunks[2]);
+}
+
+fn draw_footer(f: &mut Frame, area: Rect) {
     let footer = Block::default().borders(Borders::ALL).title("Controls");
 
-    let controls = "Esc/b: Back to List | q: Quit";
+    let controls = "I: Type into task | Esc/b: Back to List | q: Quit";
 
     f.render_widget(
         Paragraph::new(controls)
             .block(footer)
             .alignment(Alignment::Center),
-        chunks[2],
+        area,
     );
 }
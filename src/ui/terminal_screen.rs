@@ -0,0 +1,110 @@
+use ratatui::prelude::*;
+
+/// Convert a vt100 color, where the default color leaves the pane's own style in place
+fn to_color(color: vt100::Color) -> Option<Color> {
+    match color {
+        vt100::Color::Default => None,
+        vt100::Color::Idx(index) => Some(Color::Indexed(index)),
+        vt100::Color::Rgb(r, g, b) => Some(Color::Rgb(r, g, b)),
+    }
+}
+
+fn cell_style(cell: &vt100::Cell) -> Style {
+    let mut style = Style::default();
+    if let Some(fg) = to_color(cell.fgcolor()) {
+        style = style.fg(fg);
+    }
+    if let Some(bg) = to_color(cell.bgcolor()) {
+        style = style.bg(bg);
+    }
+    let modifiers = [
+        (cell.bold(), Modifier::BOLD),
+        (cell.italic(), Modifier::ITALIC),
+        (cell.underline(), Modifier::UNDERLINED),
+        (cell.inverse(), Modifier::REVERSED),
+    ];
+    for (enabled, modifier) in modifiers {
+        if enabled {
+            style = style.add_modifier(modifier);
+        }
+    }
+    style
+}
+
+/// Render the emulated screen as lines, one span per run of equally styled cells, with
+/// the cursor shown as a reversed cell unless the task hid it
+pub fn screen_lines(screen: &vt100::Screen) -> Vec<Line<'static>> {
+    let (rows, cols) = screen.size();
+    let cursor = (!screen.hide_cursor()).then(|| screen.cursor_position());
+
+    (0..rows)
+        .map(|row| {
+            let mut spans: Vec<Span<'static>> = Vec::new();
+            for col in 0..cols {
+                let Some(cell) = screen.cell(row, col) else {
+                    continue;
+                };
+                // The second half of a wide character is covered by the first
+                if cell.is_wide_continuation() {
+                    continue;
+                }
+                let mut style = cell_style(cell);
+                if cursor == Some((row, col)) {
+                    style = style.add_modifier(Modifier::REVERSED);
+                }
+                let contents = if cell.has_contents() {
+                    cell.contents()
+                } else {
+                    " ".to_string()
+                };
+
+                match spans.last_mut() {
+                    Some(span) if span.style == style => span.content.to_mut().push_str(&contents),
+                    _ => spans.push(Span::styled(contents, style)),
+                }
+            }
+            Line::from(spans)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn screen_of(bytes: &[u8]) -> vt100::Parser {
+        let mut parser = vt100::Parser::new(2, 10, 0);
+        parser.process(bytes);
+        parser
+    }
+
+    #[test]
+    fn test_screen_lines_group_styled_cells() {
+        let parser = screen_of(b"\x1b[?25l\x1b[1;31mred\x1b[0m ok");
+        let lines = screen_lines(parser.screen());
+
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].spans[0].content, "red");
+        assert_eq!(
+            lines[0].spans[0].style,
+            Style::default()
+                .fg(Color::Indexed(1))
+                .add_modifier(Modifier::BOLD)
+        );
+        assert_eq!(lines[0].spans[1].content, " ok    ");
+        assert_eq!(lines[1].spans[0].content, " ".repeat(10));
+    }
+
+    #[test]
+    fn test_screen_lines_show_cursor() {
+        let parser = screen_of(b"ab\r\nc");
+        let lines = screen_lines(parser.screen());
+
+        let cursor = &lines[1].spans[1];
+        assert_eq!(cursor.content, " ");
+        assert_eq!(
+            cursor.style,
+            Style::default().add_modifier(Modifier::REVERSED)
+        );
+    }
+}